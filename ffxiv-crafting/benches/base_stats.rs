@@ -0,0 +1,57 @@
+//! Compares the integer base stat formulas against the float ones they replaced, as they're called
+//! for every quality and progress action a solver considers.
+//!
+//! Run with `cargo bench --bench base_stats`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ffxiv_crafting::{
+    conditions::QARegularConditions, quality_map::HQMap, CharacterStats, CraftingSimulator,
+    CraftingState, RecipeLevelRanges, RecipeStats,
+};
+
+/// The float formula for base quality, before it was moved to integers.
+fn float_quality(control: u16, rlvl_control: u16, level_mod: u16) -> f64 {
+    let control = control as f64;
+    let quality = control * 35. / 100. + 35.;
+    let quality = quality * (control + 10_000.) / (rlvl_control as f64 + 10_000.);
+    quality * level_mod as f64 / 100.
+}
+
+fn base_stats(c: &mut Criterion) {
+    let recipe_level = RecipeLevelRanges::ShbMax(4);
+    let problem_def = CraftingSimulator::<_, HQMap>::new(
+        CharacterStats {
+            craftsmanship: 2_763,
+            control: 2_780,
+            max_cp: 545,
+            char_level: 80,
+        },
+        RecipeStats::new(recipe_level, 60, 5_000, 3_500),
+        QARegularConditions::Normal,
+    );
+    let mut state = CraftingState::new_simulation(&problem_def);
+    state.condition = QARegularConditions::Good;
+
+    let mut group = c.benchmark_group("conditioned_base_quality");
+    group.bench_function("float", |b| {
+        b.iter(|| {
+            let quality = float_quality(
+                black_box(2_780),
+                recipe_level.to_recipe_level_control(),
+                recipe_level.to_quality_level_mod(black_box(420)),
+            );
+            (quality * black_box(1.5)).floor() as u32
+        })
+    });
+    group.bench_function("integer", |b| {
+        b.iter(|| black_box(&state).conditioned_base_quality())
+    });
+    group.finish();
+
+    c.bench_function("floored_base_progress", |b| {
+        b.iter(|| black_box(&state).floored_base_progress())
+    });
+}
+
+criterion_group!(benches, base_stats);
+criterion_main!(benches);
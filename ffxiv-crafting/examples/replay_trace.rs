@@ -0,0 +1,63 @@
+//! Records a craft with random rolls as a trace, saves it as JSON, and then reconstructs every
+//! step of the craft from the saved trace alone.
+//!
+//! Run with `cargo run --example replay_trace --features serde`.
+
+use ffxiv_crafting::{
+    actions::collection::FfxivCraftingActions,
+    conditions::QARegularConditions,
+    quality_map::{HQChance, HQMap},
+    rand::{rngs::StdRng, SeedableRng},
+    trace::Trace,
+    CharacterStats, CraftingSimulator, CraftingState, RecipeLevelRanges, RecipeStats,
+};
+
+use FfxivCraftingActions::*;
+
+fn main() {
+    let problem = CraftingSimulator::<_, HQMap>::new(
+        CharacterStats {
+            craftsmanship: 2000,
+            control: 2000,
+            max_cp: 300,
+            char_level: 80,
+        },
+        RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 60, 6_000, 1_500),
+        QARegularConditions::Normal,
+    );
+    let rotation = [
+        Innovation,
+        HastyTouch,
+        BasicTouch,
+        StandardTouch,
+        RapidSynthesis,
+        BasicSynthesis,
+        BasicSynthesis,
+    ];
+
+    let start = CraftingState::new_simulation(&problem);
+
+    let trace = start.run_traced(
+        rotation,
+        &mut StdRng::seed_from_u64(14),
+        &mut StdRng::seed_from_u64(29),
+    );
+    let json = serde_json::to_string_pretty(&trace).unwrap();
+    println!("{}", json);
+    println!();
+
+    // Everything below only needs the JSON and the problem the craft was for
+    let trace: Trace<FfxivCraftingActions, QARegularConditions, HQChance> =
+        serde_json::from_str(&json).unwrap();
+
+    let states = trace.replay(start);
+    for (i, (step, before)) in trace.iter().zip(&states).enumerate() {
+        println!(
+            "{} {:?} -> {:?}",
+            before.summary(i),
+            step.action,
+            step.outcome
+        );
+    }
+    println!("{}", states[states.len() - 1].summary(trace.len()));
+}
@@ -0,0 +1,82 @@
+//! Solves a small craft exactly and prints the resulting rotation as an in-game macro.
+//!
+//! Run with `cargo run --example solve_craft --features mdp --release`, the solver is slow without
+//! optimizations.
+
+use ffxiv_crafting::{
+    actions::{collection::FfxivCraftingActions, TimePassing},
+    conditions::QARegularConditions,
+    mdp::{linear_rotation, CraftingMdp, MaxDelta},
+    quality_map::HQMap,
+    CharacterStats, CraftingSimulator, RecipeLevelRanges, RecipeStats,
+};
+use structured_rewards::simple_solvers::simple_solver;
+
+type Mdp<'a> = CraftingMdp<'a, FfxivCraftingActions, QARegularConditions, HQMap>;
+
+/// A deliberately tiny set of actions, the state space grows very quickly with each one added.
+const ACTIONS: [FfxivCraftingActions; 5] = [
+    FfxivCraftingActions::BasicSynthesis,
+    FfxivCraftingActions::BasicTouch,
+    FfxivCraftingActions::HastyTouch,
+    FfxivCraftingActions::StandardTouch,
+    FfxivCraftingActions::MastersMend,
+];
+
+fn main() {
+    let problem = CraftingSimulator::<_, HQMap>::new(
+        CharacterStats {
+            craftsmanship: 2000,
+            control: 2000,
+            max_cp: 200,
+            char_level: 80,
+        },
+        RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 40, 6_000, 1_000),
+        QARegularConditions::Normal,
+    );
+
+    let start = Mdp::new(&problem, &ACTIONS);
+    let policy = simple_solver(start, MaxDelta(1e-6));
+
+    let expected = policy.value(&start).map_or(0., |q| q.0);
+    println!("Expected HQ chance: {:.2}%", expected);
+    println!();
+
+    let rotation = linear_rotation(start, &policy);
+    for step in &rotation {
+        // Time passing doesn't actually depend on the state
+        let wait = if step.action.time_passed(&start.state) {
+            3
+        } else {
+            2
+        };
+        println!("/ac \"{}\" <wait.{}>", display_name(step.action), wait);
+    }
+
+    // Kept out of the macro itself so it can be pasted as-is
+    for (i, step) in rotation
+        .iter()
+        .enumerate()
+        .filter(|(_, step)| step.branches)
+    {
+        eprintln!(
+            "Note: the solver may choose differently after step {} ({}) depending on the condition",
+            i + 1,
+            display_name(step.action)
+        );
+    }
+}
+
+/// Splits the action's name into words as they're shown in-game (e.g. `BasicSynthesis` becomes
+/// "Basic Synthesis").
+fn display_name(action: FfxivCraftingActions) -> String {
+    let mut name = String::new();
+    for c in format!("{:?}", action).chars() {
+        if c.is_uppercase() && !name.is_empty() {
+            name.push(' ');
+        }
+        name.push(c);
+    }
+
+    name
+}
@@ -7,6 +7,7 @@ use syn::{
 };
 
 const EFFICIENCY: &str = "efficiency";
+const EFFICIENCY_AT: &str = "efficiency_at";
 const COST: &str = "cost";
 const BONUS: &str = "bonus";
 const LEVEL: &str = "level";
@@ -21,27 +22,74 @@ pub fn progress_action(input: TokenStream) -> TokenStream {
 
     const TAG: &str = "ffxiv_progress";
 
-    let efficiency = [(
-        EFFICIENCY,
-        Box::new(attr_literal(EFFICIENCY)) as Box<FfxivAttrMatcher>,
-    )]
+    let efficiency = [
+        (
+            EFFICIENCY,
+            Box::new(attr_literal(EFFICIENCY)) as Box<FfxivAttrMatcher>,
+        ),
+        (
+            EFFICIENCY_AT,
+            Box::new(attr_literal(EFFICIENCY_AT)) as Box<FfxivAttrMatcher>,
+        ),
+    ]
     .into_iter()
     .collect();
 
-    let val = find_attributes(&ast, TAG, efficiency);
+    let vals = find_attributes(&ast, TAG, efficiency);
 
-    let val = val.get(EFFICIENCY).into_iter().map(|v| v.to_lit_int());
+    let val = vals.get(EFFICIENCY).into_iter().map(|v| v.to_lit_int());
+
+    let base_efficiency = vals
+        .get(EFFICIENCY_AT)
+        .map(|v| v.to_lit_str().value())
+        .map(|breakpoints| {
+            let mut breakpoints = parse_breakpoints(&breakpoints);
+            // Highest level first, so the first arm that matches is the right one
+            breakpoints.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+            let (levels, efficiencies): (Vec<_>, Vec<_>) = breakpoints.into_iter().unzip();
+
+            quote!(
+                fn base_efficiency<C, M>(&self, state: &::ffxiv_crafting::CraftingState<C, M>) -> u16
+                where
+                    C: ::ffxiv_crafting::conditions::Condition,
+                    M: ::ffxiv_crafting::quality_map::QualityMap,
+                {
+                    match state.problem_def.character.char_level {
+                        #(#levels.. => #efficiencies,)*
+                        _ => <Self as ::ffxiv_crafting::actions::progress::ProgressAction>::EFFICIENCY,
+                    }
+                }
+            )
+        });
 
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::progress::ProgressAction for #ident #type_generic #(#where_clause)* {
+        impl #impl_generic ::ffxiv_crafting::actions::progress::ProgressAction for #ident #type_generic #(#where_clause)* {
             #(const EFFICIENCY: u16 = #val;)*
+
+            #base_efficiency
         }
     )
     .into()
 }
 
+/// Parses `efficiency_at`'s `"<level>:<efficiency>,..."` list into `(level, efficiency)` pairs.
+fn parse_breakpoints(breakpoints: &str) -> Vec<(u8, u16)> {
+    breakpoints
+        .split(',')
+        .map(|breakpoint| {
+            breakpoint
+                .split_once(':')
+                .and_then(|(level, efficiency)| {
+                    Some((level.trim().parse().ok()?, efficiency.trim().parse().ok()?))
+                })
+                .expect("Need to match efficiency_at with a list like \"31:120,82:180\"")
+        })
+        .collect()
+}
+
 pub fn buff_action(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let ident = &ast.ident;
@@ -76,7 +124,7 @@ pub fn buff_action(input: TokenStream) -> TokenStream {
         let parts: ExprField = match v {
             FfxivAttr::Kind(lit) => {
                 let val = lit.value();
-                syn::parse_str(&*val).expect(
+                syn::parse_str(&val).expect(
                     "Invalid format for consume, \
                             it must be a string describing struct field access",
                 )
@@ -86,7 +134,9 @@ pub fn buff_action(input: TokenStream) -> TokenStream {
             ),
         };
         quote!(
-            so_far.#parts.deactivate();
+            if so_far.#parts.is_active() {
+                so_far.#parts.deactivate_in_place();
+            }
         )
     });
 
@@ -101,7 +151,7 @@ pub fn buff_action(input: TokenStream) -> TokenStream {
             };
             quote!(
                 so_far.quality.inner_quiet += #magnitude;
-                
+
                 if so_far.quality.great_strides.is_active(){
                     so_far.quality.great_strides.deactivate_in_place();
                 }
@@ -128,7 +178,7 @@ pub fn buff_action(input: TokenStream) -> TokenStream {
         let parts: ExprField = match v {
             FfxivAttr::Kind(lit) => {
                 let val = lit.value();
-                syn::parse_str(&*val).expect(
+                syn::parse_str(&val).expect(
                     "Invalid format for activate, \
                         it must be a string describing struct field access",
                 )
@@ -139,29 +189,29 @@ pub fn buff_action(input: TokenStream) -> TokenStream {
         };
 
         quote!(
-            so_far.#parts.activate_in_place(state.condition.to_status_duration_modifier() as u8)
+            so_far.#parts.activate_in_place(state.condition.to_status_duration_modifier() as u8);
         )
     }));
 
     let buff_impl = quote!(
-        fn buff<C, M>(&self, state: &crate::CraftingState<C, M>, so_far: &mut crate::BuffState)
+        fn buff<C, M>(&self, state: &::ffxiv_crafting::CraftingState<C, M>, so_far: &mut ::ffxiv_crafting::buffs::BuffState)
         where
-            C: crate::conditions::Condition,
-            M: crate::quality_map::QualityMap,
+            C: ::ffxiv_crafting::conditions::Condition,
+            M: ::ffxiv_crafting::quality_map::QualityMap,
         {
-            use crate::buffs::{Buff, DurationalBuff, ConsumableBuff};
+            use ::ffxiv_crafting::buffs::{Buff, DurationalBuff, ConsumableBuff};
 
             #(#clause)*
         }
     );
 
     let debuff_impl = quote!(
-        fn deactivate_buff<C,M>(&self, state: &crate::CraftingState<C, M>, so_far: &mut crate::BuffState)
+        fn deactivate_buff<C,M>(&self, state: &::ffxiv_crafting::CraftingState<C, M>, so_far: &mut ::ffxiv_crafting::buffs::BuffState)
         where
-            C: crate::conditions::Condition,
-            M: crate::quality_map::QualityMap
+            C: ::ffxiv_crafting::conditions::Condition,
+            M: ::ffxiv_crafting::quality_map::QualityMap
         {
-            use crate::buffs::{Buff, DurationalBuff, ConsumableBuff};
+            use ::ffxiv_crafting::buffs::{Buff, DurationalBuff, ConsumableBuff};
             #(#deactivate_clause)*
         }
     );
@@ -169,7 +219,7 @@ pub fn buff_action(input: TokenStream) -> TokenStream {
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::buffs::BuffAction for #ident #type_generic #(#where_clause)* {
+        impl #impl_generic ::ffxiv_crafting::actions::buffs::BuffAction for #ident #type_generic #(#where_clause)* {
             #buff_impl
             #debuff_impl
         }
@@ -199,7 +249,7 @@ pub fn quality_action(input: TokenStream) -> TokenStream {
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::quality::QualityAction for #ident #type_generic #(#where_clause)* {
+        impl #impl_generic ::ffxiv_crafting::actions::quality::QualityAction for #ident #type_generic #(#where_clause)* {
             #(const EFFICIENCY: u16 = #val;)*
         }
     )
@@ -256,8 +306,8 @@ pub fn cp_cost(input: TokenStream) -> TokenStream {
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #type_generic crate::actions::CpCost for #ident #impl_generic #(#where_clause)* {
-            const CP_COST: i16 = -#val;
+        impl #type_generic ::ffxiv_crafting::actions::CpCost for #ident #impl_generic #(#where_clause)* {
+            const CP_COST: i16 = #val;
         }
     )
     .into()
@@ -313,8 +363,8 @@ pub fn durability(input: TokenStream) -> TokenStream {
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::DurabilityFactor for #ident #type_generic #(#where_clause)* {
-            #(const DURABILITY_USAGE: i8 = -#val;)*
+        impl #impl_generic ::ffxiv_crafting::actions::DurabilityFactor for #ident #type_generic #(#where_clause)* {
+            #(const DURABILITY_USAGE: i8 = #val;)*
         }
     )
     .into()
@@ -348,12 +398,12 @@ pub fn can_execute(input: TokenStream) -> TokenStream {
             _ => panic!("Unsupported condition for \"can execute\"."),
         };
         quote!(
-            fn can_execute<C, M>(&self, state: &crate::CraftingState<C, M>) -> bool
+            fn can_execute<C, M>(&self, state: &::ffxiv_crafting::CraftingState<C, M>) -> bool
             where
-                C: crate::conditions::Condition,
-                M: crate::quality_map::QualityMap,
+                C: ::ffxiv_crafting::conditions::Condition,
+                M: ::ffxiv_crafting::quality_map::QualityMap,
             {
-                use crate::buffs::Buff;
+                use ::ffxiv_crafting::buffs::Buff;
                 #condition
             }
         )
@@ -362,7 +412,7 @@ pub fn can_execute(input: TokenStream) -> TokenStream {
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::CanExecute for #ident #type_generic #(#where_clause)* {
+        impl #impl_generic ::ffxiv_crafting::actions::CanExecute for #ident #type_generic #(#where_clause)* {
             #(#can_execute_impl)*
         }
     )
@@ -392,7 +442,7 @@ pub fn action_level(input: TokenStream) -> TokenStream {
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::ActionLevel for #ident #type_generic #(#where_clause)* {
+        impl #impl_generic ::ffxiv_crafting::actions::ActionLevel for #ident #type_generic #(#where_clause)* {
             const LEVEL: u16 = #val;
         }
     )
@@ -430,14 +480,17 @@ pub fn random_action(input: TokenStream) -> TokenStream {
         .filter(|v| &*v.value() == "combo_observe")
         .map(|_| {
             quote!(
-                fn fail_rate<C: Condition, M: QualityMap>(
-                    &self,
-                    state: &CraftingState<C, M>,
-                ) -> u8 {
-                    if !state.buffs.combo.observation.is_active() {
-                        Self::FAIL_RATE
-                    } else {
+                fn fail_rate<C, M>(&self, state: &::ffxiv_crafting::CraftingState<C, M>) -> u8
+                where
+                    C: ::ffxiv_crafting::conditions::Condition,
+                    M: ::ffxiv_crafting::quality_map::QualityMap,
+                {
+                    if ::ffxiv_crafting::buffs::combo::ObserveCombo::CHAIN
+                        .can_continue(state.buffs.combo.observation)
+                    {
                         0
+                    } else {
+                        Self::FAIL_RATE
                     }
                 }
             )
@@ -446,9 +499,9 @@ pub fn random_action(input: TokenStream) -> TokenStream {
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::RandomAction for #ident #type_generic #(#where_clause)* {
+        impl #impl_generic ::ffxiv_crafting::actions::RandomAction for #ident #type_generic #(#where_clause)* {
             #(const FAIL_RATE: u8 = #chance;)*
-            type FailAction = crate::actions::failure::NullFailure<Self>;
+            type FailAction = ::ffxiv_crafting::actions::failure::NullFailure<Self>;
 
             #(#fail_rate_class)*
 
@@ -457,7 +510,7 @@ pub fn random_action(input: TokenStream) -> TokenStream {
                     unreachable!("Action cannot fail")
                 }
 
-                crate::actions::failure::NullFailure(*self)
+                ::ffxiv_crafting::actions::failure::NullFailure(*self)
             }
         }
     )
@@ -472,23 +525,12 @@ pub fn time_passed(input: TokenStream) -> TokenStream {
 
     const TAG: &str = "ffxiv_no_time_pass";
 
-    let val = !ast
-        .attrs
-        .iter()
-        .filter_map(|v| v.parse_meta().ok())
-        .filter_map(|v| {
-            if let Meta::List(list) = v {
-                Some(list)
-            } else {
-                None
-            }
-        })
-        .any(|v| v.path.is_ident(TAG));
+    let val = !ast.attrs.iter().any(|v| v.path.is_ident(TAG));
 
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::TimePassing for #ident #type_generic #(#where_clause)* {
+        impl #impl_generic ::ffxiv_crafting::actions::TimePassing for #ident #type_generic #(#where_clause)* {
             const TIME_PASSED: bool = #val;
         }
     )
@@ -504,7 +546,7 @@ pub fn action(input: TokenStream) -> TokenStream {
     quote!(
         #[automatically_derived]
         #[allow(unused_qualifications)]
-        impl #impl_generic crate::actions::Action for #ident #type_generic #(#where_clause)* {}
+        impl #impl_generic ::ffxiv_crafting::actions::Action for #ident #type_generic #(#where_clause)* {}
     )
     .into()
 }
@@ -553,7 +595,7 @@ fn find_attributes(
                         }
                     }
 
-                    criteria.retain(|k, _| removed.contains(k));
+                    criteria.retain(|k, _| !removed.contains(k));
                     removed.clear();
 
                     if criteria.is_empty() {
@@ -25,7 +25,8 @@ pub(super) fn condition_derive(input: TokenStream) -> TokenStream {
         let condition_types = parse_variants(&data);
         for (item, name) in condition_types {
             let modifier = format!("{}Modifier", name);
-            let qualified_modifier = format!("crate::lookups::{}", modifier);
+            let qualified_modifier =
+                format!("::ffxiv_crafting::conditions::raw_conditions::{}", modifier);
             let qualified_modifier = syn::parse(qualified_modifier.parse().unwrap()).unwrap();
 
             out.extend(derive_into(
@@ -60,6 +61,16 @@ pub(super) fn condition_derive(input: TokenStream) -> TokenStream {
             }
         };
 
+        let variants = data.variants.iter().map(|v| &v.ident);
+        let variants_copy = variants.clone();
+        let raw_bit = quote! {
+            fn raw_bit(self) -> u16 {
+                match self {
+                    #(Self::#variants => ::ffxiv_crafting::conditions::raw_conditions::RawConditions::#variants_copy as u16,)*
+                }
+            }
+        };
+
         let expert = if ast.attrs.contains(&parse_quote!(#[ffxiv(expert)])) {
             quote!(
                 const EXPERT: bool = true;
@@ -73,7 +84,7 @@ pub(super) fn condition_derive(input: TokenStream) -> TokenStream {
         let main_derive: TokenStream = quote! {
             #[automatically_derived]
             #[allow(unused_qualifications)]
-            impl #params crate::conditions::Condition for #ident #params #where_clause {
+            impl #params ::ffxiv_crafting::conditions::Condition for #ident #params #where_clause {
                 #expert
 
                 #(
@@ -85,6 +96,7 @@ pub(super) fn condition_derive(input: TokenStream) -> TokenStream {
                     matches!(self, Self::Good)
                 }
                 #is_excellent
+                #raw_bit
             }
         }
         .into();
@@ -123,7 +135,7 @@ impl<'a> IntoIterator for ConditionTypes<'a> {
     }
 }
 
-fn parse_variants(data: &DataEnum) -> ConditionTypes {
+fn parse_variants(data: &DataEnum) -> ConditionTypes<'_> {
     let mut quality = vec![];
     let mut progress = vec![];
     let mut success = vec![];
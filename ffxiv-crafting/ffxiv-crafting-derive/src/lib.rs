@@ -56,7 +56,7 @@ pub fn action(input: TokenStream) -> TokenStream {
     actions::action(input)
 }
 
-#[proc_macro_derive(PassthroughAction)]
+#[proc_macro_derive(PassthroughAction, attributes(ffxiv_name))]
 pub fn ffxiv_action_enum(input: TokenStream) -> TokenStream {
     passthrough::magic_action_passthrough(input)
 }
@@ -3,8 +3,8 @@ use std::iter;
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
-    self, parse_macro_input, parse_quote, Arm, FnArg, Ident, ItemEnum, ItemFn, ItemImpl, Path,
-    Receiver, TraitItemType,
+    self, parse_macro_input, parse_quote, Arm, FnArg, Ident, ItemEnum, ItemFn, ItemImpl, Lit, Meta,
+    Path, Receiver, TraitItemType, Variant,
 };
 
 pub fn magic_action_passthrough(input: TokenStream) -> TokenStream {
@@ -22,15 +22,23 @@ pub fn magic_action_passthrough(input: TokenStream) -> TokenStream {
 
     let traits = gen_traits(idents.clone().into_iter(), &ident).into_iter();
 
+    let all = gen_all(idents.clone().into_iter(), &ident);
+
+    let names = target.variants.iter().map(display_name).collect::<Vec<_>>();
+
+    let metadata = gen_metadata(idents.clone().into_iter(), names, &ident);
+
     let rand_actions = gen_rand_action(idents.into_iter(), &ident);
 
     let traits_enums: TokenStream = quote!(
         #(#traits)*
         #rand_actions
+        #all
+        #metadata
     )
     .into();
 
-    froms.extend(traits_enums.into_iter());
+    froms.extend(traits_enums);
     froms
 }
 
@@ -69,22 +77,41 @@ fn gen_traits<I: Iterator<Item = Ident> + Clone>(variants: I, me: &Ident) -> Vec
 fn traits() -> Vec<TraitBlueprint> {
     vec![
         TraitBlueprint {
-            name: parse_quote!(crate::actions::TimePassing),
+            name: parse_quote!(::ffxiv_crafting::actions::TimePassing),
             assoc_type: None,
-            funcs: vec![(
-                parse_quote!(
-                    fn time_passed<C, M>(&self, state: &crate::CraftingState<C, M>) -> bool
-                    where
-                        C: crate::conditions::Condition,
-                        M: crate::quality_map::QualityMap,
-                    {
-                    }
+            funcs: vec![
+                (
+                    parse_quote!(
+                        fn time_passed<C, M>(
+                            &self,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> bool
+                        where
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
+                        {
+                        }
+                    ),
+                    vec![parse_quote!(state)],
                 ),
-                vec![parse_quote!(state)],
-            )],
+                (
+                    parse_quote!(
+                        fn changes_condition<C, M>(
+                            &self,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> bool
+                        where
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
+                        {
+                        }
+                    ),
+                    vec![parse_quote!(state)],
+                ),
+            ],
         },
         TraitBlueprint {
-            name: parse_quote!(crate::actions::ActionLevel),
+            name: parse_quote!(::ffxiv_crafting::actions::ActionLevel),
             assoc_type: None,
             funcs: vec![(
                 parse_quote!(
@@ -94,14 +121,14 @@ fn traits() -> Vec<TraitBlueprint> {
             )],
         },
         TraitBlueprint {
-            name: parse_quote!(crate::actions::CpCost),
+            name: parse_quote!(::ffxiv_crafting::actions::CpCost),
             assoc_type: None,
             funcs: vec![(
                 parse_quote!(
-                    fn cp_cost<C, M>(&self, state: &crate::CraftingState<C, M>) -> i16
+                    fn cp_cost<C, M>(&self, state: &::ffxiv_crafting::CraftingState<C, M>) -> i16
                     where
-                        C: crate::conditions::Condition,
-                        M: crate::quality_map::QualityMap,
+                        C: ::ffxiv_crafting::conditions::Condition,
+                        M: ::ffxiv_crafting::quality_map::QualityMap,
                     {
                     }
                 ),
@@ -109,28 +136,51 @@ fn traits() -> Vec<TraitBlueprint> {
             )],
         },
         TraitBlueprint {
-            name: parse_quote!(crate::actions::DurabilityFactor),
+            name: parse_quote!(::ffxiv_crafting::actions::DurabilityFactor),
             assoc_type: None,
-            funcs: vec![(
-                parse_quote!(
-                    fn durability<C>(&self, buffs: &crate::buffs::BuffState, condition: &C) -> i8
-                    where
-                        C: crate::conditions::Condition,
-                    {
-                    }
+            funcs: vec![
+                (
+                    parse_quote!(
+                        fn durability<C>(
+                            &self,
+                            buffs: &::ffxiv_crafting::buffs::BuffState,
+                            condition: &C,
+                        ) -> i8
+                        where
+                            C: ::ffxiv_crafting::conditions::Condition,
+                        {
+                        }
+                    ),
+                    vec![parse_quote!(buffs), parse_quote!(condition)],
                 ),
-                vec![parse_quote!(buffs), parse_quote!(condition)],
-            )],
+                (
+                    parse_quote!(
+                        fn durability_in<C, M>(
+                            &self,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> i8
+                        where
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
+                        {
+                        }
+                    ),
+                    vec![parse_quote!(state)],
+                ),
+            ],
         },
         TraitBlueprint {
-            name: parse_quote!(crate::actions::CanExecute),
+            name: parse_quote!(::ffxiv_crafting::actions::CanExecute),
             assoc_type: None,
             funcs: vec![(
                 parse_quote!(
-                    fn can_execute<C, M>(&self, state: &crate::CraftingState<C, M>) -> bool
+                    fn can_execute<C, M>(
+                        &self,
+                        state: &::ffxiv_crafting::CraftingState<C, M>,
+                    ) -> bool
                     where
-                        C: crate::conditions::Condition,
-                        M: crate::quality_map::QualityMap,
+                        C: ::ffxiv_crafting::conditions::Condition,
+                        M: ::ffxiv_crafting::quality_map::QualityMap,
                     {
                     }
                 ),
@@ -138,18 +188,18 @@ fn traits() -> Vec<TraitBlueprint> {
             )],
         },
         TraitBlueprint {
-            name: parse_quote!(crate::actions::buffs::BuffAction),
+            name: parse_quote!(::ffxiv_crafting::actions::buffs::BuffAction),
             assoc_type: None,
             funcs: vec![
                 (
                     parse_quote!(
                         fn buff<C, M>(
                             &self,
-                            state: &crate::CraftingState<C, M>,
-                            so_far: &mut crate::buffs::BuffState,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                            so_far: &mut ::ffxiv_crafting::buffs::BuffState,
                         ) where
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         {
                         }
                     ),
@@ -159,11 +209,11 @@ fn traits() -> Vec<TraitBlueprint> {
                     parse_quote!(
                         fn deactivate_buff<C, M>(
                             &self,
-                            state: &crate::CraftingState<C, M>,
-                            so_far: &mut crate::buffs::BuffState,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                            so_far: &mut ::ffxiv_crafting::buffs::BuffState,
                         ) where
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         {
                         }
                     ),
@@ -172,15 +222,18 @@ fn traits() -> Vec<TraitBlueprint> {
             ],
         },
         TraitBlueprint {
-            name: parse_quote!(crate::actions::QualityAction),
+            name: parse_quote!(::ffxiv_crafting::actions::QualityAction),
             assoc_type: None,
             funcs: vec![
                 (
                     parse_quote!(
-                        fn efficiency<C, M>(&self, state: &crate::CraftingState<C, M>) -> f64
+                        fn efficiency<C, M>(
+                            &self,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> f64
                         where
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         {
                         }
                     ),
@@ -188,10 +241,13 @@ fn traits() -> Vec<TraitBlueprint> {
                 ),
                 (
                     parse_quote!(
-                        fn quality<C, M>(&self, state: &crate::CraftingState<C, M>) -> u32
+                        fn quality<C, M>(
+                            &self,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> u32
                         where
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         {
                         }
                     ),
@@ -200,15 +256,32 @@ fn traits() -> Vec<TraitBlueprint> {
             ],
         },
         TraitBlueprint {
-            name: parse_quote!(crate::actions::ProgressAction),
+            name: parse_quote!(::ffxiv_crafting::actions::ProgressAction),
             assoc_type: None,
             funcs: vec![
                 (
                     parse_quote!(
-                        fn efficiency<C, M>(&self, state: &crate::CraftingState<C, M>) -> f64
+                        fn base_efficiency<C, M>(
+                            &self,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> u16
+                        where
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
+                        {
+                        }
+                    ),
+                    vec![parse_quote!(state)],
+                ),
+                (
+                    parse_quote!(
+                        fn efficiency<C, M>(
+                            &self,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> f64
                         where
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         {
                         }
                     ),
@@ -216,10 +289,13 @@ fn traits() -> Vec<TraitBlueprint> {
                 ),
                 (
                     parse_quote!(
-                        fn progress<C, M>(&self, state: &crate::CraftingState<C, M>) -> u32
+                        fn progress<C, M>(
+                            &self,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> u32
                         where
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         {
                         }
                     ),
@@ -228,18 +304,18 @@ fn traits() -> Vec<TraitBlueprint> {
             ],
         },
         TraitBlueprint {
-            name: parse_quote!(crate::actions::Action),
+            name: parse_quote!(::ffxiv_crafting::actions::Action),
             assoc_type: None,
             funcs: vec![
                 (
                     parse_quote!(
                         fn prospective_act<C, M>(
                             self,
-                            state: &crate::CraftingState<C, M>,
-                        ) -> crate::actions::ActionResult
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> ::ffxiv_crafting::actions::ActionResult
                         where
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         {
                         }
                     ),
@@ -249,11 +325,11 @@ fn traits() -> Vec<TraitBlueprint> {
                     parse_quote!(
                         fn act<C, M>(
                             self,
-                            state: &crate::CraftingState<C, M>,
-                        ) -> crate::actions::ActionOutcome
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> ::ffxiv_crafting::actions::ActionOutcome
                         where
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         {
                         }
                     ),
@@ -262,19 +338,19 @@ fn traits() -> Vec<TraitBlueprint> {
                 (
                     parse_quote!(
                         fn act_random<
-                            R: rand::Rng,
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            R: ::ffxiv_crafting::rand::Rng,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         >(
                             self,
                             rng: &mut R,
-                            state: &crate::CraftingState<C, M>,
-                        ) -> crate::actions::RollOutcome<
-                            crate::actions::ActionOutcome,
-                            crate::actions::ActionOutcome,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> ::ffxiv_crafting::actions::RollOutcome<
+                            ::ffxiv_crafting::actions::ActionOutcome,
+                            ::ffxiv_crafting::actions::ActionOutcome,
                         >
                         where
-                            Self: crate::actions::RandomAction,
+                            Self: ::ffxiv_crafting::actions::RandomAction,
                         {
                         }
                     ),
@@ -283,19 +359,19 @@ fn traits() -> Vec<TraitBlueprint> {
                 (
                     parse_quote!(
                         fn prospective_act_random<
-                            R: rand::Rng,
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            R: ::ffxiv_crafting::rand::Rng,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         >(
                             self,
                             rng: &mut R,
-                            state: &crate::CraftingState<C, M>,
-                        ) -> crate::actions::RollOutcome<
-                            crate::actions::ActionResult,
-                            crate::actions::ActionResult,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
+                        ) -> ::ffxiv_crafting::actions::RollOutcome<
+                            ::ffxiv_crafting::actions::ActionResult,
+                            ::ffxiv_crafting::actions::ActionResult,
                         >
                         where
-                            Self: crate::actions::RandomAction,
+                            Self: ::ffxiv_crafting::actions::RandomAction,
                         {
                         }
                     ),
@@ -304,20 +380,20 @@ fn traits() -> Vec<TraitBlueprint> {
                 (
                     parse_quote!(
                         fn prospective_act_and_fail<
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         >(
                             self,
-                            state: &crate::CraftingState<C, M>,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
                         ) -> [(
                             u8,
-                            crate::actions::RollOutcome<
-                                crate::actions::ActionResult,
-                                crate::actions::ActionResult,
+                            ::ffxiv_crafting::actions::RollOutcome<
+                                ::ffxiv_crafting::actions::ActionResult,
+                                ::ffxiv_crafting::actions::ActionResult,
                             >,
                         ); 2]
                         where
-                            Self: crate::actions::RandomAction,
+                            Self: ::ffxiv_crafting::actions::RandomAction,
                         {
                         }
                     ),
@@ -326,20 +402,20 @@ fn traits() -> Vec<TraitBlueprint> {
                 (
                     parse_quote!(
                         fn act_and_fail<
-                            C: crate::conditions::Condition,
-                            M: crate::quality_map::QualityMap,
+                            C: ::ffxiv_crafting::conditions::Condition,
+                            M: ::ffxiv_crafting::quality_map::QualityMap,
                         >(
                             self,
-                            state: &crate::CraftingState<C, M>,
+                            state: &::ffxiv_crafting::CraftingState<C, M>,
                         ) -> [(
                             u8,
-                            crate::actions::RollOutcome<
-                                crate::actions::ActionOutcome,
-                                crate::actions::ActionOutcome,
+                            ::ffxiv_crafting::actions::RollOutcome<
+                                ::ffxiv_crafting::actions::ActionOutcome,
+                                ::ffxiv_crafting::actions::ActionOutcome,
                             >,
                         ); 2]
                         where
-                            Self: crate::actions::RandomAction,
+                            Self: ::ffxiv_crafting::actions::RandomAction,
                         {
                         }
                     ),
@@ -365,6 +441,79 @@ fn gen_from<I: Iterator<Item = Ident> + Clone>(variants: I, me: &Ident) -> Token
     .into()
 }
 
+fn gen_all<I: ExactSizeIterator<Item = Ident>>(variants: I, me: &Ident) -> ItemImpl {
+    let count = variants.len();
+
+    parse_quote!(
+        #[automatically_derived]
+        impl #me {
+            /// Every action in this collection, in the order its variants are declared.
+            pub const ALL: [Self; #count] = [#(Self::#variants,)*];
+        }
+    )
+}
+
+/// The in-game name of an action, either given by `#[ffxiv_name = "..."]` or made by splitting the
+/// variant's name into words, e.g. `BasicTouch` becomes "Basic Touch".
+fn display_name(variant: &Variant) -> String {
+    for meta in variant.attrs.iter().filter_map(|v| v.parse_meta().ok()) {
+        if let Meta::NameValue(name_value) = meta {
+            if name_value.path.is_ident("ffxiv_name") {
+                match name_value.lit {
+                    Lit::Str(lit) => return lit.value(),
+                    other => panic!("Invalid format for FFXIV action name, got {:?}", other),
+                }
+            }
+        }
+    }
+
+    let mut name = String::new();
+    for c in variant.ident.to_string().chars() {
+        if c.is_uppercase() && !name.is_empty() {
+            name.push(' ');
+        }
+        name.push(c);
+    }
+
+    name
+}
+
+fn gen_metadata<I: ExactSizeIterator<Item = Ident> + Clone>(
+    variants: I,
+    names: Vec<String>,
+    me: &Ident,
+) -> ItemImpl {
+    let count = variants.len();
+    let indices = 0..count;
+    let variants_copy = variants.clone();
+
+    parse_quote!(
+        #[automatically_derived]
+        impl #me {
+            /// The constants of every action in this collection, in the same order as
+            /// [`ALL`](Self::ALL).
+            pub const METADATA: [::ffxiv_crafting::actions::ActionMeta; #count] = [#(
+                ::ffxiv_crafting::actions::ActionMeta {
+                    name: #names,
+                    level: <#variants as ::ffxiv_crafting::actions::ActionLevel>::LEVEL,
+                    cp_cost: <#variants as ::ffxiv_crafting::actions::CpCost>::CP_COST,
+                    durability: <#variants as ::ffxiv_crafting::actions::DurabilityFactor>::DURABILITY_USAGE,
+                    full_repair: <#variants as ::ffxiv_crafting::actions::DurabilityFactor>::FULL_REPAIR,
+                    fail_rate: <#variants as ::ffxiv_crafting::actions::RandomAction>::FAIL_RATE,
+                    time_passed: <#variants as ::ffxiv_crafting::actions::TimePassing>::TIME_PASSED,
+                },
+            )*];
+
+            /// The constants of this action, looked up in [`METADATA`](Self::METADATA).
+            pub const fn metadata(self) -> ::ffxiv_crafting::actions::ActionMeta {
+                match self {
+                    #(Self::#variants_copy => Self::METADATA[#indices],)*
+                }
+            }
+        }
+    )
+}
+
 fn gen_trait<I: Iterator<Item = Ident> + Clone>(
     variants: I,
     me: Ident,
@@ -433,26 +582,26 @@ fn gen_rand_action<I: Iterator<Item = Ident> + Clone>(variants: I, me: &Ident) -
 
     parse_quote!(
         #[automatically_derived]
-        impl crate::actions::RandomAction for #me {
-            type FailAction = crate::actions::failure::NullFailure<Self>;
+        impl ::ffxiv_crafting::actions::RandomAction for #me {
+            type FailAction = ::ffxiv_crafting::actions::failure::NullFailure<Self>;
 
-            fn roll<R: rand::Rng, C: crate::conditions::Condition, M: crate::quality_map::QualityMap>(
+            fn roll<R: ::ffxiv_crafting::rand::Rng, C: ::ffxiv_crafting::conditions::Condition, M: ::ffxiv_crafting::quality_map::QualityMap>(
                 self,
                 rng: &mut R,
-                state: &crate::CraftingState<C, M>,
-            ) -> crate::actions::RollOutcome<Self, Self::FailAction> {
+                state: &::ffxiv_crafting::CraftingState<C, M>,
+            ) -> ::ffxiv_crafting::actions::RollOutcome<Self, Self::FailAction> {
 
-                use crate::actions::RollOutcome;
+                use ::ffxiv_crafting::actions::RollOutcome;
 
                 match self {
                     #(Self::#variants => match #variants.roll(rng, state) {
                         RollOutcome::Success(_) => RollOutcome::Success(self),
-                        RollOutcome::Failure(_) => RollOutcome::Failure(crate::actions::failure::NullFailure(self)),
+                        RollOutcome::Failure(_) => RollOutcome::Failure(::ffxiv_crafting::actions::failure::NullFailure(self)),
                     },)*
                 }
             }
 
-            fn fail_rate<C: crate::conditions::Condition, M: crate::quality_map::QualityMap>(&self, state: &crate::CraftingState<C, M>) -> u8 {
+            fn fail_rate<C: ::ffxiv_crafting::conditions::Condition, M: ::ffxiv_crafting::quality_map::QualityMap>(&self, state: &::ffxiv_crafting::CraftingState<C, M>) -> u8 {
                 match self {
                     #(Self::#variants_copy => #variants_copy.fail_rate(state),)*
                 }
@@ -460,7 +609,7 @@ fn gen_rand_action<I: Iterator<Item = Ident> + Clone>(variants: I, me: &Ident) -
 
             fn fail_action(&self) -> Self::FailAction {
                 match self {
-                    #(Self::#variants_copy2 => crate::actions::failure::NullFailure(*self),)*
+                    #(Self::#variants_copy2 => ::ffxiv_crafting::actions::failure::NullFailure(*self),)*
                 }
             }
         }
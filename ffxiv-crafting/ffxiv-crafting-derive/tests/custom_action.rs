@@ -0,0 +1,129 @@
+//! Defines a made up action outside of `ffxiv-crafting`, to make sure the derives only refer to
+//! things downstream crates can reach.
+
+use ffxiv_crafting::{
+    actions::{
+        progress::ProgressAction, quality::QualityAction, ActionLevel, DurabilityFactor,
+        RandomAction,
+    },
+    buffs::BuffState,
+    conditions::{QARegularConditions, RestoExpertConditions},
+};
+use ffxiv_crafting_derive::*;
+
+/// Twice the efficiency of Basic Synthesis for twice the durability, with a risk of failing.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
+#[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
+#[derive(CanExecute, BuffAction, ActionLevel, RandomAction, TimePassing, Action)]
+#[ffxiv_progress(efficiency = 240)]
+#[ffxiv_cp(cost = 12)]
+#[ffxiv_durability(cost = 20)]
+#[ffxiv_act_lvl(level = 90)]
+#[ffxiv_rand_act(fail_rate = 20)]
+#[ffxiv_buff_act(synthesis)]
+struct HeavySynthesis;
+
+/// Like [`HeavySynthesis`], but gets stronger as the character levels.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
+#[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
+#[derive(CanExecute, BuffAction, ActionLevel, RandomAction, TimePassing, Action)]
+#[ffxiv_progress(efficiency = 180, efficiency_at = "90:240,82:200")]
+#[ffxiv_cp(cost = 12)]
+#[ffxiv_durability(cost = 20)]
+#[ffxiv_act_lvl(level = 70)]
+#[ffxiv_buff_act(synthesis)]
+struct ScalingSynthesis;
+
+/// A follow-up to Observe that never fails when comboed.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
+#[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
+#[derive(CanExecute, BuffAction, ActionLevel, RandomAction, TimePassing, Action)]
+#[ffxiv_progress(efficiency = 150)]
+#[ffxiv_cp(cost = 5)]
+#[ffxiv_act_lvl(level = 70)]
+#[ffxiv_rand_act(fail_rate = 30, class = "combo_observe")]
+#[ffxiv_buff_act(synthesis)]
+struct ObservedSynthesis;
+
+#[test]
+fn custom_action_constants() {
+    use ffxiv_crafting::actions::CpCost;
+
+    assert_eq!(<HeavySynthesis as ProgressAction>::EFFICIENCY, 240);
+    assert_eq!(<HeavySynthesis as QualityAction>::EFFICIENCY, 0);
+    assert_eq!(HeavySynthesis::CP_COST, -12);
+    assert_eq!(HeavySynthesis::DURABILITY_USAGE, -20);
+    assert_eq!(HeavySynthesis::LEVEL, 90);
+    assert_eq!(HeavySynthesis::FAIL_RATE, 20);
+}
+
+#[test]
+fn custom_action_durability() {
+    let buffs = BuffState::default();
+
+    assert_eq!(
+        HeavySynthesis.durability(&buffs, &QARegularConditions::Normal),
+        -20
+    );
+    assert_eq!(
+        HeavySynthesis.durability(&buffs, &RestoExpertConditions::Sturdy),
+        -10
+    );
+}
+
+#[test]
+fn custom_action_efficiency_breakpoints() {
+    use ffxiv_crafting::{
+        quality_map::HQMap, CharacterStats, CraftingSimulator, CraftingState, RecipeLevelRanges,
+        RecipeStats,
+    };
+
+    let efficiency_at = |char_level| {
+        let problem_def = CraftingSimulator::<_, HQMap>::new(
+            CharacterStats {
+                craftsmanship: 2_000,
+                control: 2_000,
+                max_cp: 500,
+                char_level,
+            },
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 80, 5_000, 2_000),
+            QARegularConditions::Normal,
+        );
+        let state = CraftingState::new_simulation(&problem_def);
+
+        (
+            ScalingSynthesis.base_efficiency(&state),
+            ProgressAction::efficiency(&ScalingSynthesis, &state),
+        )
+    };
+
+    assert_eq!(<ScalingSynthesis as ProgressAction>::EFFICIENCY, 180);
+    assert_eq!(efficiency_at(81), (180, 180.));
+    assert_eq!(efficiency_at(82), (200, 200.));
+    assert_eq!(efficiency_at(89), (200, 200.));
+    assert_eq!(efficiency_at(90), (240, 240.));
+}
+
+#[test]
+fn custom_action_observe_combo() {
+    use ffxiv_crafting::{
+        buffs::combo::ObserveCombo, quality_map::HQMap, CharacterStats, CraftingSimulator,
+        CraftingState, RecipeLevelRanges, RecipeStats,
+    };
+
+    let problem_def = CraftingSimulator::<_, HQMap>::new(
+        CharacterStats {
+            craftsmanship: 2_000,
+            control: 2_000,
+            max_cp: 500,
+            char_level: 80,
+        },
+        RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 80, 5_000, 2_000),
+        QARegularConditions::Normal,
+    );
+    let mut state = CraftingState::new_simulation(&problem_def);
+    assert_eq!(ObservedSynthesis.fail_rate(&state), 30);
+
+    state.buffs.combo.observation = ObserveCombo::Active;
+    assert_eq!(ObservedSynthesis.fail_rate(&state), 0);
+}
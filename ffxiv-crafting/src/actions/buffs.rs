@@ -2,7 +2,7 @@
 
 use crate::{
     actions::CanExecute,
-    buffs::{BuffState, DurationalBuff},
+    buffs::{misc, quality, Buff, BuffState, DurationalBuff},
     conditions::Condition,
     quality_map::QualityMap,
     CraftingState,
@@ -201,3 +201,73 @@ impl CanExecute for HeartAndSoul {
         state.buffs.specialist_actions.actions_available()
     }
 }
+
+/// A specialist Dawntrail action that activates the [`Innovation`] buff for a single step without
+/// letting time pass. It uses up a crafter's delineation, and can only be used once per craft and
+/// while [`Innovation`] isn't already active.
+///
+/// [`Innovation`]: crate::buffs::quality::Innovation
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
+#[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
+#[derive(ActionLevel, RandomAction, TimePassing, Action)]
+#[ffxiv_cp(cost = 0)]
+#[ffxiv_act_lvl(level = 96)]
+#[ffxiv_durability(cost = 0)]
+#[ffxiv_no_time_pass]
+pub struct QuickInnovation;
+
+impl BuffAction for QuickInnovation {
+    fn buff<C, M>(&self, _: &CraftingState<C, M>, so_far: &mut BuffState)
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        so_far.specialist_actions -= 1;
+        so_far.quick_innovation = misc::QuickInnovation::Used;
+        so_far.quality.innovation = quality::Innovation::Active(1);
+    }
+}
+
+impl CanExecute for QuickInnovation {
+    fn can_execute<C, M>(&self, state: &CraftingState<C, M>) -> bool
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        state.buffs.specialist_actions.actions_available()
+            && state.buffs.quick_innovation == misc::QuickInnovation::Available
+            && state.buffs.quality.innovation.is_inactive()
+    }
+}
+
+/// Activates the [`TrainedPerfection`] buff, making the next action that would use durability
+/// use none at all. This can only be used once per craft.
+///
+/// [`TrainedPerfection`]: crate::buffs::durability::TrainedPerfection
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
+#[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
+#[derive(ActionLevel, RandomAction, TimePassing, Action)]
+#[ffxiv_cp(cost = 0)]
+#[ffxiv_act_lvl(level = 100)]
+#[ffxiv_durability(cost = 0)]
+pub struct TrainedPerfection;
+
+impl BuffAction for TrainedPerfection {
+    fn buff<C, M>(&self, _: &CraftingState<C, M>, so_far: &mut BuffState)
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        so_far.durability.trained_perfection.activate_in_place();
+    }
+}
+
+impl CanExecute for TrainedPerfection {
+    fn can_execute<C, M>(&self, state: &CraftingState<C, M>) -> bool
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        state.buffs.durability.trained_perfection.is_available()
+    }
+}
@@ -20,6 +20,7 @@ use ffxiv_crafting_derive::PassthroughAction;
 /// [`quality`]: crate::actions::quality
 #[derive(PassthroughAction)]
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FfxivCraftingActions {
     /* Buff */
     /// [`Veneration`]
@@ -33,22 +34,32 @@ pub enum FfxivCraftingActions {
     /// [`FinalAppraisal`]
     FinalAppraisal,
     /// [`WasteNot2`]
+    #[ffxiv_name = "Waste Not II"]
     WasteNot2,
     /// [`Manipulation`]
     Manipulation,
+    /// [`TrainedPerfection`]
+    TrainedPerfection,
+    /// [`QuickInnovation`]
+    QuickInnovation,
 
     /* Misc */
     /// [`MastersMend`]
+    #[ffxiv_name = "Master's Mend"]
     MastersMend,
+    /// [`ImmaculateMend`]
+    ImmaculateMend,
     /// [`Observe`]
     Observe,
     /// [`TricksOfTheTrade`]
+    #[ffxiv_name = "Tricks of the Trade"]
     TricksOfTheTrade,
     /// [`DelicateSynthesis`]
     DelicateSynthesis,
     /// [`CarefulObservation`]
     CarefulObservation,
     /// [`HeartAndSoul`]
+    #[ffxiv_name = "Heart and Soul"]
     HeartAndSoul,
 
     /* Progress/Synthesis */
@@ -77,6 +88,7 @@ pub enum FfxivCraftingActions {
     /// [`StandardTouch`]
     StandardTouch,
     /// [`ByregotsBlessing`]
+    #[ffxiv_name = "Byregot's Blessing"]
     ByregotsBlessing,
     /// [`PreciseTouch`]
     PreciseTouch,
@@ -94,4 +106,123 @@ pub enum FfxivCraftingActions {
     AdvancedTouch,
     /// [`TrainedFinesse`]
     TrainedFinesse,
+    /// [`RefinedTouch`]
+    RefinedTouch,
+    /// [`DaringTouch`]
+    DaringTouch,
+}
+
+/// Which of the [`buffs`], [`misc`], [`progress`], or [`quality`] modules an action is defined in,
+/// which is a rough classification of what it's used for.
+///
+/// [`buffs`]: crate::actions::buffs
+/// [`misc`]: crate::actions::misc
+/// [`progress`]: crate::actions::progress
+/// [`quality`]: crate::actions::quality
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug)]
+pub enum ActionCategory {
+    /// Actions whose only effect is applying a buff.
+    Buff,
+
+    /// Actions that don't fit elsewhere, such as repairs and [`Observe`].
+    Misc,
+
+    /// Actions whose main purpose is increasing progress.
+    Progress,
+
+    /// Actions whose main purpose is increasing quality.
+    Quality,
+}
+
+impl FfxivCraftingActions {
+    /// The [`ActionCategory`] of this action.
+    pub const fn category(self) -> ActionCategory {
+        use FfxivCraftingActions::*;
+
+        match self {
+            Veneration | WasteNot | GreatStrides | Innovation | FinalAppraisal | WasteNot2
+            | Manipulation | TrainedPerfection | QuickInnovation => ActionCategory::Buff,
+            MastersMend | ImmaculateMend | Observe | TricksOfTheTrade | DelicateSynthesis
+            | CarefulObservation | HeartAndSoul => ActionCategory::Misc,
+            BasicSynthesis | RapidSynthesis | MuscleMemory | CarefulSynthesis
+            | FocusedSynthesis | Groundwork | IntensiveSynthesis | PrudentSynthesis => {
+                ActionCategory::Progress
+            }
+            BasicTouch | HastyTouch | StandardTouch | ByregotsBlessing | PreciseTouch
+            | PrudentTouch | FocusedTouch | Reflect | PreparatoryTouch | TrainedEye
+            | AdvancedTouch | TrainedFinesse | RefinedTouch | DaringTouch => {
+                ActionCategory::Quality
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        actions::{ActionLevel, CpCost, DurabilityFactor, RandomAction, TimePassing},
+        buffs::BuffState,
+        conditions::QARegularConditions,
+        test_util::CLASSICAL_SIMULATOR,
+        CraftingState,
+    };
+
+    #[test]
+    fn metadata_matches_constants() {
+        assert_eq!(
+            FfxivCraftingActions::METADATA.len(),
+            FfxivCraftingActions::ALL.len()
+        );
+
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        for action in FfxivCraftingActions::ALL {
+            let meta = action.metadata();
+
+            // Nothing modifies these on a fresh craft in the Normal condition
+            assert_eq!(meta.level, action.level(), "{:?}", action);
+            assert_eq!(meta.fail_rate, action.fail_rate(&state), "{:?}", action);
+            assert_eq!(meta.time_passed, action.time_passed(&state), "{:?}", action);
+            assert_eq!(
+                meta.durability,
+                action.durability(&BuffState::default(), &QARegularConditions::Normal),
+                "{:?}",
+                action
+            );
+        }
+
+        let touch = FfxivCraftingActions::StandardTouch.metadata();
+        assert_eq!(touch.cp_cost, StandardTouch::CP_COST);
+        assert_eq!(touch.level, StandardTouch::LEVEL);
+
+        let tricks = FfxivCraftingActions::TricksOfTheTrade.metadata();
+        assert_eq!(tricks.cp_cost, TricksOfTheTrade::CP_COST);
+        assert_eq!(tricks.durability, TricksOfTheTrade::DURABILITY_USAGE);
+        assert!(!tricks.full_repair);
+        assert!(FfxivCraftingActions::ImmaculateMend.metadata().full_repair);
+
+        assert!(!FfxivCraftingActions::FinalAppraisal.metadata().time_passed);
+
+        let rapid = FfxivCraftingActions::RapidSynthesis.metadata();
+        assert_eq!(rapid.fail_rate, RapidSynthesis::FAIL_RATE);
+    }
+
+    #[test]
+    fn time_stopping_actions() {
+        use FfxivCraftingActions::*;
+
+        let stopping = FfxivCraftingActions::ALL
+            .into_iter()
+            .filter(|action| !action.metadata().time_passed)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stopping,
+            [
+                FinalAppraisal,
+                QuickInnovation,
+                CarefulObservation,
+                HeartAndSoul
+            ]
+        );
+    }
 }
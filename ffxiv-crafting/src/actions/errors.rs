@@ -83,3 +83,36 @@ impl Display for ActionError {
 }
 
 impl Error for ActionError {}
+
+/// An [`Error`] explaining why a [`StateDelta`] can't be [undone](CraftingState::undo) from a
+/// state, which means the delta was never added to it, e.g. undoing past the start of the craft.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum UndoError {
+    /// The delta added more quality than the state has.
+    QualityUnderflow,
+    /// The delta added more progress than the state has.
+    ProgressUnderflow,
+    /// Undoing the delta would leave durability at 0 or below, or above the recipe's max.
+    DurabilityOutOfRange,
+    /// Undoing the delta would leave CP below 0, or above the character's max.
+    CpOutOfRange,
+}
+
+impl Display for UndoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let err_msg = match self {
+            Self::QualityUnderflow => "it added more quality than the state has",
+            Self::ProgressUnderflow => "it added more progress than the state has",
+            Self::DurabilityOutOfRange => "durability would be out of range before it",
+            Self::CpOutOfRange => "CP would be out of range before it",
+        };
+
+        write!(
+            f,
+            "This delta can't have been added to the state, as {}",
+            err_msg
+        )
+    }
+}
+
+impl Error for UndoError {}
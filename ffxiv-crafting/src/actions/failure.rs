@@ -23,6 +23,7 @@ pub struct NullFailure<A: Action + ActionComponents>(pub A);
 
 impl<A: Action + ActionComponents> DurabilityFactor for NullFailure<A> {
     const DURABILITY_USAGE: i8 = 0;
+    const FULL_REPAIR: bool = A::FULL_REPAIR;
 
     fn durability<C>(&self, buffs: &crate::buffs::BuffState, condition: &C) -> i8
     where
@@ -30,6 +31,14 @@ impl<A: Action + ActionComponents> DurabilityFactor for NullFailure<A> {
     {
         self.0.durability(buffs, condition)
     }
+
+    fn durability_in<C, M>(&self, state: &CraftingState<C, M>) -> i8
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        self.0.durability_in(state)
+    }
 }
 
 impl<A: Action + ActionComponents> CpCost for NullFailure<A> {
@@ -2,9 +2,9 @@
 
 use ffxiv_crafting_derive::*;
 
-use crate::buffs::ConsumableBuff;
+use crate::buffs::{Buff, ConsumableBuff};
 
-use super::{buffs::BuffAction, CanExecute};
+use super::{buffs::BuffAction, CanExecute, DurabilityFactor, TimePassing};
 
 /// Spends 88 CP to instantly repair 30 durability.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
@@ -31,7 +31,24 @@ pub struct MastersMend;
 #[ffxiv_buff_act(activate = "combo.observation")]
 pub struct Observe;
 
-/// "Consumes" a [`Good`] or [`Excellent`] [`Condition`] to restore 20 CP.
+/// Spends 112 CP to restore durability all the way to the recipe's maximum, no matter how
+/// much was lost.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
+#[derive(ProgressAction, QualityAction, CpCost, BuffAction)]
+#[derive(CanExecute, ActionLevel, RandomAction, TimePassing, Action)]
+#[ffxiv_cp(cost = 112)]
+#[ffxiv_act_lvl(level = 98)]
+pub struct ImmaculateMend;
+
+impl DurabilityFactor for ImmaculateMend {
+    const DURABILITY_USAGE: i8 = 0;
+    const FULL_REPAIR: bool = true;
+}
+
+/// "Consumes" a [`Good`] or [`Excellent`] [`Condition`] to restore 20 CP. It can also be used in
+/// any other condition by consuming [`HeartAndSoul`] instead, which still restores the full 20 CP.
+///
+/// [`HeartAndSoul`]: crate::actions::buffs::HeartAndSoul
 ///
 /// [`Condition`]: crate::conditions::Condition
 /// [`Good`]: crate::conditions::QARegularConditions::Good
@@ -41,6 +58,7 @@ pub struct Observe;
 #[derive(CanExecute, ActionLevel, RandomAction, TimePassing, Action)]
 #[ffxiv_cp(bonus = 20)]
 #[ffxiv_act_lvl(level = 13)]
+#[ffxiv_durability(cost = 0)]
 #[ffxiv_can_exe(class = "good_excellent")]
 pub struct TricksOfTheTrade;
 
@@ -53,7 +71,10 @@ impl BuffAction for TricksOfTheTrade {
         C: crate::conditions::Condition,
         M: crate::quality_map::QualityMap,
     {
-        if !(state.condition.is_excellent() || state.condition.is_good()) {
+        // Heart and Soul is only spent if it's what let Tricks be used at all
+        if !(state.condition.is_excellent() || state.condition.is_good())
+            && state.buffs.heart_and_soul.is_active()
+        {
             so_far.heart_and_soul.deactivate_in_place();
         }
     }
@@ -88,13 +109,17 @@ pub struct DelicateSynthesis;
 /// [`FinalAppraisal`]: crate::actions::buffs::FinalAppraisal
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
 #[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
-#[derive(ActionLevel, RandomAction, TimePassing, Action)]
+#[derive(ActionLevel, RandomAction, Action)]
 #[ffxiv_cp(cost = 0)]
 #[ffxiv_act_lvl(level = 55)]
 #[ffxiv_durability(cost = 0)]
-#[ffxiv_no_time_pass]
 pub struct CarefulObservation;
 
+impl TimePassing for CarefulObservation {
+    const TIME_PASSED: bool = false;
+    const CHANGES_CONDITION: bool = true;
+}
+
 impl BuffAction for CarefulObservation {
     fn buff<C, M>(&self, _: &crate::CraftingState<C, M>, so_far: &mut crate::buffs::BuffState)
     where
@@ -114,3 +139,159 @@ impl CanExecute for CarefulObservation {
         state.buffs.specialist_actions.actions_available()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        actions::{Action, CpCost, DurabilityFactor},
+        buffs::{quality::InnerQuiet, Buff, DurationalBuff},
+        conditions::QARegularConditions,
+        test_util::{CLASSICAL_SIMULATOR, DAWNTRAIL_SIMULATOR},
+        CraftingState,
+    };
+
+    #[test]
+    fn costs_spend_cp() {
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        assert_eq!(Observe.cp_cost(&state), -7);
+        assert_eq!(MastersMend.cp_cost(&state), -88);
+    }
+
+    #[test]
+    fn durability_costs_and_repairs() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.curr_durability = 40;
+
+        assert_eq!(Observe.durability(&state.buffs, &state.condition), 0);
+        assert_eq!(
+            DelicateSynthesis.durability(&state.buffs, &state.condition),
+            -10
+        );
+
+        let next = state + MastersMend.act(&state).outcome();
+        assert_eq!(next.curr_durability, 70);
+    }
+
+    #[test]
+    fn delicate_synthesis_touches_and_synthesizes() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.quality.inner_quiet = InnerQuiet::Active(1);
+        state.buffs.progress.muscle_memory.activate_in_place(0);
+
+        let next = state + DelicateSynthesis.act(&state).outcome();
+        assert!(next.curr_progress > 0);
+        assert!(next.curr_quality > 0);
+        assert_eq!(next.buffs.quality.inner_quiet, InnerQuiet::Active(2));
+        assert!(!next.buffs.progress.muscle_memory.is_active());
+    }
+
+    #[test]
+    fn delicate_synthesis_completes_with_quality() {
+        use crate::{actions::ActionOutcome, quality_map::HQChance};
+
+        // The last of the durability, so completion has to be checked before failure
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.curr_durability = 10;
+        state.curr_progress = CLASSICAL_SIMULATOR.recipe.max_progress - 1;
+        state.curr_quality = CLASSICAL_SIMULATOR.recipe.max_quality - 1;
+
+        let outcome = DelicateSynthesis.act(&state);
+        assert!(matches!(outcome, ActionOutcome::Completed(_)));
+        assert_eq!(outcome.map_quality(&state), Some(HQChance(100)));
+
+        let next = state + outcome.outcome();
+        assert!(next.curr_progress >= CLASSICAL_SIMULATOR.recipe.max_progress);
+        assert!(next.curr_quality > CLASSICAL_SIMULATOR.recipe.max_quality);
+        assert_eq!(next.curr_durability, 0);
+        assert_eq!(next.curr_cp, state.curr_cp - 32);
+    }
+
+    #[test]
+    fn tricks_clamps_to_max_cp() {
+        let max_cp = CLASSICAL_SIMULATOR.character.max_cp;
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.condition = QARegularConditions::Good;
+        state.curr_cp = max_cp - 5;
+
+        let delta = TricksOfTheTrade.act(&state).outcome();
+        assert_eq!(delta.added_cp, 20);
+        assert_eq!((state + delta).curr_cp, max_cp);
+    }
+
+    #[test]
+    fn immaculate_mend_repairs_to_max() {
+        use crate::buffs::durability::Manipulation;
+
+        let max = DAWNTRAIL_SIMULATOR.recipe.max_durability;
+        let mut state = CraftingState::new_simulation(&DAWNTRAIL_SIMULATOR);
+
+        state.curr_durability = 10;
+        assert_eq!(ImmaculateMend.durability_in(&state), max - 10);
+        let delta = ImmaculateMend.act(&state).outcome();
+        assert_eq!(delta.action_durability, max - 10);
+        assert_eq!((state + delta).curr_durability, max);
+        assert_eq!((state + delta).curr_cp, state.curr_cp - 112);
+
+        // Near (or at) max it only repairs what's missing, and Manipulation can't push it over
+        state.buffs.durability.manipulation = Manipulation::Active(4);
+        for durability in [max - 5, max] {
+            state.curr_durability = durability;
+            let outcome = ImmaculateMend.act(&state);
+            assert_eq!(outcome.outcome().action_durability, max - durability);
+            assert_eq!(outcome.durability_after_repair(&state), Some(max));
+            assert_eq!((state + outcome.outcome()).curr_durability, max);
+        }
+
+        // Undoing it restores the durability exactly, since the delta records how much was repaired
+        state.curr_durability = 25;
+        state.buffs.durability.manipulation = Manipulation::Inactive;
+        let delta = ImmaculateMend.act(&state).outcome();
+        let undone = (state + delta).undo(delta, state.buffs).unwrap();
+        assert_eq!(undone.curr_durability, state.curr_durability);
+        assert_eq!(undone.curr_cp, state.curr_cp);
+    }
+
+    #[test]
+    fn tricks_needs_good_condition() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        assert!(!TricksOfTheTrade.can_execute(&state));
+
+        for condition in [QARegularConditions::Good, QARegularConditions::Excellent] {
+            state.condition = condition;
+            assert!(TricksOfTheTrade.can_execute(&state));
+            assert_eq!(TricksOfTheTrade.cp_cost(&state), 20);
+            assert_eq!(
+                TricksOfTheTrade.durability(&state.buffs, &state.condition),
+                0
+            );
+        }
+
+        state.condition = QARegularConditions::Poor;
+        state.buffs.heart_and_soul.activate_in_place();
+        assert!(TricksOfTheTrade.can_execute(&state));
+    }
+
+    #[test]
+    fn tricks_off_condition_with_heart_and_soul() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.curr_cp -= 50;
+
+        // Not usable at all without Heart and Soul, so it gives nothing
+        assert_eq!(TricksOfTheTrade.cp_cost(&state), 0);
+
+        state.buffs.heart_and_soul.activate_in_place();
+        assert_eq!(TricksOfTheTrade.cp_cost(&state), 20);
+
+        let next = state + TricksOfTheTrade.act(&state).outcome();
+        assert_eq!(next.curr_cp, state.curr_cp + 20);
+        assert!(next.buffs.heart_and_soul.is_inactive());
+
+        // In a Good condition Heart and Soul is kept for later
+        state.condition = QARegularConditions::Good;
+        let next = state + TricksOfTheTrade.act(&state).outcome();
+        assert_eq!(next.curr_cp, state.curr_cp + 20);
+        assert!(next.buffs.heart_and_soul.is_active());
+    }
+}
@@ -6,7 +6,12 @@ use std::ops::{Add, AddAssign};
 use derivative::Derivative;
 use rand::Rng;
 
-use crate::{buffs::BuffState, conditions::Condition, quality_map::QualityMap, CraftingState};
+use crate::{
+    buffs::{Buff, BuffState, ConsumableBuff},
+    conditions::Condition,
+    quality_map::QualityMap,
+    CraftingState,
+};
 
 pub mod buffs;
 pub mod collection;
@@ -18,21 +23,11 @@ pub mod quality;
 
 use self::{
     buffs::BuffAction,
-    errors::{ActionError, ActionResult},
+    errors::{ActionError, ActionResult, UndoError},
     progress::ProgressAction,
     quality::QualityAction,
 };
 
-mod prelude {
-    pub use crate::{
-        actions::{
-            buffs::BuffAction, progress::ProgressAction, quality::QualityAction, Action,
-            CanExecute, CpCost, DurabilityFactor, RandomAction,
-        },
-        CraftingState,
-    };
-}
-
 /// A `delta` that encodes a change to a given [`CraftingState`]. It can be added to
 /// the state with the add operator to yield a new one (or modify it in-place with `+=`).
 ///
@@ -40,6 +35,7 @@ mod prelude {
 /// so it's up to the user to keep these values together.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StateDelta {
     added_quality: u32,
     added_progress: u32,
@@ -49,6 +45,8 @@ pub struct StateDelta {
     added_cp: i16,
     #[derivative(Default(value = "true"))]
     time_passed: bool,
+    #[derivative(Default(value = "true"))]
+    condition_changed: bool,
     final_appraisal_triggered: bool,
 }
 
@@ -62,6 +60,24 @@ impl StateDelta {
         }
     }
 
+    /// Whether the action this came from took up a step of the craft. If it didn't (e.g.
+    /// [`FinalAppraisal`]), the condition usually doesn't change either, see
+    /// [`condition_changed`](StateDelta::condition_changed).
+    ///
+    /// [`FinalAppraisal`]: crate::actions::buffs::FinalAppraisal
+    pub fn time_passed(&self) -> bool {
+        self.time_passed
+    }
+
+    /// Whether a new condition should be rolled after this delta. This is the same as
+    /// [`time_passed`](StateDelta::time_passed) except for actions like [`CarefulObservation`],
+    /// which stop time but still move the condition along.
+    ///
+    /// [`CarefulObservation`]: crate::actions::misc::CarefulObservation
+    pub fn condition_changed(&self) -> bool {
+        self.condition_changed
+    }
+
     /// Returns a version of `self` where the repair due to buffs is zerod out.
     ///
     /// Useful because [`Manipulation`]'s buff only repairs after a durability check.
@@ -102,6 +118,8 @@ where
                 .min(self.problem_def.recipe.max_durability),
             curr_cp: (self.curr_cp + other.added_cp).min(self.problem_def.character.max_cp),
             first_step: self.first_step && !other.time_passed,
+            #[cfg(feature = "step-count")]
+            steps: self.steps + other.time_passed as u16,
             ..*self
         }
     }
@@ -122,7 +140,78 @@ where
             .curr_durability
             .min(self.problem_def.recipe.max_durability);
         self.buffs = rhs.new_buffs;
-        self.first_step = self.first_step && !rhs.time_passed
+        self.first_step = self.first_step && !rhs.time_passed;
+        #[cfg(feature = "step-count")]
+        {
+            self.steps += rhs.time_passed as u16;
+        }
+    }
+}
+
+impl<'a, C, M> CraftingState<'a, C, M>
+where
+    C: Condition,
+    M: QualityMap,
+{
+    /// Reverses adding `delta` to a state, e.g. to step back through a rotation without
+    /// simulating it again from the start.
+    ///
+    /// A [`StateDelta`] only records the buffs *after* the action, so the buffs from before
+    /// it was taken have to be given as `prev_buffs`. Keep a copy of `state.buffs` from before
+    /// each action alongside its delta to be able to undo it.
+    ///
+    /// Repairs and CP gains that were capped at the maximum when the delta was added can't be
+    /// undone exactly, as the delta doesn't record how much was lost to the cap. Whether this was
+    /// the first step is inferred from the rest of the state being untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`UndoError`] if the delta can't have been added to this state, e.g. undoing
+    /// an action from the start state, rather than wrapping around or leaving stats out of range.
+    pub fn undo(&self, delta: StateDelta, prev_buffs: BuffState) -> Result<Self, UndoError> {
+        let curr_quality = self
+            .curr_quality
+            .checked_sub(delta.added_quality)
+            .ok_or(UndoError::QualityUnderflow)?;
+        let curr_progress = self
+            .curr_progress
+            .checked_sub(delta.added_progress)
+            .ok_or(UndoError::ProgressUnderflow)?;
+        let curr_durability = self
+            .curr_durability
+            .checked_sub(delta.buff_repair)
+            .and_then(|durability| durability.checked_sub(delta.action_durability))
+            .filter(|durability| (1..=self.problem_def.recipe.max_durability).contains(durability))
+            .ok_or(UndoError::DurabilityOutOfRange)?;
+        let curr_cp = self
+            .curr_cp
+            .checked_sub(delta.added_cp)
+            .filter(|cp| (0..=self.problem_def.character.max_cp).contains(cp))
+            .ok_or(UndoError::CpOutOfRange)?;
+
+        let mut prev = Self {
+            curr_quality,
+            curr_progress,
+            curr_durability,
+            curr_cp,
+            buffs: prev_buffs,
+            ..*self
+        };
+
+        prev.first_step = self.first_step
+            || (delta.time_passed
+                && prev.curr_quality == 0
+                && prev.curr_progress == 0
+                && prev.curr_durability == self.problem_def.recipe.max_durability
+                && prev.curr_cp == self.problem_def.character.max_cp
+                && prev.buffs == BuffState::default());
+
+        #[cfg(feature = "step-count")]
+        {
+            prev.steps = self.steps.saturating_sub(delta.time_passed as u16);
+        }
+
+        Ok(prev)
     }
 }
 
@@ -186,6 +275,27 @@ impl ActionOutcome {
         }
     }
 
+    /// The durability the item is left with once the turn is over, including any repair from
+    /// [`Manipulation`] (and capped at the recipe's maximum, like adding the delta to the state).
+    ///
+    /// This is [`None`] if the craft [completed](ActionOutcome::Completed) or
+    /// [failed](ActionOutcome::Failure), as the repair only happens after the durability check, so
+    /// an item that broke is never repaired.
+    ///
+    /// [`Manipulation`]: crate::buffs::durability::Manipulation
+    pub fn durability_after_repair<C: Condition, M: QualityMap>(
+        &self,
+        state: &CraftingState<C, M>,
+    ) -> Option<i8> {
+        match self {
+            Self::InProgress(delta) => Some(
+                (state.curr_durability + delta.action_durability + delta.buff_repair)
+                    .min(state.problem_def.recipe.max_durability),
+            ),
+            _ => None,
+        }
+    }
+
     /// Unrwaps the `outcome` value, this is not an [`Option`] because all variants contain an
     /// `outcome`.
     pub fn outcome(self) -> StateDelta {
@@ -259,20 +369,24 @@ pub trait Action: Sized + ActionComponents {
         delta.added_progress = appraised;
 
         delta.added_quality = self.quality(state);
-        delta.action_durability = self.durability(&state.buffs, &state.condition);
+        delta.action_durability = self.durability_in(state);
+        spend_trained_perfection(&self, state, &mut delta.new_buffs);
 
         self.deactivate_buff(state, &mut delta.new_buffs);
 
+        delta.condition_changed = self.changes_condition(state);
         if self.time_passed(state) {
             delta.new_buffs.decay();
+
+            // Repair isn't applied during a "time stop" so it's in here rather
+            // than after.
+            delta.buff_repair = state.buffs.durability.repair();
         } else {
+            delta.time_passed = false;
+
             // Combo actions still fail to trigger after using
             // time-agnostic actions
             delta.new_buffs.combo.decay();
-
-            // Repair isn't applied during a "time stop" so it's in the else rather
-            // than after.
-            delta.buff_repair = state.buffs.durability.repair();
         }
         self.buff(state, &mut delta.new_buffs);
 
@@ -285,8 +399,11 @@ pub trait Action: Sized + ActionComponents {
     /// executed (e.g. low CP or just a plain inexecutable action). If you want to speculatively
     /// execute, use [`Action::prospective_act`].
     ///
-    /// If you need to take into account an action's rng, use [`Action::act_random`] or
-    /// [`Action::act_and_fail`].
+    /// This never rolls for failure, so it's the outcome of the action succeeding no matter its
+    /// [`fail_rate`](RandomAction::fail_rate), the same as the success branch of
+    /// [`Action::act_and_fail`]. This makes it usable for deterministic best-case simulations
+    /// without a fake [`Rng`]. It doesn't roll the next condition either. If you need to take
+    /// into account an action's rng, use [`Action::act_random`] or [`Action::act_and_fail`].
     fn act<C, M>(self, state: &CraftingState<C, M>) -> ActionOutcome
     where
         C: Condition,
@@ -300,7 +417,7 @@ pub trait Action: Sized + ActionComponents {
         let can_act = self.can_execute(state);
 
         #[cfg(debug_assertions)]
-        match(state.curr_cp + delta.added_cp < 0, can_act) {
+        match(state.curr_cp + delta.added_cp < 0, !can_act) {
             (true, true) => panic!("Attempted to use action with not enough CP and \
             in a state where that action is impossible, to prospectively execute use `prospective_act`"),
             (true, false) => panic!("Attempted to use action with not enough CP, \
@@ -323,22 +440,24 @@ pub trait Action: Sized + ActionComponents {
         delta.added_progress = appraised;
 
         delta.added_quality = self.quality(state);
-        delta.action_durability = self.durability(&state.buffs, &state.condition);
-
-        delta.buff_repair = state.buffs.durability.repair();
+        delta.action_durability = self.durability_in(state);
+        spend_trained_perfection(&self, state, &mut delta.new_buffs);
 
         self.deactivate_buff(state, &mut delta.new_buffs);
 
+        delta.condition_changed = self.changes_condition(state);
         if self.time_passed(state) {
             delta.new_buffs.decay();
+
+            // Repair isn't applied during a "time stop" so it's in here rather
+            // than after.
+            delta.buff_repair = state.buffs.durability.repair();
         } else {
+            delta.time_passed = false;
+
             // Combo actions still fail to trigger after using
             // time-agnostic actions
             delta.new_buffs.combo.decay();
-
-            // Repair isn't applied during a "time stop" so it's in the else rather
-            // than after.
-            delta.buff_repair = state.buffs.durability.repair();
         }
         self.buff(state, &mut delta.new_buffs);
 
@@ -427,6 +546,34 @@ pub trait Action: Sized + ActionComponents {
     }
 }
 
+/// Consumes [`TrainedPerfection`] if `action` would have used durability without it. This is
+/// checked separately from [`DurabilityFactor::durability`], since with the buff active every
+/// action reports using no durability, whether it would have or not.
+///
+/// [`TrainedPerfection`]: crate::buffs::durability::TrainedPerfection
+fn spend_trained_perfection<A, C, M>(
+    action: &A,
+    state: &CraftingState<C, M>,
+    new_buffs: &mut BuffState,
+) where
+    A: DurabilityFactor,
+    C: Condition,
+    M: QualityMap,
+{
+    if !state.buffs.durability.trained_perfection.is_active() {
+        return;
+    }
+
+    let mut without = state.buffs;
+    without.durability.trained_perfection.deactivate_in_place();
+    if action.durability(&without, &state.condition) < 0 {
+        new_buffs
+            .durability
+            .trained_perfection
+            .deactivate_in_place();
+    }
+}
+
 /// A trait that denotes an action's ability to determine if it can execute in the current state.
 /// Note that this is specifically for actions such as [`MuscleMemory`] that can only be executed
 /// on the first action, CP execution availability is determined separately and you should not
@@ -451,7 +598,8 @@ pub trait CanExecute {
 /// a different amount of restoration or breakage should take place based on the current state.
 ///
 /// This takes into account the current [`Condition`] in expert crafting, as well as
-/// the [`WasteNot`](crate::buffs::durability::WasteNot) buff.
+/// the [`WasteNot`](crate::buffs::durability::WasteNot) and
+/// [`TrainedPerfection`](crate::buffs::durability::TrainedPerfection) buffs.
 ///
 /// Currently, aside from the constant, this should not need overriding for any current action
 /// and should monomorphize well.
@@ -461,7 +609,19 @@ pub trait DurabilityFactor {
     /// durability.
     const DURABILITY_USAGE: i8 = -10;
 
+    /// Whether this action restores durability all the way to the recipe's maximum, like
+    /// [`ImmaculateMend`], rather than by [`DURABILITY_USAGE`](DurabilityFactor::DURABILITY_USAGE).
+    /// How much that is depends on the current durability, so only
+    /// [`durability_in`](DurabilityFactor::durability_in) accounts for it.
+    ///
+    /// [`ImmaculateMend`]: crate::actions::misc::ImmaculateMend
+    const FULL_REPAIR: bool = false;
+
     /// Determines the amount of durability this action will restore or use given the current [`Condition`].
+    ///
+    /// This doesn't know the current durability, so it doesn't include a
+    /// [full repair](DurabilityFactor::FULL_REPAIR). Prefer
+    /// [`durability_in`](DurabilityFactor::durability_in) when there's a state to hand.
     fn durability<C>(&self, buffs: &BuffState, condition: &C) -> i8
     where
         C: Condition,
@@ -470,6 +630,10 @@ pub trait DurabilityFactor {
             return Self::DURABILITY_USAGE;
         }
 
+        if buffs.durability.trained_perfection.is_active() {
+            return 0;
+        }
+
         let condition_mod = condition.to_durability_modifier() as u64 as f64 / 100.;
         let buff_mod = buffs.durability.durability_cost_mod() as f64 / 100.;
 
@@ -484,6 +648,24 @@ pub trait DurabilityFactor {
         // from zero.
         (Self::DURABILITY_USAGE as f64 * condition_mod * buff_mod).floor() as i8
     }
+
+    /// The amount of durability this action will restore or use in the given state. This is
+    /// what's actually applied when the action is executed.
+    ///
+    /// This is [`durability`](DurabilityFactor::durability), except for actions with a
+    /// [full repair](DurabilityFactor::FULL_REPAIR), where it's however much durability the item
+    /// is missing.
+    fn durability_in<C, M>(&self, state: &CraftingState<C, M>) -> i8
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        if Self::FULL_REPAIR {
+            state.problem_def.recipe.max_durability - state.curr_durability
+        } else {
+            self.durability(&state.buffs, &state.condition)
+        }
+    }
 }
 
 /// Defines the amount of CP an [`Action`] uses. Like other action qualities, the
@@ -507,8 +689,12 @@ pub trait CpCost {
             return 0;
         }
 
+        // Restoring CP needs a Good or Excellent condition, or Heart and Soul to stand in for one
         if Self::CP_COST > 0 {
-            return if state.condition.is_good() || state.condition.is_excellent() {
+            return if state.condition.is_good()
+                || state.condition.is_excellent()
+                || state.buffs.heart_and_soul.is_active()
+            {
                 Self::CP_COST
             } else {
                 0
@@ -642,4 +828,60 @@ pub trait TimePassing {
     {
         Self::TIME_PASSED
     }
+
+    /// Whether the condition changes after this action. Almost always the same as
+    /// [`TIME_PASSED`], the exception being [`CarefulObservation`], which exists to move the
+    /// condition along without passing time.
+    ///
+    /// [`TIME_PASSED`]: TimePassing::TIME_PASSED
+    /// [`CarefulObservation`]: crate::actions::misc::CarefulObservation
+    const CHANGES_CONDITION: bool = Self::TIME_PASSED;
+
+    /// Currently just defers to [`CHANGES_CONDITION`], for the same reasons as
+    /// [`time_passed`](TimePassing::time_passed).
+    ///
+    /// [`CHANGES_CONDITION`]: TimePassing::CHANGES_CONDITION
+    #[allow(unused_variables)]
+    fn changes_condition<C, M>(&self, state: &CraftingState<C, M>) -> bool
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        Self::CHANGES_CONDITION
+    }
+}
+
+/// The compile-time constants of an action, gathered in one place. These are the unmodified
+/// values, e.g. [`cp_cost`](ActionMeta::cp_cost) is [`CP_COST`](CpCost::CP_COST) and doesn't
+/// account for the [`Condition`].
+///
+/// A collection deriving the action traits exposes these for each of its actions as a `METADATA`
+/// table, in the same order as `ALL`, which is handy for building lookup tables (e.g. for a UI)
+/// without dispatching to each action.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ActionMeta {
+    /// The name of this action in game, e.g. "Byregot's Blessing". Unlike the rest of these,
+    /// it's given by the collection rather than the action itself: the variant's name split into
+    /// words, unless the variant has an `#[ffxiv_name = "..."]` attribute.
+    pub name: &'static str,
+
+    /// The level this action is learned at, see [`ActionLevel::LEVEL`].
+    pub level: u16,
+
+    /// The CP this action costs (or restores, if positive), see [`CpCost::CP_COST`].
+    pub cp_cost: i16,
+
+    /// The durability this action uses (or restores, if positive), see
+    /// [`DurabilityFactor::DURABILITY_USAGE`].
+    pub durability: i8,
+
+    /// Whether this action restores durability to the recipe's maximum instead, see
+    /// [`DurabilityFactor::FULL_REPAIR`].
+    pub full_repair: bool,
+
+    /// The chance of this action failing, see [`RandomAction::FAIL_RATE`].
+    pub fail_rate: u8,
+
+    /// Whether this action passes time, see [`TimePassing::TIME_PASSED`].
+    pub time_passed: bool,
 }
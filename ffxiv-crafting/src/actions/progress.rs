@@ -18,9 +18,23 @@ pub trait ProgressAction {
     /// [`efficiency`]: ProgressAction::efficiency
     const EFFICIENCY: u16 = 0;
 
+    /// The action's efficiency for the character in `state`, before any modifiers. This is
+    /// [`EFFICIENCY`] unless the action gets stronger at higher levels, which the derive can
+    /// generate with e.g. `#[ffxiv_progress(efficiency = 100, efficiency_at = "31:120")]`.
+    ///
+    /// [`EFFICIENCY`]: ProgressAction::EFFICIENCY
+    #[allow(unused_variables)]
+    fn base_efficiency<C, M>(&self, state: &CraftingState<C, M>) -> u16
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        Self::EFFICIENCY
+    }
+
     /// Calculates the efficiency of the current action on the crafting state.
     /// By default this is simply the efficiency bonus granted buffs,
-    /// multiplied by the action's efficiency.
+    /// multiplied by the action's [base efficiency](ProgressAction::base_efficiency).
     fn efficiency<C, M>(&self, state: &CraftingState<C, M>) -> f64
     where
         C: Condition,
@@ -30,7 +44,7 @@ pub trait ProgressAction {
             return 0.;
         }
 
-        let efficiency = Self::EFFICIENCY + state.buffs.progress.bonus_efficiency();
+        let efficiency = self.base_efficiency(state) + state.buffs.progress.bonus_efficiency();
         let efficiency_mod = (100. + state.buffs.progress.efficiency_mod() as f64) / 100.;
 
         efficiency_mod * efficiency as f64
@@ -50,11 +64,11 @@ pub trait ProgressAction {
             return 0;
         }
 
-        let progress = state.base_progress();
+        let progress = state.floored_base_progress();
         let condition_mod = state.condition.to_progress_modifier() as u64 as f64 / 100.;
         let efficiency = self.efficiency(state);
 
-        (progress.floor() * condition_mod * efficiency) as u32
+        (progress as f64 * condition_mod * efficiency / 100.) as u32
     }
 }
 
@@ -63,69 +77,29 @@ use ffxiv_crafting_derive::*;
 /// The most basic progress-increasing ability. It has 120 efficiency (after level 31) and no cost,
 /// other than the standard 10 durability taken off.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
-#[derive(QualityAction, CpCost, DurabilityFactor)]
+#[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
 #[derive(CanExecute, BuffAction, ActionLevel, RandomAction, TimePassing, Action)]
 #[ffxiv_cp(cost = 0)]
+#[ffxiv_progress(efficiency = 100, efficiency_at = "31:120")]
 #[ffxiv_act_lvl(level = 1)]
 #[ffxiv_buff_act(synthesis)]
 pub struct BasicSynthesis;
 
-impl ProgressAction for BasicSynthesis {
-    const EFFICIENCY: u16 = 100;
-
-    fn efficiency<C, M>(&self, state: &CraftingState<C, M>) -> f64
-    where
-        C: Condition,
-        M: QualityMap,
-    {
-        let efficiency = if state.problem_def.character.char_level >= 31 {
-            120
-        } else {
-            Self::EFFICIENCY
-        };
-
-        let efficiency = efficiency + state.buffs.progress.bonus_efficiency();
-        let efficiency_mod = (100. + state.buffs.progress.efficiency_mod() as f64) / 100.;
-
-        efficiency_mod * efficiency as f64
-    }
-}
-
 /// A risky, CP-free synthesis move that's extremely efficient, with a bit over 4x the amount
 /// of efficiency as [`BasicSynthesis`]. However, it fails 50% of the time, simply damaging the item.
 ///
 /// That said, its expected value is still twice as efficient as [`BasicSynthesis`], before taking into account
 /// the power of consistently leveraging buffs.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
-#[derive(QualityAction, CpCost, DurabilityFactor)]
+#[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
 #[derive(CanExecute, BuffAction, ActionLevel, RandomAction, TimePassing, Action)]
 #[ffxiv_cp(cost = 0)]
+#[ffxiv_progress(efficiency = 250, efficiency_at = "63:500")]
 #[ffxiv_act_lvl(level = 9)]
 #[ffxiv_rand_act(fail_rate = 50)]
 #[ffxiv_buff_act(synthesis)]
 pub struct RapidSynthesis;
 
-impl ProgressAction for RapidSynthesis {
-    const EFFICIENCY: u16 = 250;
-
-    fn efficiency<C, M>(&self, state: &CraftingState<C, M>) -> f64
-    where
-        C: Condition,
-        M: QualityMap,
-    {
-        let efficiency = if state.problem_def.character.char_level >= 63 {
-            500
-        } else {
-            Self::EFFICIENCY
-        };
-
-        let efficiency = efficiency + state.buffs.progress.bonus_efficiency();
-        let efficiency_mod = (100. + state.buffs.progress.efficiency_mod() as f64) / 100.;
-
-        efficiency_mod * efficiency as f64
-    }
-}
-
 /// A powerful action that can only be used on the first step. It has 2.5x the efficiency of
 /// [`BasicSynthesis`], and adds a buff that will cause the next progress-increasing action
 /// to gain 100 bonus efficiency
@@ -142,34 +116,14 @@ pub struct MuscleMemory;
 /// An action that's only minorly more powerful than [`BasicSynthesis`], but
 /// also doesn't cost very much.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
-#[derive(QualityAction, CpCost, DurabilityFactor)]
+#[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
 #[derive(CanExecute, BuffAction, ActionLevel, RandomAction, TimePassing, Action)]
 #[ffxiv_cp(cost = 7)]
+#[ffxiv_progress(efficiency = 150, efficiency_at = "82:180")]
 #[ffxiv_act_lvl(level = 62)]
 #[ffxiv_buff_act(synthesis)]
 pub struct CarefulSynthesis;
 
-impl ProgressAction for CarefulSynthesis {
-    const EFFICIENCY: u16 = 150;
-
-    fn efficiency<C, M>(&self, state: &CraftingState<C, M>) -> f64
-    where
-        C: Condition,
-        M: QualityMap,
-    {
-        let efficiency = if state.problem_def.character.char_level >= 82 {
-            180
-        } else {
-            Self::EFFICIENCY
-        };
-
-        let efficiency = efficiency + state.buffs.progress.bonus_efficiency();
-        let efficiency_mod = (100. + state.buffs.progress.efficiency_mod() as f64) / 100.;
-
-        efficiency_mod * efficiency as f64
-    }
-}
-
 /// An cheap action with 200 efficiency, about ~1.7x that of [`BasicSynthesis`] that costs 5 CP,
 /// (so 50 extra efficiency for 2 less CP than [`CarefulSynthesis`]).
 ///
@@ -210,25 +164,34 @@ pub struct Groundwork;
 impl ProgressAction for Groundwork {
     const EFFICIENCY: u16 = 300;
 
-    fn efficiency<C, M>(&self, state: &CraftingState<C, M>) -> f64
+    fn base_efficiency<C, M>(&self, state: &CraftingState<C, M>) -> u16
     where
         C: Condition,
         M: QualityMap,
     {
-        let efficiency = if state.problem_def.character.char_level >= 86 {
+        if state.problem_def.character.char_level >= 86 {
             360
         } else {
             Self::EFFICIENCY
-        };
+        }
+    }
+
+    fn efficiency<C, M>(&self, state: &CraftingState<C, M>) -> f64
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        let efficiency = self.base_efficiency(state);
 
-        let durability = self.durability(&state.buffs, &state.condition);
-        let efficiency = if durability < state.curr_durability {
+        // Durability usage is negative
+        let durability = self.durability_in(state);
+        let efficiency = if state.curr_durability < -durability {
             efficiency / 2
         } else {
             efficiency
         };
 
-        let efficiency_mod = 100. + state.buffs.progress.efficiency_mod() as f64 / 100.;
+        let efficiency_mod = (100. + state.buffs.progress.efficiency_mod() as f64) / 100.;
 
         efficiency_mod * efficiency as f64
     }
@@ -288,3 +251,43 @@ impl CanExecute for PrudentSynthesis {
         state.buffs.durability.waste_not.is_inactive()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{buffs::DurationalBuff, test_util::CLASSICAL_SIMULATOR};
+
+    #[test]
+    fn efficiency_is_a_percentage() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let base = state.floored_base_progress();
+
+        // Basic Synthesis is 120% efficiency from level 31, and Veneration adds another 50%
+        assert_eq!(BasicSynthesis.progress(&state), base * 120 / 100);
+        state.buffs.progress.veneration.activate_in_place(0);
+        assert_eq!(BasicSynthesis.progress(&state), base * 180 / 100);
+    }
+
+    #[test]
+    fn groundwork_costs() {
+        use crate::actions::CpCost;
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let base = state.floored_base_progress();
+
+        // 18 CP and 20 durability for 300% efficiency, before its level 86 trait
+        assert_eq!(Groundwork.cp_cost(&state), -18);
+        assert_eq!(Groundwork.durability(&state.buffs, &state.condition), -20);
+        assert_eq!(Groundwork.progress(&state), base * 3);
+
+        // Still full strength with exactly enough durability, but halved with any less
+        state.curr_durability = 20;
+        assert_eq!(Groundwork.progress(&state), base * 3);
+        state.curr_durability = 10;
+        assert_eq!(Groundwork.progress(&state), base * 150 / 100);
+
+        // Buffs scale the halved efficiency too
+        state.buffs.progress.veneration.activate_in_place(0);
+        assert_eq!(Groundwork.progress(&state), base * 225 / 100);
+    }
+}
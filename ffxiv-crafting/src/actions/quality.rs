@@ -1,8 +1,8 @@
 //! Defines the effects on quality that actions have, as well as collects actions whose primary purpose is increasing the quality property.
 
 use crate::{
-    actions::{buffs::BuffAction, CanExecute, CpCost},
-    buffs::{combo::BasicTouchCombo, Buff, ConsumableBuff, DurationalBuff},
+    actions::{buffs::BuffAction, collection::FfxivCraftingActions, CanExecute, CpCost},
+    buffs::{combo::BasicTouchCombo, Buff, ConsumableBuff},
     conditions::Condition,
     quality_map::QualityMap,
     CraftingState,
@@ -20,6 +20,13 @@ pub trait QualityAction {
 
     /// Calculates the efficiency of the current action on the crafting state. By default this is simply the efficiency bonus granted buffs,
     /// multiplied by the action's efficiency.
+    ///
+    /// [`GreatStrides`] and [`Innovation`] are added together into a single modifier, while [`InnerQuiet`] is a
+    /// separate multiplier on top of that, e.g. [`BasicTouch`] with both buffs and 10 stacks is `100 * 2.5 * 2.0 = 500`.
+    ///
+    /// [`GreatStrides`]: crate::buffs::quality::GreatStrides
+    /// [`Innovation`]: crate::buffs::quality::Innovation
+    /// [`InnerQuiet`]: crate::buffs::quality::InnerQuiet
     fn efficiency<C, M>(&self, state: &CraftingState<C, M>) -> f64
     where
         C: Condition,
@@ -30,9 +37,9 @@ pub trait QualityAction {
         }
 
         let efficiency_mod = (100. + state.buffs.quality.efficiency_mod() as f64) / 100.;
+        let iq_mod = (100. + state.buffs.quality.inner_quiet.efficiency_bonus() as f64) / 100.;
 
-        efficiency_mod
-            * (Self::EFFICIENCY + state.buffs.quality.inner_quiet.efficiency_bonus()) as f64
+        efficiency_mod * iq_mod * Self::EFFICIENCY as f64
     }
 
     /// Returns the amount of quality that will be added by executing the given `action` in the current `state`.
@@ -49,11 +56,10 @@ pub trait QualityAction {
             return 0;
         }
 
-        let quality = state.base_quality();
-        let condition_mod = state.condition.to_quality_modifier() as u64 as f64 / 100.;
+        let quality = state.conditioned_base_quality();
         let efficiency = self.efficiency(state);
 
-        ((quality * condition_mod).floor() * efficiency) as u32
+        (quality as f64 * efficiency / 100.) as u32
     }
 }
 
@@ -69,18 +75,28 @@ use ffxiv_crafting_derive::*;
 pub struct BasicTouch;
 
 impl BuffAction for BasicTouch {
-    fn buff<C, M>(&self, _: &CraftingState<C, M>, so_far: &mut crate::buffs::BuffState)
+    fn buff<C, M>(&self, state: &CraftingState<C, M>, so_far: &mut crate::buffs::BuffState)
     where
         C: Condition,
         M: QualityMap,
     {
-        so_far.combo.basic_touch.activate(0);
+        so_far.combo.basic_touch = BasicTouchCombo::CHAIN.advance(
+            FfxivCraftingActions::BasicTouch,
+            state.buffs.combo.basic_touch,
+        );
         so_far.quality.inner_quiet += 1;
+
+        if so_far.quality.great_strides.is_active() {
+            so_far.quality.great_strides.deactivate_in_place();
+        }
     }
 }
 
 /// A risky, costless quality increasing move. Will do nothing but consume
-/// durability if it fails.
+/// durability if it fails. If it succeeds, it grants [`Expedience`] so [`DaringTouch`]
+/// can be used next.
+///
+/// [`Expedience`]: crate::buffs::quality::Expedience
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
 #[derive(ProgressAction, QualityAction, CpCost, DurabilityFactor)]
 #[derive(CanExecute, BuffAction, ActionLevel, RandomAction, TimePassing, Action)]
@@ -88,7 +104,7 @@ impl BuffAction for BasicTouch {
 #[ffxiv_quality(efficiency = 100)]
 #[ffxiv_act_lvl(level = 9)]
 #[ffxiv_rand_act(fail_rate = 40)]
-#[ffxiv_buff_act(touch)]
+#[ffxiv_buff_act(touch, activate = "quality.expedience")]
 pub struct HastyTouch;
 
 /// An inefficient quality increasing action. This becomes efficient if combo'd
@@ -101,14 +117,17 @@ pub struct HastyTouch;
 pub struct StandardTouch;
 
 impl CpCost for StandardTouch {
-    const CP_COST: i16 = 32;
+    const CP_COST: i16 = -32;
 
     fn cp_cost<C, M>(&self, state: &CraftingState<C, M>) -> i16
     where
         C: Condition,
         M: QualityMap,
     {
-        let cost = if matches!(state.buffs.combo.basic_touch, BasicTouchCombo::BasicTouch) {
+        let cost = if BasicTouchCombo::CHAIN.continues(
+            FfxivCraftingActions::StandardTouch,
+            state.buffs.combo.basic_touch,
+        ) {
             BasicTouch::CP_COST
         } else {
             Self::CP_COST
@@ -122,14 +141,19 @@ impl CpCost for StandardTouch {
 }
 
 impl BuffAction for StandardTouch {
-    fn buff<C, M>(&self, _: &CraftingState<C, M>, so_far: &mut crate::buffs::BuffState)
+    fn buff<C, M>(&self, state: &CraftingState<C, M>, so_far: &mut crate::buffs::BuffState)
     where
         C: Condition,
         M: QualityMap,
     {
         so_far.quality.inner_quiet += 1;
-        if matches!(so_far.combo.basic_touch, BasicTouchCombo::BasicTouch) {
-            so_far.combo.basic_touch = BasicTouchCombo::StandardTouch;
+        so_far.combo.basic_touch = BasicTouchCombo::CHAIN.advance(
+            FfxivCraftingActions::StandardTouch,
+            state.buffs.combo.basic_touch,
+        );
+
+        if so_far.quality.great_strides.is_active() {
+            so_far.quality.great_strides.deactivate_in_place();
         }
     }
 }
@@ -163,18 +187,13 @@ impl QualityAction for ByregotsBlessing {
         C: Condition,
         M: QualityMap,
     {
-        let efficiency_mod = 100. + state.buffs.quality.efficiency_mod() as f64 / 100.;
-        // This is technically stacks * 30 if you do the math but it's clearer both bonuses are being applied this way
-        //
-        // Further note: the patch notes note that efficiency bonus is limited to 300 but that seems natural.
-        // 200 from the Byregot-specific mechanic, and 100 from normal IQ. Need to verify if perhaps this limit
-        // is enforced somewhere else (e.g. if it applies after applying the mod below, or if this number maxes at
-        // 300 instead of 400).
-        let efficiency = 100.
-            + state.buffs.quality.inner_quiet.stacks() as f64 * 20.
-            + state.buffs.quality.inner_quiet.efficiency_bonus() as f64;
-
-        efficiency_mod * efficiency
+        let efficiency_mod = (100. + state.buffs.quality.efficiency_mod() as f64) / 100.;
+        let iq_mod = (100. + state.buffs.quality.inner_quiet.efficiency_bonus() as f64) / 100.;
+        // The patch notes note that efficiency is limited to 300, which is the 200 from the Byregot-specific
+        // mechanic at 10 stacks. The normal IQ bonus still applies on top of this, like any other touch.
+        let efficiency = 100. + state.buffs.quality.inner_quiet.stacks() as f64 * 20.;
+
+        efficiency_mod * iq_mod * efficiency
     }
 }
 
@@ -184,7 +203,11 @@ impl BuffAction for ByregotsBlessing {
         C: Condition,
         M: QualityMap,
     {
-        so_far.quality.inner_quiet.deactivate();
+        so_far.quality.inner_quiet.deactivate_in_place();
+
+        if so_far.quality.great_strides.is_active() {
+            so_far.quality.great_strides.deactivate_in_place();
+        }
     }
 }
 
@@ -226,6 +249,10 @@ impl BuffAction for PreciseTouch {
         M: QualityMap,
     {
         so_far.quality.inner_quiet += 2;
+
+        if so_far.quality.great_strides.is_active() {
+            so_far.quality.great_strides.deactivate_in_place();
+        }
     }
 }
 
@@ -353,16 +380,16 @@ impl CanExecute for TrainedEye {
 pub struct AdvancedTouch;
 
 impl CpCost for AdvancedTouch {
-    const CP_COST: i16 = 32;
+    const CP_COST: i16 = -32;
 
     fn cp_cost<C, M>(&self, state: &CraftingState<C, M>) -> i16
     where
         C: Condition,
         M: QualityMap,
     {
-        let cost = if matches!(
+        let cost = if BasicTouchCombo::CHAIN.continues(
+            FfxivCraftingActions::AdvancedTouch,
             state.buffs.combo.basic_touch,
-            BasicTouchCombo::StandardTouch
         ) {
             BasicTouch::CP_COST
         } else {
@@ -397,3 +424,234 @@ impl CanExecute for TrainedFinesse {
         state.buffs.quality.inner_quiet.stacks() == 10
     }
 }
+
+/// A quality action as strong as [`BasicTouch`] for a little more CP. Used right after
+/// [`BasicTouch`], it grants an extra [`InnerQuiet`] stack (for 2 in total), but doesn't get the
+/// CP discount [`StandardTouch`] does, and ends the combo.
+///
+/// [`InnerQuiet`]: crate::buffs::quality::InnerQuiet
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
+#[derive(ProgressAction, QualityAction, DurabilityFactor, CpCost)]
+#[derive(CanExecute, ActionLevel, RandomAction, TimePassing, Action)]
+#[ffxiv_quality(efficiency = 100)]
+#[ffxiv_act_lvl(level = 92)]
+#[ffxiv_cp(cost = 24)]
+pub struct RefinedTouch;
+
+impl BuffAction for RefinedTouch {
+    fn buff<C, M>(&self, state: &CraftingState<C, M>, so_far: &mut crate::buffs::BuffState)
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        so_far.quality.inner_quiet += 1;
+        if BasicTouchCombo::CHAIN.continues(
+            FfxivCraftingActions::RefinedTouch,
+            state.buffs.combo.basic_touch,
+        ) {
+            so_far.quality.inner_quiet += 1;
+        }
+        so_far.combo.basic_touch = BasicTouchCombo::CHAIN.advance(
+            FfxivCraftingActions::RefinedTouch,
+            state.buffs.combo.basic_touch,
+        );
+
+        if so_far.quality.great_strides.is_active() {
+            so_far.quality.great_strides.deactivate_in_place();
+        }
+    }
+}
+
+/// A costless quality action with 150 efficiency, but with the same 60% success rate as
+/// [`HastyTouch`], and only usable right after a successful [`HastyTouch`] (while
+/// [`Expedience`] is active).
+///
+/// [`Expedience`]: crate::buffs::quality::Expedience
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Default)]
+#[derive(ProgressAction, QualityAction, DurabilityFactor, CpCost)]
+#[derive(BuffAction, ActionLevel, RandomAction, TimePassing, Action)]
+#[ffxiv_quality(efficiency = 150)]
+#[ffxiv_act_lvl(level = 96)]
+#[ffxiv_cp(cost = 0)]
+#[ffxiv_rand_act(fail_rate = 40)]
+#[ffxiv_buff_act(touch, consume = "quality.expedience")]
+pub struct DaringTouch;
+
+impl CanExecute for DaringTouch {
+    fn can_execute<C, M>(&self, state: &CraftingState<C, M>) -> bool
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        state.buffs.quality.expedience.is_active()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{CLASSICAL_SIMULATOR, DAWNTRAIL_SIMULATOR};
+
+    #[test]
+    fn efficiency_is_a_percentage() {
+        use crate::buffs::quality::Innovation;
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let base = state.conditioned_base_quality();
+
+        // Basic Touch is 100% efficiency, and Innovation adds another 50%
+        assert_eq!(BasicTouch.quality(&state), base);
+        state.buffs.quality.innovation = Innovation::Active(4);
+        assert_eq!(BasicTouch.quality(&state), base * 150 / 100);
+    }
+
+    #[test]
+    fn touch_combo_costs() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        assert_eq!(StandardTouch.cp_cost(&state), -32);
+        assert_eq!(AdvancedTouch.cp_cost(&state), -32);
+
+        state.buffs.combo.basic_touch = BasicTouchCombo::BasicTouch;
+        assert_eq!(StandardTouch.cp_cost(&state), -18);
+
+        state.buffs.combo.basic_touch = BasicTouchCombo::StandardTouch;
+        assert_eq!(AdvancedTouch.cp_cost(&state), -18);
+    }
+
+    #[test]
+    fn touch_combo_chains() {
+        use crate::actions::Action;
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+
+        state += BasicTouch.act(&state).outcome();
+        assert_eq!(state.buffs.combo.basic_touch, BasicTouchCombo::BasicTouch);
+        assert_eq!(StandardTouch.cp_cost(&state), -18);
+
+        state += StandardTouch.act(&state).outcome();
+        assert_eq!(
+            state.buffs.combo.basic_touch,
+            BasicTouchCombo::StandardTouch
+        );
+        assert_eq!(AdvancedTouch.cp_cost(&state), -18);
+
+        state += AdvancedTouch.act(&state).outcome();
+        assert_eq!(state.buffs.combo.basic_touch, BasicTouchCombo::Inactive);
+    }
+
+    #[test]
+    fn strides_innovation_and_iq_stack() {
+        use crate::buffs::quality::{GreatStrides, InnerQuiet, Innovation};
+
+        // 2000 control at level 80 against an rlvl 403 recipe (1387 control, no level modifier)
+        // has a base quality of floor((2000 * 0.35 + 35) * 12000 / 11387) = 774
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        assert_eq!(BasicTouch.quality(&state), 774);
+
+        state.buffs.quality.inner_quiet = InnerQuiet::Active(10);
+        assert_eq!(BasicTouch.quality(&state), 1_548);
+
+        state.buffs.quality.inner_quiet = InnerQuiet::Inactive;
+        state.buffs.quality.great_strides = GreatStrides::Active(3);
+        state.buffs.quality.innovation = Innovation::Active(4);
+        // Great Strides and Innovation add up to +150%, not 2.0 * 1.5 = +200%
+        assert_eq!(BasicTouch.quality(&state), 1_935);
+
+        // Inner Quiet multiplies on top of both, rather than adding to the action's efficiency
+        // (which would give 774 * 2.5 * 2.0 all the same for Basic Touch, but not for others)
+        state.buffs.quality.inner_quiet = InnerQuiet::Active(10);
+        assert_eq!(BasicTouch.quality(&state), 3_870);
+        assert_eq!(StandardTouch.quality(&state), 4_837);
+
+        // Byregot's Blessing's own per-stack bonus is separate from the normal Inner Quiet bonus
+        assert_eq!(ByregotsBlessing.quality(&state), 11_610);
+
+        state.buffs.quality.great_strides = GreatStrides::Inactive;
+        state.buffs.quality.inner_quiet = InnerQuiet::Active(4);
+        assert_eq!(BasicTouch.quality(&state), 1_625);
+    }
+
+    #[test]
+    fn byregots_blessing_spends_inner_quiet() {
+        use crate::{actions::Action, buffs::quality::InnerQuiet};
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.quality.inner_quiet = InnerQuiet::Active(5);
+
+        state += ByregotsBlessing.act(&state).outcome();
+        assert_eq!(state.buffs.quality.inner_quiet, InnerQuiet::Inactive);
+        assert!(!ByregotsBlessing.can_execute(&state));
+    }
+
+    #[test]
+    fn refined_touch_stacks_after_basic_touch() {
+        use crate::{actions::Action, buffs::quality::InnerQuiet};
+
+        let mut state = CraftingState::new_simulation(&DAWNTRAIL_SIMULATOR);
+        state.buffs.quality.inner_quiet = InnerQuiet::Active(1);
+
+        let alone = state + RefinedTouch.act(&state).outcome();
+        assert_eq!(alone.buffs.quality.inner_quiet, InnerQuiet::Active(2));
+        assert_eq!(alone.curr_cp, state.curr_cp - 24);
+
+        let touched = state + BasicTouch.act(&state).outcome();
+        assert_eq!(RefinedTouch.cp_cost(&touched), -24);
+        let combo = touched + RefinedTouch.act(&touched).outcome();
+        assert_eq!(combo.buffs.quality.inner_quiet, InnerQuiet::Active(4));
+        assert_eq!(combo.buffs.combo.basic_touch, BasicTouchCombo::Inactive);
+    }
+
+    #[test]
+    fn refined_touch_consumes_great_strides() {
+        use crate::{actions::Action, buffs::quality::GreatStrides};
+
+        let mut state = CraftingState::new_simulation(&DAWNTRAIL_SIMULATOR);
+        state.buffs.quality.great_strides = GreatStrides::Active(3);
+
+        state += RefinedTouch.act(&state).outcome();
+        assert_eq!(state.buffs.quality.great_strides, GreatStrides::Inactive);
+    }
+
+    #[test]
+    fn daring_touch_needs_expedience() {
+        use crate::{
+            actions::{progress::BasicSynthesis, Action, RandomAction, RollOutcome},
+            buffs::quality::Expedience,
+        };
+
+        let state = CraftingState::new_simulation(&DAWNTRAIL_SIMULATOR);
+        assert!(!DaringTouch.can_execute(&state));
+
+        let [(_, RollOutcome::Failure(failed)), (_, RollOutcome::Success(hasty))] =
+            HastyTouch.act_and_fail(&state)
+        else {
+            unreachable!("act_and_fail always puts the failure first")
+        };
+
+        // Only a successful Hasty Touch grants Expedience
+        let failed = state + failed.outcome();
+        assert_eq!(failed.buffs.quality.expedience, Expedience::Inactive);
+        assert!(!DaringTouch.can_execute(&failed));
+
+        let hasty = state + hasty.outcome();
+        assert_eq!(hasty.buffs.quality.expedience, Expedience::Active(1));
+        assert!(DaringTouch.can_execute(&hasty));
+        assert_eq!(DaringTouch.fail_rate(&hasty), 40);
+        assert_eq!(DaringTouch.cp_cost(&hasty), 0);
+
+        let daring = hasty + DaringTouch.act(&hasty).outcome();
+        assert!(daring.curr_quality > hasty.curr_quality * 2);
+        assert_eq!(daring.buffs.quality.expedience, Expedience::Inactive);
+        assert!(!DaringTouch.can_execute(&daring));
+
+        // Even when it would outlast the step, Daring Touch still consumes it
+        let mut primed = hasty;
+        primed.buffs.quality.expedience = Expedience::Active(3);
+        let daring = primed + DaringTouch.act(&primed).outcome();
+        assert_eq!(daring.buffs.quality.expedience, Expedience::Inactive);
+
+        // Otherwise it only lasts until the next action
+        let waited = hasty + BasicSynthesis.act(&hasty).outcome();
+        assert!(!DaringTouch.can_execute(&waited));
+    }
+}
@@ -9,10 +9,68 @@ use std::ops::{Sub, SubAssign};
 use derivative::Derivative;
 
 use super::{Buff, DurationalBuff};
+use crate::actions::collection::FfxivCraftingActions;
+
+/// One link in a [`ComboChain`]: using `action` while the combo is in the `from` state puts it
+/// in the `to` state. A `from` of `None` means the action starts the chain no matter what state
+/// the combo was in.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ComboStep<S> {
+    /// The action that takes this step.
+    pub action: FfxivCraftingActions,
+
+    /// The state the combo has to be in for `action` to take this step, or `None` if `action`
+    /// starts the chain.
+    pub from: Option<S>,
+
+    /// The state the combo is in after this step.
+    pub to: S,
+}
+
+/// A declarative description of a combo, as the list of [`ComboStep`]s that advance it. Any
+/// action that isn't a step from the current state breaks the combo, leaving it in its
+/// default (inactive) state.
+///
+/// The chains the game actually uses are [`BasicTouchCombo::CHAIN`] and [`ObserveCombo::CHAIN`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ComboChain<S: 'static> {
+    /// Every step of the chain. If more than one step matches, the first wins.
+    pub steps: &'static [ComboStep<S>],
+}
+
+impl<S: Copy + Eq + Default> ComboChain<S> {
+    /// The step `action` would take from `current`, if any.
+    pub fn step(&self, action: FfxivCraftingActions, current: S) -> Option<&ComboStep<S>> {
+        self.steps
+            .iter()
+            .find(|step| step.action == action && step.from.is_none_or(|from| from == current))
+    }
+
+    /// The state the combo is in after using `action` from `current`.
+    pub fn advance(&self, action: FfxivCraftingActions, current: S) -> S {
+        self.step(action, current)
+            .map_or_else(S::default, |step| step.to)
+    }
+
+    /// Whether `action` continues the combo from `current`, as opposed to starting it
+    /// or not being part of it at all. This is when combo bonuses apply.
+    pub fn continues(&self, action: FfxivCraftingActions, current: S) -> bool {
+        self.step(action, current)
+            .is_some_and(|step| step.from.is_some())
+    }
+
+    /// Whether any step continues the combo from `current`, i.e. whether a follow-up action
+    /// would get its combo bonus. This is what actions that aren't one of the chain's steps
+    /// (such as ones defined outside this crate) use to join in as a follow-up.
+    pub fn can_continue(&self, current: S) -> bool {
+        self.steps.iter().any(|step| step.from == Some(current))
+    }
+}
 
 /// A collection of miscellaneous combo triggers that don't fit elsewhere.
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Default)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComboTriggers {
     pub basic_touch: BasicTouchCombo,
     pub observation: ObserveCombo,
@@ -41,13 +99,17 @@ impl ComboTriggers {
 
 /// Denotes the combo between [`BasicTouch`], [`StandardTouch`], and [`AdvancedTouch`]. Three moves
 /// that, when used after the previous one in the chain, inherit the low CP cost of [`BasicTouch`], but
-/// with increasing efficiency.
+/// with increasing efficiency. [`RefinedTouch`] can also follow [`BasicTouch`] instead, for an extra
+/// [`InnerQuiet`] stack.
 ///
+/// [`RefinedTouch`]: crate::actions::quality::RefinedTouch
+/// [`InnerQuiet`]: crate::buffs::quality::InnerQuiet
 /// [`BasicTouch`]: crate::actions::quality::BasicTouch
 /// [`StandardTouch`]: crate::actions::quality::StandardTouch
 /// [`AdvancedTouch`]: crate::actions::quality::StandardTouch
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BasicTouchCombo {
     /// [`BasicTouch`] was not used last turn and its combo is unavailable.
     ///
@@ -67,6 +129,40 @@ pub enum BasicTouchCombo {
     StandardTouch,
 }
 
+impl BasicTouchCombo {
+    /// [`BasicTouch`] into [`StandardTouch`] into [`AdvancedTouch`], or [`BasicTouch`] into
+    /// [`RefinedTouch`].
+    ///
+    /// [`BasicTouch`]: crate::actions::quality::BasicTouch
+    /// [`StandardTouch`]: crate::actions::quality::StandardTouch
+    /// [`AdvancedTouch`]: crate::actions::quality::AdvancedTouch
+    /// [`RefinedTouch`]: crate::actions::quality::RefinedTouch
+    pub const CHAIN: ComboChain<Self> = ComboChain {
+        steps: &[
+            ComboStep {
+                action: FfxivCraftingActions::BasicTouch,
+                from: None,
+                to: Self::BasicTouch,
+            },
+            ComboStep {
+                action: FfxivCraftingActions::StandardTouch,
+                from: Some(Self::BasicTouch),
+                to: Self::StandardTouch,
+            },
+            ComboStep {
+                action: FfxivCraftingActions::AdvancedTouch,
+                from: Some(Self::StandardTouch),
+                to: Self::Inactive,
+            },
+            ComboStep {
+                action: FfxivCraftingActions::RefinedTouch,
+                from: Some(Self::BasicTouch),
+                to: Self::Inactive,
+            },
+        ],
+    };
+}
+
 impl Buff for BasicTouchCombo {
     fn is_active(&self) -> bool {
         matches!(self, Self::BasicTouch | Self::StandardTouch)
@@ -104,6 +200,7 @@ impl SubAssign<u8> for BasicTouchCombo {
 /// [`FocusedSynthesis`]: crate::actions::progress::FocusedSynthesis
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObserveCombo {
     /// [`Observe`] was not used last turn and its combo is unavailable.
     ///
@@ -117,6 +214,33 @@ pub enum ObserveCombo {
     Active,
 }
 
+impl ObserveCombo {
+    /// [`Observe`] into either [`FocusedSynthesis`] or [`FocusedTouch`].
+    ///
+    /// [`Observe`]: crate::actions::misc::Observe
+    /// [`FocusedSynthesis`]: crate::actions::progress::FocusedSynthesis
+    /// [`FocusedTouch`]: crate::actions::quality::FocusedTouch
+    pub const CHAIN: ComboChain<Self> = ComboChain {
+        steps: &[
+            ComboStep {
+                action: FfxivCraftingActions::Observe,
+                from: None,
+                to: Self::Active,
+            },
+            ComboStep {
+                action: FfxivCraftingActions::FocusedSynthesis,
+                from: Some(Self::Active),
+                to: Self::Inactive,
+            },
+            ComboStep {
+                action: FfxivCraftingActions::FocusedTouch,
+                from: Some(Self::Active),
+                to: Self::Inactive,
+            },
+        ],
+    };
+}
+
 impl Buff for ObserveCombo {
     fn is_active(&self) -> bool {
         matches!(self, Self::Active)
@@ -145,3 +269,72 @@ impl SubAssign<u8> for ObserveCombo {
         *self = self.sub(rhs)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use FfxivCraftingActions::*;
+
+    #[test]
+    fn touch_chain() {
+        let chain = BasicTouchCombo::CHAIN;
+
+        let mut combo = BasicTouchCombo::default();
+        for (action, continues, to) in [
+            (BasicTouch, false, BasicTouchCombo::BasicTouch),
+            (StandardTouch, true, BasicTouchCombo::StandardTouch),
+            (AdvancedTouch, true, BasicTouchCombo::Inactive),
+        ] {
+            assert_eq!(chain.continues(action, combo), continues);
+            combo = chain.advance(action, combo);
+            assert_eq!(combo, to);
+        }
+
+        assert!(chain.continues(RefinedTouch, BasicTouchCombo::BasicTouch));
+        assert!(!chain.continues(RefinedTouch, BasicTouchCombo::StandardTouch));
+        assert_eq!(
+            chain.advance(RefinedTouch, BasicTouchCombo::BasicTouch),
+            BasicTouchCombo::Inactive
+        );
+
+        assert!(!chain.continues(StandardTouch, BasicTouchCombo::Inactive));
+        assert!(!chain.continues(AdvancedTouch, BasicTouchCombo::BasicTouch));
+        assert_eq!(
+            chain.advance(BasicTouch, BasicTouchCombo::StandardTouch),
+            BasicTouchCombo::BasicTouch
+        );
+        assert_eq!(
+            chain.advance(BasicSynthesis, BasicTouchCombo::BasicTouch),
+            BasicTouchCombo::Inactive
+        );
+    }
+
+    #[test]
+    fn observe_chain() {
+        let chain = ObserveCombo::CHAIN;
+
+        assert_eq!(
+            chain.advance(Observe, ObserveCombo::Inactive),
+            ObserveCombo::Active
+        );
+        assert_eq!(
+            chain.advance(Observe, ObserveCombo::Active),
+            ObserveCombo::Active
+        );
+
+        for action in [FocusedSynthesis, FocusedTouch] {
+            assert!(chain.continues(action, ObserveCombo::Active));
+            assert!(!chain.continues(action, ObserveCombo::Inactive));
+            assert_eq!(
+                chain.advance(action, ObserveCombo::Active),
+                ObserveCombo::Inactive
+            );
+        }
+
+        assert!(!chain.continues(Observe, ObserveCombo::Active));
+
+        assert!(chain.can_continue(ObserveCombo::Active));
+        assert!(!chain.can_continue(ObserveCombo::Inactive));
+    }
+}
@@ -4,15 +4,17 @@ use std::ops::{Sub, SubAssign};
 
 use derivative::Derivative;
 
-use super::{Buff, DurationalBuff};
+use super::{Buff, ConsumableBuff, DurationalBuff};
 
 /// A simple collection of all the durability buffs, for cleaner fields on simulation
 /// structs.
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Default)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DurabilityBuffs {
     pub manipulation: Manipulation,
     pub waste_not: WasteNot,
+    pub trained_perfection: TrainedPerfection,
 }
 
 impl DurabilityBuffs {
@@ -42,6 +44,11 @@ impl DurabilityBuffs {
 /// [`Manipulation`]: crate::actions::buffs::Manipulation
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedManipulation")
+)]
 pub enum Manipulation {
     /// This buff is currently not active and gives no benefit.
     #[derivative(Default)]
@@ -56,6 +63,12 @@ pub enum Manipulation {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(Manipulation via UncheckedManipulation {
+    Inactive;
+    Active(1..=super::max_duration::<Self>())
+});
+
 impl Manipulation {
     /// The amount of durability this repairs at the end of the turn
     pub const REPAIR_VALUE: i8 = 5;
@@ -85,6 +98,15 @@ impl DurationalBuff for Manipulation {
     }
 }
 
+impl ConsumableBuff for Manipulation {
+    fn deactivate(self) -> (Self, u8) {
+        match self {
+            Self::Active(val) => (Self::Inactive, val),
+            Self::Inactive => panic!("Attempt to deactivate inactive Manipulation"),
+        }
+    }
+}
+
 impl Sub<u8> for Manipulation {
     type Output = Self;
 
@@ -92,8 +114,8 @@ impl Sub<u8> for Manipulation {
         debug_assert_eq!(rhs, 1, "Buffs should only decrease their duration by 1");
 
         match self {
+            Self::Inactive | Self::Active(1) => Self::Inactive,
             Self::Active(val) => Self::Active(val - rhs),
-            Self::Inactive => Self::Inactive,
         }
     }
 }
@@ -116,6 +138,11 @@ impl SubAssign<u8> for Manipulation {
 /// [`WasteNot2`]: crate::actions::buffs::WasteNot2
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedWasteNot")
+)]
 pub enum WasteNot {
     /// This buff is currently not active and gives no benefit.
     #[derivative(Default)]
@@ -145,6 +172,13 @@ pub enum WasteNot {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(WasteNot via UncheckedWasteNot {
+    Inactive;
+    WasteNot(1..=super::max_duration::<Self>()),
+    WasteNot2(1..=super::max_duration::<Self>() + 4)
+});
+
 impl WasteNot {
     /// The raw discount applied to the durability, before dividing by 100.
     pub const DISCOUNT: u16 = 50;
@@ -190,24 +224,70 @@ impl Sub<u8> for WasteNot {
         debug_assert_eq!(rhs, 1, "Buffs should only decrease their duration by 1");
 
         match self {
+            Self::Inactive | Self::WasteNot(1) | Self::WasteNot2(1) => Self::Inactive,
             Self::WasteNot(ref mut val) | Self::WasteNot2(ref mut val) => {
-                *val -= 1;
+                *val -= rhs;
                 self
             }
-            Self::Inactive => Self::Inactive,
         }
     }
 }
 
 impl SubAssign<u8> for WasteNot {
     fn sub_assign(&mut self, rhs: u8) {
-        debug_assert_eq!(rhs, 1, "Buffs should only decrease their duration by 1");
+        *self = self.sub(rhs)
+    }
+}
+
+/// The buff associated with the action [`TrainedPerfection`], which makes the next action that
+/// would use durability use none at all. Unlike most buffs it doesn't wear off over time, and
+/// it can only be activated once per craft, so it also tracks whether it's been used up.
+///
+/// [`TrainedPerfection`]: crate::actions::buffs::TrainedPerfection
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Derivative)]
+#[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrainedPerfection {
+    /// This buff hasn't been activated yet this craft.
+    #[derivative(Default)]
+    Available,
+    /// This buff is active and the next action that uses durability will use none.
+    Active,
+    /// This buff has already been activated and consumed, and can't be activated again
+    /// this craft.
+    Spent,
+}
+
+impl TrainedPerfection {
+    /// Activates this buff. Similar to the variant in [`DurationalBuff`], but this buff
+    /// does not have a duration.
+    pub fn activate(self) -> Self {
+        Self::Active
+    }
 
+    /// Activates this buff and overwrites the current value.
+    /// Similar to the variant in [`DurationalBuff`], but this buff does not have a duration.
+    pub fn activate_in_place(&mut self) {
+        *self = Self::Active
+    }
+
+    /// Whether this buff can still be activated this craft.
+    pub fn is_available(self) -> bool {
+        matches!(self, Self::Available)
+    }
+}
+
+impl Buff for TrainedPerfection {
+    fn is_active(&self) -> bool {
+        matches!(self, Self::Active)
+    }
+}
+
+impl ConsumableBuff for TrainedPerfection {
+    fn deactivate(self) -> (Self, u8) {
         match self {
-            Self::WasteNot(ref mut val) | Self::WasteNot2(ref mut val) => {
-                *val -= 1;
-            }
-            _ => {}
+            Self::Active => (Self::Spent, 0),
+            _ => panic!("Attempt to deactivate inactive Trained Perfection"),
         }
     }
 }
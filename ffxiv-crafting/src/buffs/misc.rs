@@ -15,6 +15,11 @@ use super::{Buff, ConsumableBuff};
 /// [`CarefulObservation`]: crate::actions::misc::CarefulObservation
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedSpecialistActions")
+)]
 pub enum SpecialistActions {
     /// The crafter is not a specialist.
     #[derivative(Default)]
@@ -31,6 +36,12 @@ pub enum SpecialistActions {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(SpecialistActions via UncheckedSpecialistActions {
+    NotSpecialist, Unavailable;
+    Availalble(1..=3)
+});
+
 impl SpecialistActions {
     /// Returns if specialist actions can be used (i.e. there are enough delineations).
     pub fn actions_available(&self) -> bool {
@@ -41,6 +52,23 @@ impl SpecialistActions {
     pub fn actions_unavailable(&self) -> bool {
         matches!(self, Self::Unavailable | Self::NotSpecialist)
     }
+
+    /// Returns if the crafter is a specialist at all, whether or not they have any delineations.
+    pub fn is_specialist(&self) -> bool {
+        !matches!(self, Self::NotSpecialist)
+    }
+
+    /// Returns if a delineation was spent going from `self` to `after`, e.g. by an
+    /// action such as [`CarefulObservation`].
+    ///
+    /// [`CarefulObservation`]: crate::actions::misc::CarefulObservation
+    pub fn used_delineation(&self, after: &Self) -> bool {
+        match (self, after) {
+            (Self::Availalble(before), Self::Availalble(after)) => after < before,
+            (Self::Availalble(_), Self::Unavailable) => true,
+            _ => false,
+        }
+    }
 }
 
 impl Sub<u8> for SpecialistActions {
@@ -57,7 +85,8 @@ impl Sub<u8> for SpecialistActions {
             Self::Availalble(4..=u8::MAX) => {
                 panic!("Too many crafters delineations - we're constrained to 3 per craft.")
             }
-            Self::Availalble(val @ 1..=3) => Self::Availalble(val - 1),
+            Self::Availalble(1) => Self::Unavailable,
+            Self::Availalble(val @ 2..=3) => Self::Availalble(val - 1),
             Self::NotSpecialist => Self::NotSpecialist,
             Self::Unavailable => Self::Unavailable,
         }
@@ -78,6 +107,26 @@ impl SubAssign<u8> for SpecialistActions {
     }
 }
 
+/// Tracks whether [`QuickInnovation`] has been used yet, since it can only be used once per
+/// craft no matter how many delineations are left. Like [`SpecialistActions`], this isn't a
+/// buff in any real sense.
+///
+/// [`QuickInnovation`]: crate::actions::buffs::QuickInnovation
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Derivative)]
+#[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QuickInnovation {
+    /// [`QuickInnovation`] hasn't been used this craft.
+    ///
+    /// [`QuickInnovation`]: crate::actions::buffs::QuickInnovation
+    #[derivative(Default)]
+    Available,
+    /// [`QuickInnovation`] has already been used this craft.
+    ///
+    /// [`QuickInnovation`]: crate::actions::buffs::QuickInnovation
+    Used,
+}
+
 /// The buff associated with the action [`HeartAndSoul`],
 /// which allows actions such as [`TricksOfTheTrade`] to be executed even when the
 /// condition is not good or excellent.
@@ -87,6 +136,7 @@ impl SubAssign<u8> for SpecialistActions {
 /// [`TricksOfTheTrade`]: crate::actions::misc::TricksOfTheTrade
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeartAndSoul {
     /// This buff is currently not active and gives no benefit.
     #[derivative(Default)]
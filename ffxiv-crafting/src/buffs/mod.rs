@@ -5,11 +5,54 @@ use std::ops::{Sub, SubAssign};
 use self::{
     combo::ComboTriggers,
     durability::DurabilityBuffs,
-    misc::{HeartAndSoul, SpecialistActions},
+    misc::{HeartAndSoul, QuickInnovation, SpecialistActions},
     progress::ProgressBuffs,
     quality::QualityBuffs,
 };
 
+/// Deserializes a buff by way of an unchecked copy of it named `$unchecked`, rejecting values the
+/// buff could never have during a craft, such as a duration of zero or more stacks than it can
+/// hold. The buff needs `#[serde(try_from = "$unchecked")]` for this to be used.
+#[cfg(feature = "serde")]
+macro_rules! deserialize_checked {
+    ($buff:ident via $unchecked:ident { $($unit:ident),* ; $($variant:ident($range:expr)),+ $(,)? }) => {
+        #[derive(serde::Deserialize)]
+        enum $unchecked {
+            $($unit,)*
+            $($variant(u8),)+
+        }
+
+        impl TryFrom<$unchecked> for $buff {
+            type Error = String;
+
+            fn try_from(value: $unchecked) -> Result<Self, Self::Error> {
+                match value {
+                    $($unchecked::$unit => Ok(Self::$unit),)*
+                    $($unchecked::$variant(value) => {
+                        let range = $range;
+                        if range.contains(&value) {
+                            Ok(Self::$variant(value))
+                        } else {
+                            Err(format!(
+                                "{}::{}({value}) is outside of {range:?}",
+                                stringify!($buff),
+                                stringify!($variant),
+                            ))
+                        }
+                    })+
+                }
+            }
+        }
+    };
+}
+
+/// The most turns a durational buff can have, i.e. its base duration plus the bonus from
+/// [`Primed`](crate::conditions::RestoExpertConditions::Primed).
+#[cfg(feature = "serde")]
+const fn max_duration<B: DurationalBuff>() -> u8 {
+    B::BASE_DURATION + crate::lookups::StatusDurationModifier::Primed as u8
+}
+
 pub mod combo;
 pub mod durability;
 pub mod misc;
@@ -114,6 +157,7 @@ pub trait ConsumableBuff: Buff {
 /// buff management a bit less ugly
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Default)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BuffState {
     pub quality: QualityBuffs,
     pub progress: ProgressBuffs,
@@ -125,6 +169,10 @@ pub struct BuffState {
     /// trait just for modifying this. See the type documentation for more info on
     /// what this enum is for.
     pub specialist_actions: SpecialistActions,
+
+    /// Whether [`QuickInnovation`](crate::actions::buffs::QuickInnovation) has been used this
+    /// craft, which can only happen once.
+    pub quick_innovation: QuickInnovation,
 }
 
 impl BuffState {
@@ -135,4 +183,224 @@ impl BuffState {
         self.durability.decay();
         self.combo.decay();
     }
+
+    /// Replaces any durational buff with no turns remaining (e.g. `Veneration::Active(0)`) with
+    /// its inactive form, so buff states that mean the same thing compare (and hash) the same.
+    ///
+    /// Buffs that tick down on their own never end up with zero turns remaining, so this is only
+    /// needed for buff states that were built or edited by hand.
+    pub fn canonical(mut self) -> Self {
+        macro_rules! zero_is_inactive {
+            ($($field:ident).+, $($variant:path)|+) => {
+                if matches!(self.$($field).+, $($variant(0))|+) {
+                    self.$($field).+ = Default::default();
+                }
+            };
+        }
+
+        zero_is_inactive!(quality.great_strides, quality::GreatStrides::Active);
+        zero_is_inactive!(quality.innovation, quality::Innovation::Active);
+        zero_is_inactive!(quality.expedience, quality::Expedience::Active);
+        zero_is_inactive!(progress.veneration, progress::Veneration::Active);
+        zero_is_inactive!(progress.muscle_memory, progress::MuscleMemory::Active);
+        zero_is_inactive!(progress.final_appraisal, progress::FinalAppraisal::Active);
+        zero_is_inactive!(durability.manipulation, durability::Manipulation::Active);
+        zero_is_inactive!(
+            durability.waste_not,
+            durability::WasteNot::WasteNot | durability::WasteNot::WasteNot2
+        );
+
+        self
+    }
+
+    /// Lists every buff that's currently active, in the order they're declared in [`BuffName`].
+    pub fn active(&self) -> Vec<BuffName> {
+        let mut active = Vec::new();
+
+        macro_rules! check {
+            ($name:ident, $($field:ident).+) => {
+                if self.$($field).+.is_active() {
+                    active.push(BuffName::$name);
+                }
+            };
+        }
+
+        check!(InnerQuiet, quality.inner_quiet);
+        check!(GreatStrides, quality.great_strides);
+        check!(Innovation, quality.innovation);
+        check!(Expedience, quality.expedience);
+        check!(Veneration, progress.veneration);
+        check!(MuscleMemory, progress.muscle_memory);
+        check!(FinalAppraisal, progress.final_appraisal);
+        check!(Manipulation, durability.manipulation);
+        check!(WasteNot, durability.waste_not);
+        check!(TrainedPerfection, durability.trained_perfection);
+        check!(BasicTouchCombo, combo.basic_touch);
+        check!(ObserveCombo, combo.observation);
+        check!(HeartAndSoul, heart_and_soul);
+
+        active
+    }
+
+    /// Lists the buffs that were active in `self`, but that differ between `natural` (what
+    /// `self` looks like after simply letting time pass) and `after` (what `self` looks like
+    /// after an action was actually taken). That is, the buffs an action consumed or refreshed.
+    ///
+    /// Gaining [`InnerQuiet`] stacks isn't counted, only losing them is.
+    ///
+    /// [`InnerQuiet`]: self::quality::InnerQuiet
+    pub(crate) fn consumed_or_refreshed(&self, natural: &Self, after: &Self) -> Vec<BuffName> {
+        let mut changed = Vec::new();
+
+        macro_rules! check {
+            ($name:ident, $($field:ident).+) => {
+                if self.$($field).+.is_active() && natural.$($field).+ != after.$($field).+ {
+                    changed.push(BuffName::$name);
+                }
+            };
+        }
+
+        if self.quality.inner_quiet.is_active()
+            && after.quality.inner_quiet.stacks() < natural.quality.inner_quiet.stacks()
+        {
+            changed.push(BuffName::InnerQuiet);
+        }
+        check!(GreatStrides, quality.great_strides);
+        check!(Innovation, quality.innovation);
+        check!(Expedience, quality.expedience);
+        check!(Veneration, progress.veneration);
+        check!(MuscleMemory, progress.muscle_memory);
+        check!(FinalAppraisal, progress.final_appraisal);
+        check!(Manipulation, durability.manipulation);
+        check!(WasteNot, durability.waste_not);
+        check!(TrainedPerfection, durability.trained_perfection);
+        check!(BasicTouchCombo, combo.basic_touch);
+        check!(ObserveCombo, combo.observation);
+        check!(HeartAndSoul, heart_and_soul);
+
+        changed
+    }
+}
+
+/// Names every buff tracked by [`BuffState`], for reporting buff changes without
+/// exposing their internal state.
+///
+/// [`SpecialistActions`] and [`QuickInnovation`] aren't buffs in any real sense, so they have no
+/// name here.
+#[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[allow(missing_docs)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BuffName {
+    InnerQuiet,
+    GreatStrides,
+    Innovation,
+    Expedience,
+    Veneration,
+    MuscleMemory,
+    FinalAppraisal,
+    Manipulation,
+    WasteNot,
+    TrainedPerfection,
+    BasicTouchCombo,
+    ObserveCombo,
+    HeartAndSoul,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn zero_duration_is_inactive() {
+        let mut expired = BuffState::default();
+        expired.progress.veneration = progress::Veneration::Active(0);
+        expired.durability.waste_not = durability::WasteNot::WasteNot2(0);
+
+        assert_ne!(expired, BuffState::default());
+        assert_eq!(expired.canonical(), BuffState::default().canonical());
+
+        let mut active = BuffState::default();
+        active.quality.innovation = quality::Innovation::Active(1);
+        assert_eq!(active.canonical(), active);
+    }
+
+    #[test]
+    fn decay_never_leaves_zero_duration() {
+        use durability::Manipulation;
+        use progress::Veneration;
+
+        let mut buffs = BuffState::default();
+        buffs.progress.veneration = Veneration::Inactive.activate(0);
+        buffs.durability.manipulation = Manipulation::Inactive.activate(0);
+
+        for _ in 0..Veneration::BASE_DURATION.max(Manipulation::BASE_DURATION) {
+            buffs.decay();
+            assert_eq!(buffs.canonical(), buffs);
+        }
+
+        assert_eq!(buffs, BuffState::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let mut buffs = BuffState::default();
+        buffs.quality.inner_quiet = quality::InnerQuiet::Active(7);
+        buffs.quality.great_strides = quality::GreatStrides::Active(2);
+        buffs.quality.innovation = quality::Innovation::Active(3);
+        buffs.progress.veneration = progress::Veneration::Active(1);
+        buffs.durability.manipulation = durability::Manipulation::Active(5);
+        buffs.durability.waste_not = durability::WasteNot::WasteNot2(6);
+        buffs.combo.basic_touch = combo::BasicTouchCombo::BasicTouch;
+        buffs.specialist_actions = SpecialistActions::Availalble(2);
+
+        let json = serde_json::to_string(&buffs).unwrap();
+        let loaded: BuffState = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, buffs);
+        assert_eq!(loaded.quality.inner_quiet.stacks(), 7);
+        assert_eq!(
+            loaded.durability.manipulation,
+            durability::Manipulation::Active(5)
+        );
+
+        let active = buffs.active();
+        let json = serde_json::to_string(&active).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<BuffName>>(&json).unwrap(),
+            active
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_rejects_impossible_buffs() {
+        use durability::WasteNot;
+        use misc::SpecialistActions;
+        use quality::InnerQuiet;
+
+        assert!(serde_json::from_str::<WasteNot>(r#"{"WasteNot2":0}"#).is_err());
+        assert!(serde_json::from_str::<WasteNot>(r#"{"WasteNot":8}"#).is_err());
+        assert_eq!(
+            serde_json::from_str::<WasteNot>(r#"{"WasteNot2":8}"#).unwrap(),
+            WasteNot::WasteNot2(8)
+        );
+
+        assert!(serde_json::from_str::<InnerQuiet>(r#"{"Active":200}"#).is_err());
+        assert!(serde_json::from_str::<InnerQuiet>(r#"{"Active":0}"#).is_err());
+        assert_eq!(
+            serde_json::from_str::<InnerQuiet>(r#"{"Active":10}"#).unwrap(),
+            InnerQuiet::Active(10)
+        );
+
+        assert!(serde_json::from_str::<SpecialistActions>(r#"{"Availalble":0}"#).is_err());
+        assert!(serde_json::from_str::<SpecialistActions>(r#"{"Availalble":4}"#).is_err());
+        assert_eq!(
+            serde_json::from_str::<SpecialistActions>(r#""Unavailable""#).unwrap(),
+            SpecialistActions::Unavailable
+        );
+
+        let mut json = serde_json::to_value(BuffState::default()).unwrap();
+        json["progress"]["veneration"] = serde_json::json!({ "Active": 0 });
+        assert!(serde_json::from_value::<BuffState>(json).is_err());
+    }
 }
@@ -12,6 +12,7 @@ use super::{Buff, BuffState, ConsumableBuff, DurationalBuff};
 /// structs.
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Default)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProgressBuffs {
     pub veneration: Veneration,
     pub muscle_memory: MuscleMemory,
@@ -65,6 +66,11 @@ pub trait ProgressEfficiencyMod: DurationalBuff {
 /// [`progress`]: crate::actions::progress
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedVeneration")
+)]
 pub enum Veneration {
     /// This buff is currently not active and gives no benefit.
     #[derivative(Default)]
@@ -80,6 +86,12 @@ pub enum Veneration {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(Veneration via UncheckedVeneration {
+    Inactive;
+    Active(1..=super::max_duration::<Self>())
+});
+
 impl Buff for Veneration {
     fn is_active(&self) -> bool {
         matches!(self, Self::Active(_))
@@ -101,8 +113,8 @@ impl Sub<u8> for Veneration {
         debug_assert_eq!(rhs, 1, "Buffs should only decrease their duration by 1");
 
         match self {
+            Self::Inactive | Self::Active(1) => Self::Inactive,
             Self::Active(val) => Self::Active(val - rhs),
-            Self::Inactive => Self::Inactive,
         }
     }
 }
@@ -128,6 +140,11 @@ impl ProgressEfficiencyMod for Veneration {
 /// [`progress`]: crate::actions::progress
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedMuscleMemory")
+)]
 pub enum MuscleMemory {
     /// This buff is currently not active and gives no benefit.
     #[derivative(Default)]
@@ -144,6 +161,12 @@ pub enum MuscleMemory {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(MuscleMemory via UncheckedMuscleMemory {
+    Inactive;
+    Active(1..=super::max_duration::<Self>())
+});
+
 impl MuscleMemory {
     /// The bonus efficiency added onto the next synthesis action, when
     /// active.
@@ -190,8 +213,8 @@ impl Sub<u8> for MuscleMemory {
         debug_assert_eq!(rhs, 1, "Buffs should only decrease their duration by 1");
 
         match self {
+            Self::Inactive | Self::Active(1) => Self::Inactive,
             Self::Active(val) => Self::Active(val - rhs),
-            Self::Inactive => Self::Inactive,
         }
     }
 }
@@ -210,6 +233,11 @@ impl SubAssign<u8> for MuscleMemory {
 /// [`progress`]: crate::actions::progress
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedFinalAppraisal")
+)]
 pub enum FinalAppraisal {
     /// This buff is currently not active and gives no benefit.
     #[derivative(Default)]
@@ -226,6 +254,12 @@ pub enum FinalAppraisal {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(FinalAppraisal via UncheckedFinalAppraisal {
+    Inactive;
+    Active(1..=super::max_duration::<Self>())
+});
+
 impl FinalAppraisal {
     /// Compares progress computed during the most recent
     /// action execution stage to the progress needed to
@@ -285,8 +319,8 @@ impl Sub<u8> for FinalAppraisal {
         debug_assert_eq!(rhs, 1, "Buffs should only decrease their duration by 1");
 
         match self {
+            Self::Inactive | Self::Active(1) => Self::Inactive,
             Self::Active(val) => Self::Active(val - rhs),
-            Self::Inactive => Self::Inactive,
         }
     }
 }
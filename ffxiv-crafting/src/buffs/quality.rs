@@ -16,10 +16,12 @@ const MAX_IQ: u8 = 11;
 /// structs.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QualityBuffs {
     pub inner_quiet: InnerQuiet,
     pub great_strides: GreatStrides,
     pub innovation: Innovation,
+    pub expedience: Expedience,
 }
 
 impl QualityBuffs {
@@ -27,6 +29,7 @@ impl QualityBuffs {
     pub fn decay(&mut self) {
         self.great_strides.decay_in_place();
         self.innovation.decay_in_place();
+        self.expedience.decay_in_place();
     }
 
     /// Calculates the efficiency bonuses granted by these buffs.
@@ -65,6 +68,11 @@ pub trait QualityEfficiencyMod: DurationalBuff {
 /// [`ByregotsBlessing`]: crate::actions::quality::ByregotsBlessing
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedInnerQuiet")
+)]
 pub enum InnerQuiet {
     /// This buff is current not active and gives no benefit.
     #[derivative(Default)]
@@ -87,6 +95,12 @@ pub enum InnerQuiet {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(InnerQuiet via UncheckedInnerQuiet {
+    Inactive;
+    Active(1..=10)
+});
+
 impl InnerQuiet {
     /// Returns the [`InnerQuiet`] quality modifier, which is 20% per stack added on to
     /// the character's current `control`.
@@ -114,7 +128,8 @@ impl InnerQuiet {
         }
     }
 
-    /// Returns the additive bonus to efficiency granted by inner quiet
+    /// Returns the bonus to efficiency granted by inner quiet, as a percentage. This is a multiplier
+    /// on top of the action's efficiency and any other buffs, not an addition to the efficiency itself.
     pub fn efficiency_bonus(&self) -> u16 {
         (self.stacks() as u16) * 10
     }
@@ -199,6 +214,11 @@ impl DivAssign<u8> for InnerQuiet {
 /// [`GreatStrides`]: crate::actions::buffs::GreatStrides
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedGreatStrides")
+)]
 pub enum GreatStrides {
     /// This buff is currently not active and gives no benefit.
     #[derivative(Default)]
@@ -215,6 +235,12 @@ pub enum GreatStrides {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(GreatStrides via UncheckedGreatStrides {
+    Inactive;
+    Active(1..=super::max_duration::<Self>())
+});
+
 impl Buff for GreatStrides {
     fn is_active(&self) -> bool {
         matches!(self, Self::Active(_))
@@ -270,6 +296,11 @@ impl SubAssign<u8> for GreatStrides {
 /// [`Innovation`]: crate::actions::buffs::Innovation
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Derivative)]
 #[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedInnovation")
+)]
 pub enum Innovation {
     /// This buff is currently not active and gives no benefit.
     #[derivative(Default)]
@@ -285,6 +316,12 @@ pub enum Innovation {
     ),
 }
 
+#[cfg(feature = "serde")]
+deserialize_checked!(Innovation via UncheckedInnovation {
+    Inactive;
+    Active(1..=super::max_duration::<Self>())
+});
+
 impl Buff for Innovation {
     fn is_active(&self) -> bool {
         matches!(self, Self::Active(_))
@@ -321,3 +358,83 @@ impl SubAssign<u8> for Innovation {
         *self = self.sub(rhs)
     }
 }
+
+/// The buff granted by a successful [`HastyTouch`], which allows [`DaringTouch`] to be used on
+/// the next step. [`DaringTouch`] consumes it.
+///
+/// [`HastyTouch`]: crate::actions::quality::HastyTouch
+/// [`DaringTouch`]: crate::actions::quality::DaringTouch
+#[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Derivative)]
+#[derivative(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedExpedience")
+)]
+pub enum Expedience {
+    /// [`DaringTouch`] can't be used.
+    ///
+    /// [`DaringTouch`]: crate::actions::quality::DaringTouch
+    #[derivative(Default)]
+    Inactive,
+    /// [`DaringTouch`] can be used.
+    ///
+    /// [`DaringTouch`]: crate::actions::quality::DaringTouch
+    Active(
+        /// The number of turns remaining on this buff, once it hits
+        /// 0 this will become [`Inactive`]. As this is a [`ConsumableBuff`],
+        /// this will also become [`Inactive`] if [`DaringTouch`] is used.
+        ///
+        /// [`Inactive`]: Expedience::Inactive
+        /// [`DaringTouch`]: crate::actions::quality::DaringTouch
+        u8,
+    ),
+}
+
+#[cfg(feature = "serde")]
+deserialize_checked!(Expedience via UncheckedExpedience {
+    Inactive;
+    Active(1..=super::max_duration::<Self>())
+});
+
+impl Buff for Expedience {
+    fn is_active(&self) -> bool {
+        matches!(self, Self::Active(_))
+    }
+}
+
+impl DurationalBuff for Expedience {
+    const BASE_DURATION: u8 = 1;
+
+    fn activate(self, bonus: u8) -> Self {
+        Self::Active(Self::BASE_DURATION + bonus)
+    }
+}
+
+impl ConsumableBuff for Expedience {
+    fn deactivate(self) -> (Self, u8) {
+        match self {
+            Self::Active(duration) => (Self::Inactive, duration),
+            Self::Inactive => panic!("Attempt to consume Expedience when it's not active"),
+        }
+    }
+}
+
+impl Sub<u8> for Expedience {
+    type Output = Self;
+
+    fn sub(self, rhs: u8) -> Self::Output {
+        debug_assert_eq!(rhs, 1, "Buffs should only decrease their duration by 1");
+
+        match self {
+            Self::Inactive | Self::Active(1) => Self::Inactive,
+            Self::Active(val) => Self::Active(val - rhs),
+        }
+    }
+}
+
+impl SubAssign<u8> for Expedience {
+    fn sub_assign(&mut self, rhs: u8) {
+        *self = self.sub(rhs)
+    }
+}
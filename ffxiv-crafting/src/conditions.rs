@@ -4,11 +4,11 @@
 //!
 //! [`actions`]: crate::actions
 
-use std::error::Error;
+use std::{cmp::Ordering, error::Error};
 
 use derivative::Derivative;
 use ffxiv_crafting_derive::Condition;
-use rand::distributions::Distribution;
+use rand::{distributions::Distribution, Rng};
 
 use crate::lookups::{
     self, ConditionBits, CpUsageModifier, DurabilityModifier, ProgressModifier, QualityModifier,
@@ -22,11 +22,15 @@ pub mod raw_conditions {
 
     #[doc(inline)]
     pub use crate::lookups::{
-        CpUsageModifier, DurabilityModifier, ProgressModifier, QualityModifier,
+        CpUsageModifier, DurabilityModifier, ProgressModifier, QualityModifier, RawConditions,
         StatusDurationModifier, SuccessRateModifier,
     };
 }
 
+/// The chance of [`Excellent`](QARegularConditions::Excellent) following a normal condition
+/// in regular crafting, which Quality Assurance doesn't change.
+const EXCELLENT_RATE: u8 = 4;
+
 // // Two different types is sad, if we get the #[exhaustive_patterns] feature though
 // // we can create an unreachable variant that holds a PhantomData to QA/NoQA that defines
 // // the distribution
@@ -46,6 +50,7 @@ pub mod raw_conditions {
     Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Condition, Derivative
 )]
 #[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NoQARegularConditions {
     /// Normal condition -- nothing special.
     #[derivative(Default)]
@@ -77,6 +82,10 @@ pub enum NoQARegularConditions {
     Poor,
 }
 
+impl NoQARegularConditions {
+    const GOOD_RATE: u8 = 20;
+}
+
 impl Distribution<Self> for NoQARegularConditions {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Self {
         match self {
@@ -84,9 +93,9 @@ impl Distribution<Self> for NoQARegularConditions {
             Self::Excellent => Self::Poor,
             Self::Normal => {
                 let roll: u8 = rng.gen_range(0..100);
-                if roll < 20 {
+                if roll < Self::GOOD_RATE {
                     Self::Good
-                } else if roll < 20 + 4 {
+                } else if roll < Self::GOOD_RATE + EXCELLENT_RATE {
                     Self::Excellent
                 } else {
                     Self::Normal
@@ -96,15 +105,25 @@ impl Distribution<Self> for NoQARegularConditions {
     }
 }
 
+impl ConditionTransitions for NoQARegularConditions {
+    fn transitions(self) -> Vec<(Self, f64)> {
+        match self {
+            Self::Good | Self::Poor => vec![(Self::Normal, 1.)],
+            Self::Excellent => vec![(Self::Poor, 1.)],
+            Self::Normal => weighted_transitions(
+                &[Self::GOOD_RATE, EXCELLENT_RATE],
+                &[Self::Good, Self::Excellent],
+                Self::Normal,
+            ),
+        }
+    }
+}
+
 impl TryFrom<ConditionBits> for NoQARegularConditions {
     type Error = Box<dyn Error>;
 
     fn try_from(value: ConditionBits) -> Result<Self, Self::Error> {
-        if value.0 == lookups::NORMAL_CONDITIONS {
-            Ok(Self::default())
-        } else {
-            Err("Bits don't match this condition pattern".into())
-        }
+        default_if_kind(value, ConditionKind::Regular)
     }
 }
 
@@ -123,6 +142,7 @@ impl TryFrom<ConditionBits> for NoQARegularConditions {
     Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Condition, Derivative
 )]
 #[derivative(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QARegularConditions {
     /// Normal condition -- nothing special.
     #[derivative(Default)]
@@ -154,6 +174,10 @@ pub enum QARegularConditions {
     Poor,
 }
 
+impl QARegularConditions {
+    const GOOD_RATE: u8 = 20 + 5;
+}
+
 impl Distribution<Self> for QARegularConditions {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Self {
         match self {
@@ -161,9 +185,9 @@ impl Distribution<Self> for QARegularConditions {
             Self::Excellent => Self::Poor,
             Self::Normal => {
                 let roll: u8 = rng.gen_range(0..100);
-                if roll < 20 + 5 {
+                if roll < Self::GOOD_RATE {
                     Self::Good
-                } else if roll < 20 + 5 + 4 {
+                } else if roll < Self::GOOD_RATE + EXCELLENT_RATE {
                     Self::Excellent
                 } else {
                     Self::Normal
@@ -173,15 +197,25 @@ impl Distribution<Self> for QARegularConditions {
     }
 }
 
+impl ConditionTransitions for QARegularConditions {
+    fn transitions(self) -> Vec<(Self, f64)> {
+        match self {
+            Self::Good | Self::Poor => vec![(Self::Normal, 1.)],
+            Self::Excellent => vec![(Self::Poor, 1.)],
+            Self::Normal => weighted_transitions(
+                &[Self::GOOD_RATE, EXCELLENT_RATE],
+                &[Self::Good, Self::Excellent],
+                Self::Normal,
+            ),
+        }
+    }
+}
+
 impl TryFrom<ConditionBits> for QARegularConditions {
     type Error = Box<dyn Error>;
 
     fn try_from(value: ConditionBits) -> Result<Self, Self::Error> {
-        if value.0 == lookups::NORMAL_CONDITIONS {
-            Ok(Self::default())
-        } else {
-            Err("Bits don't match this condition pattern".into())
-        }
+        default_if_kind(value, ConditionKind::Regular)
     }
 }
 
@@ -204,6 +238,7 @@ impl TryFrom<ConditionBits> for QARegularConditions {
 )]
 #[derivative(Default)]
 #[ffxiv(expert)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RelicExpertConditions {
     /// Normal condition -- nothing special.
     #[derivative(Default)]
@@ -232,19 +267,22 @@ pub enum RelicExpertConditions {
     Sturdy,
 }
 
+impl RelicExpertConditions {
+    const WEIGHTS: [u8; 4] = [12, 15, 12, 15];
+    const RESULTS: [RelicExpertConditions; 4] = [
+        RelicExpertConditions::Good,
+        RelicExpertConditions::Centered,
+        RelicExpertConditions::Pliant,
+        RelicExpertConditions::Sturdy,
+    ];
+}
+
 impl Distribution<Self> for RelicExpertConditions {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Self {
-        const WEIGHTS: [u8; 4] = [12, 15, 12, 15];
-        const RESULTS: [RelicExpertConditions; 4] = [
-            RelicExpertConditions::Good,
-            RelicExpertConditions::Centered,
-            RelicExpertConditions::Pliant,
-            RelicExpertConditions::Sturdy,
-        ];
         let roll: u8 = rng.gen_range(0..100);
 
         let mut acc = 0;
-        for (weight, result) in WEIGHTS.iter().zip(RESULTS) {
+        for (weight, result) in Self::WEIGHTS.iter().zip(Self::RESULTS) {
             acc += weight;
             if roll < acc {
                 return result;
@@ -255,15 +293,17 @@ impl Distribution<Self> for RelicExpertConditions {
     }
 }
 
+impl ConditionTransitions for RelicExpertConditions {
+    fn transitions(self) -> Vec<(Self, f64)> {
+        weighted_transitions(&Self::WEIGHTS, &Self::RESULTS, Self::Normal)
+    }
+}
+
 impl TryFrom<ConditionBits> for RelicExpertConditions {
     type Error = Box<dyn Error>;
 
     fn try_from(value: ConditionBits) -> Result<Self, Self::Error> {
-        if value.0 == lookups::EXPERT_CRAFT_1 {
-            Ok(Self::default())
-        } else {
-            Err("Bits don't match this condition pattern".into())
-        }
+        default_if_kind(value, ConditionKind::RelicExpert)
     }
 }
 
@@ -284,6 +324,7 @@ impl TryFrom<ConditionBits> for RelicExpertConditions {
 )]
 #[derivative(Default)]
 #[ffxiv(expert)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RestoExpertConditions {
     /// Normal condition -- nothing special.
     #[derivative(Default)]
@@ -327,19 +368,22 @@ pub enum RestoExpertConditions {
     Primed,
 }
 
+impl RestoExpertConditions {
+    const WEIGHTS: [u8; 4] = [12, 12, 12, 12];
+    const RESULTS: [RestoExpertConditions; 4] = [
+        RestoExpertConditions::Good,
+        RestoExpertConditions::Pliant,
+        RestoExpertConditions::Malleable,
+        RestoExpertConditions::Primed,
+    ];
+}
+
 impl Distribution<Self> for RestoExpertConditions {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Self {
-        const WEIGHTS: [u8; 4] = [12, 12, 12, 12];
-        const RESULTS: [RestoExpertConditions; 4] = [
-            RestoExpertConditions::Good,
-            RestoExpertConditions::Pliant,
-            RestoExpertConditions::Malleable,
-            RestoExpertConditions::Primed,
-        ];
         let roll: u8 = rng.gen_range(0..100);
 
         let mut acc = 0;
-        for (weight, result) in WEIGHTS.iter().zip(RESULTS) {
+        for (weight, result) in Self::WEIGHTS.iter().zip(Self::RESULTS) {
             acc += weight;
             if roll < acc {
                 return result;
@@ -350,18 +394,63 @@ impl Distribution<Self> for RestoExpertConditions {
     }
 }
 
+impl ConditionTransitions for RestoExpertConditions {
+    fn transitions(self) -> Vec<(Self, f64)> {
+        weighted_transitions(&Self::WEIGHTS, &Self::RESULTS, Self::Normal)
+    }
+}
+
 impl TryFrom<ConditionBits> for RestoExpertConditions {
     type Error = Box<dyn Error>;
 
     fn try_from(value: ConditionBits) -> Result<Self, Self::Error> {
-        if value.0 == lookups::EXPERT_CRAFT_2 {
-            Ok(Self::default())
-        } else {
-            Err("Bits don't match this condition pattern".into())
+        default_if_kind(value, ConditionKind::RestoExpert)
+    }
+}
+
+/// The family of [`Condition`]s a recipe uses, as encoded in the game's condition flags. This tells
+/// you which [`Condition`] type to simulate a recipe with.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConditionKind {
+    /// Normal, Good, Excellent, and Poor. Whether this is [`QARegularConditions`] or
+    /// [`NoQARegularConditions`] depends on the character's level, not the recipe.
+    Regular,
+
+    /// [`RelicExpertConditions`]
+    RelicExpert,
+
+    /// [`RestoExpertConditions`]
+    RestoExpert,
+}
+
+/// Determines which family of conditions the given bits describe, or errors if they don't match
+/// any known set.
+impl TryFrom<ConditionBits> for ConditionKind {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: ConditionBits) -> Result<Self, Self::Error> {
+        match value.0 {
+            lookups::NORMAL_CONDITIONS => Ok(Self::Regular),
+            lookups::EXPERT_CRAFT_1 => Ok(Self::RelicExpert),
+            lookups::EXPERT_CRAFT_2 => Ok(Self::RestoExpert),
+            _ => Err("Bits don't match any known condition pattern".into()),
         }
     }
 }
 
+/// Shared implementation of the `TryFrom<ConditionBits>` impls, all of which start from their
+/// default condition.
+fn default_if_kind<C: Default>(
+    value: ConditionBits,
+    kind: ConditionKind,
+) -> Result<C, Box<dyn Error>> {
+    if ConditionKind::try_from(value)? == kind {
+        Ok(C::default())
+    } else {
+        Err("Bits don't match this condition pattern".into())
+    }
+}
+
 /// The general trait all conditions implement, this just maps the conditions
 /// to their modifiers in the internal condition tables. This is autoderived with
 /// a proc macro and most of the functions are self explanatory. The modifiers are all
@@ -382,4 +471,258 @@ pub trait Condition: Copy + Sized + Distribution<Self> {
     fn to_cp_usage_modifier(self) -> CpUsageModifier;
     fn is_good(self) -> bool;
     fn is_excellent(self) -> bool;
+
+    /// The bit this condition occupies in the game's condition flags. Unlike the variant order of
+    /// the implementing enum, this never changes, so it's used to give [`CraftingState`] a stable
+    /// ordering (see [`stable_cmp`]).
+    ///
+    /// [`CraftingState`]: crate::CraftingState
+    fn raw_bit(self) -> u16;
+
+    /// Generates the conditions of a craft `len` steps long, starting from the [`Default`]
+    /// (Normal) condition and sampling each following one from the last, as
+    /// [`CraftingState::gen_succ`] would. This is useful for replaying a rotation against the same
+    /// conditions several times. Time stopping actions don't take up a step, so they reuse the
+    /// condition of the step they're in, except for [`CarefulObservation`] which counts as one.
+    ///
+    /// [`CraftingState::gen_succ`]: crate::CraftingState::gen_succ
+    /// [`CarefulObservation`]: crate::actions::misc::CarefulObservation
+    fn sample_sequence<R: Rng + ?Sized>(len: usize, rng: &mut R) -> Vec<Self>
+    where
+        Self: Default,
+    {
+        std::iter::successors(Some(Self::default()), |prev| Some(prev.sample(rng)))
+            .take(len)
+            .collect()
+    }
+}
+
+/// Orders conditions by their [`raw_bit`](Condition::raw_bit), rather than by where they happen
+/// to be declared. This keeps the ordering of states (and thus tie-breaking in solvers)
+/// reproducible even if condition variants are reordered.
+pub fn stable_cmp<C: Condition>(a: &C, b: &C) -> Ordering {
+    a.raw_bit().cmp(&b.raw_bit())
+}
+
+/// [`stable_cmp`] for use in [`PartialOrd`] implementations.
+pub fn stable_partial_cmp<C: Condition>(a: &C, b: &C) -> Option<Ordering> {
+    Some(stable_cmp(a, b))
+}
+
+/// Exposes the exact distribution a [`Condition`] samples its successor from. This is for solvers
+/// that need to enumerate every condition that can follow the current one rather than roll one,
+/// and reads the same rates as the [`Distribution`] implementation.
+pub trait ConditionTransitions: Condition {
+    /// Lists the conditions that can follow this one, along with the probability of each
+    /// in `[0.0, 1.0]`. Conditions that can't follow this one are left out, and the probabilities
+    /// sum to `1.0`.
+    fn transitions(self) -> Vec<(Self, f64)>;
+
+    /// The chance of each special condition occurring from [`Default`] (Normal), e.g. for a legend
+    /// in a UI showing "Good: 25%, Excellent: 4%". Normal itself, and conditions that only follow
+    /// others (such as Poor), are left out.
+    fn display_probabilities() -> Vec<(Self, f64)>
+    where
+        Self: Default + PartialEq,
+    {
+        Self::default()
+            .transitions()
+            .into_iter()
+            .filter(|&(condition, p)| condition != Self::default() && p > 0.)
+            .collect()
+    }
+}
+
+/// Turns a table of weights out of 100 into transition probabilities, giving whatever is left over
+/// to `rest`.
+fn weighted_transitions<C: Copy>(weights: &[u8], results: &[C], rest: C) -> Vec<(C, f64)> {
+    let remaining = 100 - weights.iter().sum::<u8>();
+
+    weights
+        .iter()
+        .zip(results)
+        .map(|(weight, result)| (*result, *weight as f64 / 100.))
+        .chain(std::iter::once((rest, remaining as f64 / 100.)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::test_util::FixedRng;
+
+    /// Samples once with every possible roll and checks the tally matches [`transitions`].
+    ///
+    /// [`transitions`]: ConditionTransitions::transitions
+    fn check_transitions<C>(from: C)
+    where
+        C: ConditionTransitions + Eq + std::hash::Hash + std::fmt::Debug,
+    {
+        let mut sampled = HashMap::new();
+        for roll in 0..100 {
+            *sampled
+                .entry(from.sample(&mut FixedRng::new(roll)))
+                .or_insert(0u8) += 1;
+        }
+
+        let transitions = from.transitions();
+        assert!((transitions.iter().map(|(_, p)| p).sum::<f64>() - 1.).abs() < 1e-9);
+
+        let expected = transitions
+            .into_iter()
+            .filter(|(_, p)| *p > 0.)
+            .map(|(c, p)| (c, (p * 100.).round() as u8))
+            .collect::<HashMap<_, _>>();
+
+        assert_eq!(sampled, expected, "transitions from {:?}", from);
+    }
+
+    #[test]
+    fn condition_kinds_from_bits() {
+        for (bits, kind) in [
+            (lookups::NORMAL_CONDITIONS, ConditionKind::Regular),
+            (lookups::EXPERT_CRAFT_1, ConditionKind::RelicExpert),
+            (lookups::EXPERT_CRAFT_2, ConditionKind::RestoExpert),
+        ] {
+            assert_eq!(ConditionKind::try_from(ConditionBits(bits)).unwrap(), kind);
+        }
+
+        assert!(ConditionKind::try_from(ConditionBits(0)).is_err());
+        assert!(ConditionKind::try_from(ConditionBits(lookups::NORMAL_CONDITIONS | 0x20)).is_err());
+    }
+
+    #[test]
+    fn stable_ordering() {
+        use QARegularConditions as QA;
+        use RelicExpertConditions as Relic;
+        use RestoExpertConditions as Resto;
+
+        fn assert_sorted<C: Condition + std::fmt::Debug>(conditions: &[C]) {
+            for pair in conditions.windows(2) {
+                assert_eq!(stable_cmp(&pair[0], &pair[1]), Ordering::Less, "{:?}", pair);
+            }
+        }
+
+        assert_sorted(&[QA::Normal, QA::Good, QA::Excellent, QA::Poor]);
+        assert_sorted(&[
+            Relic::Normal,
+            Relic::Good,
+            Relic::Centered,
+            Relic::Pliant,
+            Relic::Sturdy,
+        ]);
+        assert_sorted(&[
+            Resto::Normal,
+            Resto::Good,
+            Resto::Pliant,
+            Resto::Sturdy,
+            Resto::Malleable,
+            Resto::Primed,
+        ]);
+    }
+
+    /// Checks every step of a long sampled sequence is a transition that can actually happen.
+    fn check_sequence<C>()
+    where
+        C: ConditionTransitions + Default + Eq + std::fmt::Debug,
+    {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let sequence = C::sample_sequence(500, &mut StdRng::seed_from_u64(3));
+        assert_eq!(sequence.len(), 500);
+        assert_eq!(sequence[0], C::default());
+
+        for pair in sequence.windows(2) {
+            assert!(
+                pair[0]
+                    .transitions()
+                    .iter()
+                    .any(|&(to, p)| to == pair[1] && p > 0.),
+                "{:?}",
+                pair
+            );
+        }
+    }
+
+    #[test]
+    fn sequences_follow_transitions() {
+        check_sequence::<NoQARegularConditions>();
+        check_sequence::<QARegularConditions>();
+        check_sequence::<RelicExpertConditions>();
+        check_sequence::<RestoExpertConditions>();
+
+        let sequence = QARegularConditions::sample_sequence(500, &mut FixedRng::lowest());
+        for pair in sequence.windows(2) {
+            match pair[0] {
+                QARegularConditions::Excellent => assert_eq!(pair[1], QARegularConditions::Poor),
+                QARegularConditions::Good | QARegularConditions::Poor => {
+                    assert_eq!(pair[1], QARegularConditions::Normal)
+                }
+                QARegularConditions::Normal => {}
+            }
+        }
+
+        assert!(QARegularConditions::sample_sequence(0, &mut FixedRng::lowest()).is_empty());
+    }
+
+    #[test]
+    fn display_probabilities() {
+        assert_eq!(
+            QARegularConditions::display_probabilities(),
+            vec![
+                (QARegularConditions::Good, 0.25),
+                (QARegularConditions::Excellent, 0.04)
+            ]
+        );
+        assert_eq!(
+            NoQARegularConditions::display_probabilities(),
+            vec![
+                (NoQARegularConditions::Good, 0.2),
+                (NoQARegularConditions::Excellent, 0.04)
+            ]
+        );
+
+        let resto = RestoExpertConditions::display_probabilities();
+        assert_eq!(resto.len(), 4);
+        assert!(resto.iter().all(|&(_, p)| p == 0.12));
+    }
+
+    #[test]
+    fn transitions_match_sampling() {
+        use NoQARegularConditions as NoQA;
+        use QARegularConditions as QA;
+        use RelicExpertConditions as Relic;
+        use RestoExpertConditions as Resto;
+
+        for from in [NoQA::Normal, NoQA::Good, NoQA::Excellent, NoQA::Poor] {
+            check_transitions(from);
+        }
+
+        for from in [QA::Normal, QA::Good, QA::Excellent, QA::Poor] {
+            check_transitions(from);
+        }
+
+        for from in [
+            Relic::Normal,
+            Relic::Good,
+            Relic::Centered,
+            Relic::Pliant,
+            Relic::Sturdy,
+        ] {
+            check_transitions(from);
+        }
+
+        for from in [
+            Resto::Normal,
+            Resto::Good,
+            Resto::Pliant,
+            Resto::Sturdy,
+            Resto::Malleable,
+            Resto::Primed,
+        ] {
+            check_transitions(from);
+        }
+    }
 }
@@ -0,0 +1,1418 @@
+//! Checks the tables hand-copied into the simulator against files datamined from the game, so
+//! they can be kept accurate across patches.
+//!
+//! The files are expected in the CSV format exported by tools like SaintCoinach, where a `#` row
+//! names the columns and any other rows that don't start with a number (such as the `key` and type
+//! rows) are skipped.
+//!
+//! The game's recipes can also be loaded from these files into a [`RecipeRegistry`], so a
+//! simulator can be set up from just the name of the item being crafted, with
+//! [`RecipeRegistry::simulator_for`]. The crafting-relevant parts of the game's items, such as
+//! whether they can be HQ, can be loaded with [`Item::from_csv`].
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display},
+};
+
+use crate::{
+    conditions::{
+        ConditionKind, NoQARegularConditions, QARegularConditions, RelicExpertConditions,
+        RestoExpertConditions,
+    },
+    difficulty::{difficulty_rating, Difficulty},
+    lookups::{LEVEL_MOD_PROGRESS, LEVEL_MOD_QUALITY},
+    quality_map::{CollectabilityMap, HQMap, QualityMap},
+    CharacterStats, CraftingSimulator, CraftingState, RecipeError, RecipeLevelRanges, RecipeStats,
+};
+
+/// The smallest level difference in the level modifier tables, any lower difference uses the
+/// same modifiers.
+const MIN_DIFFERENCE: i16 = -30;
+
+/// How many levels below a character a recipe can be while still being worth making for EXP.
+const LEVELING_RANGE: u8 = 5;
+
+/// The private-use character the game shows after the name of an HQ item.
+const HQ_ICON: char = '\u{e03c}';
+
+/// The key of the first Disciple of the Hand (Carpenter) in `ClassJob.csv`, which lists them in
+/// the same order as `CraftType.csv`.
+const CLASSJOB_OFFSET: u8 = 8;
+
+/// The level characters learn the Quality Assurance trait at, which makes Good conditions more
+/// likely in regular recipes.
+const QUALITY_ASSURANCE_LEVEL: u8 = 63;
+
+/// The progress and quality level modifiers for a single level difference.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LevelMods {
+    #[allow(missing_docs)]
+    pub progress: u16,
+    #[allow(missing_docs)]
+    pub quality: u16,
+}
+
+/// A level difference where the simulator's modifiers disagree with the datamined ones. Either
+/// side is [`None`] if it has no entry for that difference at all.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LevelModMismatch {
+    /// The character's level minus the recipe's, as in the game's `CraftLevelDifference` table.
+    pub difference: i16,
+
+    /// The modifiers the simulator uses.
+    pub hardcoded: Option<LevelMods>,
+
+    /// The modifiers from the game files.
+    pub datamined: Option<LevelMods>,
+}
+
+/// Writes the mismatch as a line of a diff, e.g. `-29: progress 82 -> 81, quality 64 -> 64`.
+impl Display for LevelModMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn side(mods: Option<LevelMods>, f: impl Fn(LevelMods) -> u16) -> String {
+            mods.map_or_else(|| "missing".to_owned(), |mods| f(mods).to_string())
+        }
+
+        write!(
+            f,
+            "{}: progress {} -> {}, quality {} -> {}",
+            self.difference,
+            side(self.hardcoded, |mods| mods.progress),
+            side(self.datamined, |mods| mods.progress),
+            side(self.hardcoded, |mods| mods.quality),
+            side(self.datamined, |mods| mods.quality),
+        )
+    }
+}
+
+/// Compares the level modifiers the simulator uses against the contents of the game's
+/// `CraftLevelDifference.csv`, which needs `Difference`, `ProgressFactor`, and `QualityFactor`
+/// columns. Returns every level difference where they disagree, in order, so an empty list means
+/// the simulator is up to date.
+pub fn diff_level_mods(csv: &str) -> Result<Vec<LevelModMismatch>, ParseDatamineError> {
+    let mut datamined = vec![];
+
+    for (line, [difference, progress, quality]) in
+        read_table(csv, ["Difference", "ProgressFactor", "QualityFactor"])?
+    {
+        let (Ok(difference), Ok(progress), Ok(quality)) =
+            (difference.parse(), progress.parse(), quality.parse())
+        else {
+            return Err(ParseDatamineError::Malformed { line });
+        };
+
+        datamined.push((difference, LevelMods { progress, quality }));
+    }
+
+    let hardcoded = LEVEL_MOD_PROGRESS
+        .into_iter()
+        .zip(LEVEL_MOD_QUALITY)
+        .zip(MIN_DIFFERENCE..)
+        .map(|((progress, quality), difference)| (difference, LevelMods { progress, quality }))
+        .collect::<Vec<_>>();
+
+    let lookup = |table: &[(i16, LevelMods)], difference| {
+        table
+            .iter()
+            .find(|(other, _)| *other == difference)
+            .map(|(_, mods)| *mods)
+    };
+
+    let mut differences = hardcoded
+        .iter()
+        .chain(&datamined)
+        .map(|(difference, _)| *difference)
+        .collect::<Vec<_>>();
+    differences.sort_unstable();
+    differences.dedup();
+
+    Ok(differences
+        .into_iter()
+        .map(|difference| LevelModMismatch {
+            difference,
+            hardcoded: lookup(&hardcoded, difference),
+            datamined: lookup(&datamined, difference),
+        })
+        .filter(|mismatch| mismatch.hardcoded != mismatch.datamined)
+        .collect())
+}
+
+/// A recipe from the game's files, as found in a [`RecipeRegistry`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DataminedRecipe {
+    /// The ID of the recipe itself, the key of its row in `Recipe.csv`.
+    pub recipe_id: u32,
+
+    /// The ID of the item the recipe makes, the key of its row in `Item.csv`.
+    pub item_id: u32,
+
+    /// The class that makes the recipe.
+    pub craft_type: CraftType,
+
+    /// The name of the item the recipe makes, or an empty string if the item wasn't found.
+    pub name: String,
+
+    /// The recipe's stats, ready to simulate.
+    pub recipe: RecipeStats,
+
+    /// The craftsmanship a character needs to start the recipe, or 0 if there's no requirement.
+    pub required_craftsmanship: u16,
+
+    /// The control a character needs to start the recipe, or 0 if there's no requirement.
+    pub required_control: u16,
+
+    /// Whether the game lists this as a secondary recipe for the item, as another class's recipe
+    /// for something that's mainly made by a different one.
+    pub is_secondary: bool,
+
+    /// Whether the item can come out HQ. Items that can't only care about quality for their
+    /// collectability, if at all.
+    pub can_hq: bool,
+
+    /// Whether making the recipe rewards the character with EXP.
+    pub exp_rewarded: bool,
+
+    /// The page of the crafting log the recipe is listed on, the key of its row in
+    /// `RecipeNotebookList.csv`.
+    pub notebook: u32,
+
+    /// The master recipe book that unlocks this recipe, the key of its row in
+    /// `SecretRecipeBook.csv`, or [`None`] if it doesn't need one.
+    pub secret_book: Option<u32>,
+}
+
+impl DataminedRecipe {
+    /// Every reason `character` can't make this recipe, or an empty list if they can.
+    ///
+    /// Whether the character can reach the recipe's
+    /// [required quality](RecipeStats::required_quality) can only be estimated without solving
+    /// the craft, so this is only reported if Basic Touch alone can't reach it, using whatever
+    /// durability and CP are left after completing the craft with Basic Synthesis, ignoring Inner
+    /// Quiet. Buffs can still make up the difference, so treat this one as a warning.
+    pub fn check_requirements(&self, character: &CharacterStats) -> Vec<RequirementFailure> {
+        const TOUCH_CP: u32 = 18;
+
+        let mut failures = vec![];
+
+        let required = self.recipe.required_character_level();
+        if character.char_level < required {
+            failures.push(RequirementFailure::Level {
+                required,
+                actual: character.char_level,
+            });
+        }
+        if character.craftsmanship < self.required_craftsmanship {
+            failures.push(RequirementFailure::Craftsmanship {
+                required: self.required_craftsmanship,
+                actual: character.craftsmanship,
+            });
+        }
+        if character.control < self.required_control {
+            failures.push(RequirementFailure::Control {
+                required: self.required_control,
+                actual: character.control,
+            });
+        }
+
+        // The estimate needs the character's level to be one the simulator knows about
+        let required_quality = self.recipe.required_quality();
+        if required_quality == 0
+            || !failures.is_empty()
+            || character.char_level > CharacterStats::MAX_LEVEL
+        {
+            return failures;
+        }
+
+        // The condition, quality map, and whether the character is a specialist make no
+        // difference to the base stats
+        let problem_def = CraftingSimulator::<_, HQMap>::new(
+            *character,
+            self.recipe,
+            QARegularConditions::Normal,
+        );
+        let state = CraftingState::new_specialist_simulation(&problem_def, 0);
+
+        let synths = (self.recipe.max_progress as f64 / state.base_progress()).ceil() as u32;
+        let actions = (self.recipe.max_durability.max(0) / 10) as u32;
+        let touches = actions
+            .saturating_sub(synths)
+            .min(character.max_cp.max(0) as u32 / TOUCH_CP);
+        let estimated = (touches as f64 * state.base_quality()) as u32;
+
+        if estimated < required_quality {
+            failures.push(RequirementFailure::QualityOutOfReach {
+                required: required_quality,
+                estimated,
+            });
+        }
+
+        failures
+    }
+
+    /// Whether this is a good recipe for `character` to level up with: it rewards EXP, its level
+    /// is no more than 5 below theirs, they meet its [requirements], and it isn't
+    /// [`Impossible`](Difficulty::Impossible) for them to complete. Specialist recipes never
+    /// count, and neither does anything for a character whose level is outside 1 to
+    /// [`MAX_LEVEL`](CharacterStats::MAX_LEVEL).
+    ///
+    /// [requirements]: DataminedRecipe::check_requirements
+    pub fn is_leveling_recipe(&self, character: &CharacterStats) -> bool {
+        (1..=CharacterStats::MAX_LEVEL).contains(&character.char_level)
+            && self.exp_rewarded
+            && !self.recipe.specialist_required()
+            && self.recipe.required_character_level() + LEVELING_RANGE >= character.char_level
+            && self.check_requirements(character).is_empty()
+            && difficulty_rating(self.recipe, *character) != Difficulty::Impossible
+    }
+}
+
+/// An item from the game's files, with just the columns of `Item.csv` that matter for crafting.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Item {
+    /// The ID of the item, the key of its row in `Item.csv`.
+    pub item_id: u32,
+
+    /// The item's name in game, without any HQ marker.
+    pub name: String,
+
+    /// The item level, which isn't related to the level of any recipe making it.
+    pub ilvl: u16,
+
+    /// Whether the item can come out HQ.
+    pub can_be_hq: bool,
+
+    /// Whether the item is a collectable, rated by its collectability rather than being HQ.
+    pub is_collectable: bool,
+
+    /// The class that repairs the item, or [`None`] if it can't be repaired.
+    pub repaired_by: Option<CraftType>,
+}
+
+impl Item {
+    /// Loads the items from the contents of the game's `Item.csv`, which needs `Name`,
+    /// `Level{Item}`, `CanBeHq`, `IsCollectable`, and `ClassJob{Repair}` columns. Unused rows,
+    /// which have no name, are skipped.
+    pub fn from_csv(items: &str) -> Result<Vec<Self>, ParseDatamineError> {
+        let mut parsed = vec![];
+        for (line, [item_id, name, ilvl, can_be_hq, is_collectable, repair]) in read_table(
+            items,
+            [
+                "#",
+                "Name",
+                "Level{Item}",
+                "CanBeHq",
+                "IsCollectable",
+                "ClassJob{Repair}",
+            ],
+        )? {
+            let malformed = ParseDatamineError::Malformed { line };
+            let item_id = item_id.parse::<u32>().map_err(|_| malformed)?;
+            let ilvl = ilvl.parse::<u16>().map_err(|_| malformed)?;
+            let can_be_hq = parse_bool(&can_be_hq).ok_or(malformed)?;
+            let is_collectable = parse_bool(&is_collectable).ok_or(malformed)?;
+            let repair = repair.parse::<u8>().map_err(|_| malformed)?;
+
+            if name.is_empty() {
+                continue;
+            }
+
+            parsed.push(Self {
+                item_id,
+                name,
+                ilvl,
+                can_be_hq,
+                is_collectable,
+                repaired_by: CraftType::from_classjob_id(repair),
+            });
+        }
+
+        Ok(parsed)
+    }
+
+    /// The item's name as the game shows it, with the HQ icon on the end if `hq` is set. This is
+    /// the form [`RecipeRegistry::get_by_name`] accepts as-is.
+    pub fn display_name(&self, hq: bool) -> String {
+        if hq {
+            format!("{}{}", self.name, HQ_ICON)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+/// A reason a character can't make a recipe, as found by [`DataminedRecipe::check_requirements`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(missing_docs)]
+pub enum RequirementFailure {
+    /// The character's level is below the recipe's required level.
+    Level { required: u8, actual: u8 },
+
+    /// The character's craftsmanship is below what's needed to start the recipe.
+    Craftsmanship { required: u16, actual: u16 },
+
+    /// The character's control is below what's needed to start the recipe.
+    Control { required: u16, actual: u16 },
+
+    /// The character likely can't reach the recipe's required quality, as Basic Touch alone only
+    /// gets to `estimated`.
+    QualityOutOfReach { required: u32, estimated: u32 },
+}
+
+impl Display for RequirementFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Level { required, actual } => write!(
+                f,
+                "The recipe requires level {}, but the character is level {}",
+                required, actual
+            ),
+            Self::Craftsmanship { required, actual } => write!(
+                f,
+                "The recipe requires {} craftsmanship, but the character has {}",
+                required, actual
+            ),
+            Self::Control { required, actual } => write!(
+                f,
+                "The recipe requires {} control, but the character has {}",
+                required, actual
+            ),
+            Self::QualityOutOfReach {
+                required,
+                estimated,
+            } => write!(
+                f,
+                "The recipe requires {} quality, but Basic Touch alone only reaches about {}",
+                required, estimated
+            ),
+        }
+    }
+}
+
+/// The Disciples of the Hand, i.e. which class makes a recipe.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CraftType {
+    #[allow(missing_docs)]
+    Carpenter,
+    #[allow(missing_docs)]
+    Blacksmith,
+    #[allow(missing_docs)]
+    Armorer,
+    #[allow(missing_docs)]
+    Goldsmith,
+    #[allow(missing_docs)]
+    Leatherworker,
+    #[allow(missing_docs)]
+    Weaver,
+    #[allow(missing_docs)]
+    Alchemist,
+    #[allow(missing_docs)]
+    Culinarian,
+}
+
+impl CraftType {
+    /// Every class, in the order of their keys in the game's `CraftType.csv`.
+    pub const ALL: [Self; 8] = [
+        Self::Carpenter,
+        Self::Blacksmith,
+        Self::Armorer,
+        Self::Goldsmith,
+        Self::Leatherworker,
+        Self::Weaver,
+        Self::Alchemist,
+        Self::Culinarian,
+    ];
+
+    /// The class with the given key in `CraftType.csv`, as used by the `CraftType` column of
+    /// `Recipe.csv`.
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    /// The class with the given key in `ClassJob.csv`, as used by the `ClassJob{Repair}` column
+    /// of `Item.csv`, or [`None`] if it's not a Disciple of the Hand.
+    pub fn from_classjob_id(id: u8) -> Option<Self> {
+        id.checked_sub(CLASSJOB_OFFSET).and_then(Self::from_id)
+    }
+}
+
+/// Looks up the game's recipes by the item they make, so a simulator can be set up without
+/// copying a recipe's stats by hand, or by where they're listed in the crafting log, or by the
+/// class that makes them.
+///
+/// Only recipes at a recipe level the simulator has data for are loaded. If several recipes make
+/// the same item (e.g. for different classes), looking them up by the item gives the first that
+/// isn't [secondary](DataminedRecipe::is_secondary), or the first of all if they're all secondary.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecipeRegistry {
+    recipes: Vec<DataminedRecipe>,
+    by_item_id: HashMap<u32, usize>,
+    by_name: HashMap<String, u32>,
+    by_notebook: HashMap<u32, Vec<usize>>,
+    by_secret_book: HashMap<u32, Vec<usize>>,
+}
+
+impl RecipeRegistry {
+    /// Loads the recipes from the contents of the game's `Recipe.csv`, which needs `CraftType`,
+    /// `Item{Result}`, `Amount{Result}`, `RecipeLevelTable`, `DifficultyFactor`, `QualityFactor`,
+    /// `DurabilityFactor`, `RequiredQuality`, `RequiredCraftsmanship`, `RequiredControl`,
+    /// `IsSecondary`, `IsSpecializationRequired`, `CanHq`, `ExpRewarded`,
+    /// `RecipeNotebookList`, and `SecretRecipeBook` columns, naming their results from `Item.csv`,
+    /// which needs a `Name` column.
+    pub fn from_csv(recipes: &str, items: &str) -> Result<Self, ParseDatamineError> {
+        let mut names = HashMap::new();
+        for (line, [id, name]) in read_table(items, ["#", "Name"])? {
+            let id = id
+                .parse::<u32>()
+                .map_err(|_| ParseDatamineError::Malformed { line })?;
+            names.insert(id, name);
+        }
+
+        let mut registry = Self::default();
+        for (line, fields) in read_table(
+            recipes,
+            [
+                "#",
+                "CraftType",
+                "Item{Result}",
+                "Amount{Result}",
+                "RecipeLevelTable",
+                "DifficultyFactor",
+                "QualityFactor",
+                "DurabilityFactor",
+                "RequiredQuality",
+                "RequiredCraftsmanship",
+                "RequiredControl",
+                "IsSecondary",
+                "IsSpecializationRequired",
+                "CanHq",
+                "ExpRewarded",
+                "RecipeNotebookList",
+                "SecretRecipeBook",
+            ],
+        )? {
+            let malformed = ParseDatamineError::Malformed { line };
+            let [recipe_id, craft_type, item_id, amount, rlvl, progress, quality, durability, required_quality, craftsmanship, control, secondary, specialist, can_hq, exp_rewarded, notebook, secret_book] =
+                fields;
+            let [recipe_id, item_id, notebook, secret_book] =
+                [recipe_id, item_id, notebook, secret_book]
+                    .map(|id| id.parse::<u32>().map_err(|_| malformed));
+            let (recipe_id, item_id, notebook, secret_book) =
+                (recipe_id?, item_id?, notebook?, secret_book?);
+            let craft_type = craft_type
+                .parse::<u8>()
+                .ok()
+                .and_then(CraftType::from_id)
+                .ok_or(malformed)?;
+            let amount = amount.parse::<u8>().map_err(|_| malformed)?;
+            let rlvl = rlvl.parse::<u16>().map_err(|_| malformed)?;
+            let [progress, quality, durability] = [progress, quality, durability]
+                .map(|factor| factor.parse::<u16>().map_err(|_| malformed));
+            let (progress, quality, durability) = (progress?, quality?, durability?);
+            let required_quality = required_quality.parse::<u32>().map_err(|_| malformed)?;
+            let [required_craftsmanship, required_control] =
+                [craftsmanship, control].map(|stat| stat.parse::<u16>().map_err(|_| malformed));
+            let (required_craftsmanship, required_control) =
+                (required_craftsmanship?, required_control?);
+            let is_secondary = parse_bool(&secondary).ok_or(malformed)?;
+            let specialist_required = parse_bool(&specialist).ok_or(malformed)?;
+            let can_hq = parse_bool(&can_hq).ok_or(malformed)?;
+            let exp_rewarded = parse_bool(&exp_rewarded).ok_or(malformed)?;
+
+            // Unused rows have no result
+            if item_id == 0 {
+                continue;
+            }
+            let Some(recipe_level) = RecipeLevelRanges::try_from_rlvl(rlvl) else {
+                continue;
+            };
+
+            let mut recipe = RecipeStats::from_factors(recipe_level, progress, quality, durability)
+                .map_err(|_| malformed)?
+                .with_result_quantity(amount)
+                .with_required_quality(required_quality);
+            if specialist_required {
+                recipe = recipe.with_specialist_required();
+            }
+
+            let name = names.get(&item_id).cloned().unwrap_or_default();
+            if !name.is_empty() {
+                registry
+                    .by_name
+                    .entry(name.to_lowercase())
+                    .or_insert(item_id);
+            }
+
+            let index = registry.recipes.len();
+            let preferred = registry.by_item_id.entry(item_id).or_insert(index);
+            if *preferred != index && !is_secondary && registry.recipes[*preferred].is_secondary {
+                *preferred = index;
+            }
+            registry
+                .by_notebook
+                .entry(notebook)
+                .or_default()
+                .push(index);
+            let secret_book = (secret_book != 0).then_some(secret_book);
+            if let Some(book) = secret_book {
+                registry.by_secret_book.entry(book).or_default().push(index);
+            }
+
+            registry.recipes.push(DataminedRecipe {
+                recipe_id,
+                item_id,
+                craft_type,
+                name,
+                recipe,
+                required_craftsmanship,
+                required_control,
+                is_secondary,
+                can_hq,
+                exp_rewarded,
+                notebook,
+                secret_book,
+            });
+        }
+
+        Ok(registry)
+    }
+
+    /// Finds the recipe that makes the item with the given name. This ignores case, as well as
+    /// any HQ marker on the end of the name (either ` HQ` or the game's HQ icon), so names
+    /// copied from the game work as-is.
+    pub fn get_by_name(&self, name: &str) -> Option<&DataminedRecipe> {
+        let name = name.trim().to_lowercase();
+        let name = name
+            .strip_suffix(HQ_ICON)
+            .or_else(|| name.strip_suffix(" hq"))
+            .unwrap_or(&name)
+            .trim_end();
+
+        self.by_name
+            .get(name)
+            .and_then(|id| self.get_by_item_id(*id))
+    }
+
+    /// Finds the recipe that makes the item with the given ID.
+    pub fn get_by_item_id(&self, item_id: u32) -> Option<&DataminedRecipe> {
+        self.by_item_id
+            .get(&item_id)
+            .map(|index| &self.recipes[*index])
+    }
+
+    /// The recipes listed on the given page of the crafting log (the key of its row in
+    /// `RecipeNotebookList.csv`), in the order they appear in `Recipe.csv`.
+    pub fn recipes_in_notebook(&self, notebook: u32) -> Vec<&DataminedRecipe> {
+        self.lookup_all(&self.by_notebook, notebook)
+    }
+
+    /// The recipes unlocked by the given master recipe book (the key of its row in
+    /// `SecretRecipeBook.csv`), in the order they appear in `Recipe.csv`.
+    pub fn recipes_in_book(&self, book: u32) -> Vec<&DataminedRecipe> {
+        self.lookup_all(&self.by_secret_book, book)
+    }
+
+    /// The recipes made by the given class, in the order they appear in `Recipe.csv`.
+    pub fn recipes_for_class(&self, craft_type: CraftType) -> Vec<&DataminedRecipe> {
+        self.recipes
+            .iter()
+            .filter(|recipe| recipe.craft_type == craft_type)
+            .collect()
+    }
+
+    fn lookup_all(&self, index: &HashMap<u32, Vec<usize>>, key: u32) -> Vec<&DataminedRecipe> {
+        index
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .map(|index| &self.recipes[*index])
+            .collect()
+    }
+
+    /// Sets up a simulator for `character` making the item with the given name (as found by
+    /// [`get_by_name`](RecipeRegistry::get_by_name)), with the [`Condition`] type the recipe
+    /// uses, and an [`HQMap`] or [`CollectabilityMap`] depending on whether the item can be HQ.
+    ///
+    /// Regular recipes use [`QARegularConditions`] once the character has learned Quality
+    /// Assurance, and [`NoQARegularConditions`] before that.
+    ///
+    /// [`Condition`]: crate::conditions::Condition
+    pub fn simulator_for(
+        &self,
+        name: &str,
+        character: CharacterStats,
+    ) -> Result<DataminedSimulator, SimulatorForError> {
+        let found = self
+            .get_by_name(name)
+            .ok_or(SimulatorForError::UnknownItem)?;
+
+        let simulator = if found.can_hq {
+            DataminedSimulator::Hq(ConditionedSimulator::checked_new(character, found.recipe)?)
+        } else {
+            DataminedSimulator::Collectable(ConditionedSimulator::checked_new(
+                character,
+                found.recipe,
+            )?)
+        };
+
+        Ok(simulator)
+    }
+
+    /// The number of recipes loaded, including every recipe for items made by several.
+    pub fn len(&self) -> usize {
+        self.recipes.len()
+    }
+
+    /// Whether no recipes were loaded at all.
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty()
+    }
+}
+
+/// A simulator for a datamined recipe, as set up by [`RecipeRegistry::simulator_for`], with the
+/// [`QualityMap`] picked by whether the item can be HQ.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DataminedSimulator {
+    /// The item can be HQ.
+    Hq(ConditionedSimulator<HQMap>),
+
+    /// The item can't be HQ, so quality only matters for its collectability.
+    Collectable(ConditionedSimulator<CollectabilityMap>),
+}
+
+/// A simulator with the [`Condition`] type picked to match the recipe (and, for regular recipes,
+/// the character).
+///
+/// [`Condition`]: crate::conditions::Condition
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConditionedSimulator<M>
+where
+    M: QualityMap,
+{
+    /// A regular recipe, for a character who hasn't learned Quality Assurance yet.
+    NoQARegular(CraftingSimulator<NoQARegularConditions, M>),
+
+    /// A regular recipe, for a character who has learned Quality Assurance.
+    QARegular(CraftingSimulator<QARegularConditions, M>),
+
+    /// An expert recipe using [`RelicExpertConditions`].
+    RelicExpert(CraftingSimulator<RelicExpertConditions, M>),
+
+    /// An expert recipe using [`RestoExpertConditions`].
+    RestoExpert(CraftingSimulator<RestoExpertConditions, M>),
+}
+
+impl<M> ConditionedSimulator<M>
+where
+    M: QualityMap,
+{
+    /// Sets up a [`checked`](CraftingSimulator::checked_new) simulator starting from the default
+    /// condition of whichever type `recipe` uses.
+    fn checked_new(character: CharacterStats, recipe: RecipeStats) -> Result<Self, RecipeError> {
+        Ok(match recipe.recipe_level.condition_kind() {
+            ConditionKind::Regular if character.char_level >= QUALITY_ASSURANCE_LEVEL => {
+                Self::QARegular(CraftingSimulator::checked_new(
+                    character,
+                    recipe,
+                    Default::default(),
+                )?)
+            }
+            ConditionKind::Regular => Self::NoQARegular(CraftingSimulator::checked_new(
+                character,
+                recipe,
+                Default::default(),
+            )?),
+            ConditionKind::RelicExpert => Self::RelicExpert(CraftingSimulator::checked_new(
+                character,
+                recipe,
+                Default::default(),
+            )?),
+            ConditionKind::RestoExpert => Self::RestoExpert(CraftingSimulator::checked_new(
+                character,
+                recipe,
+                Default::default(),
+            )?),
+        })
+    }
+}
+
+/// The reasons [`RecipeRegistry::simulator_for`] can't set up a simulator.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SimulatorForError {
+    /// There's no recipe for an item with that name.
+    UnknownItem,
+
+    /// The character can't make the recipe.
+    Recipe(RecipeError),
+}
+
+impl From<RecipeError> for SimulatorForError {
+    fn from(err: RecipeError) -> Self {
+        Self::Recipe(err)
+    }
+}
+
+impl Display for SimulatorForError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownItem => write!(f, "There's no recipe for an item with that name"),
+            Self::Recipe(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for SimulatorForError {}
+
+/// Reads a datamined boolean, which are written as `True` and `False`.
+fn parse_bool(field: &str) -> Option<bool> {
+    match field {
+        "True" => Some(true),
+        "False" => Some(false),
+        _ => None,
+    }
+}
+
+/// Reads the rows of data from a datamined table, along with the line each starts on, keeping only
+/// the fields from the columns in `names`, in that order. Using `#` as a name gives each row's
+/// key.
+fn read_table<const N: usize>(
+    csv: &str,
+    names: [&'static str; N],
+) -> Result<Vec<(usize, [String; N])>, ParseDatamineError> {
+    let mut rows = vec![];
+    let mut columns = None;
+
+    for (line, fields) in split_rows(csv) {
+        if fields.first().map(String::as_str) == Some("#") {
+            let mut found = [0; N];
+            for (column, name) in found.iter_mut().zip(names) {
+                *column = fields
+                    .iter()
+                    .position(|field| field == name)
+                    .ok_or(ParseDatamineError::MissingColumn(name))?;
+            }
+            columns = Some(found);
+            continue;
+        }
+
+        if fields[0].parse::<i64>().is_err() {
+            continue;
+        }
+
+        let columns = columns.ok_or(ParseDatamineError::MissingColumn(names[0]))?;
+        if columns.iter().any(|column| *column >= fields.len()) {
+            return Err(ParseDatamineError::Malformed { line });
+        }
+
+        rows.push((line, columns.map(|column| fields[column].clone())));
+    }
+
+    Ok(rows)
+}
+
+/// Splits CSV text into rows of trimmed fields, along with the line each row starts on. A quoted
+/// field can contain commas and line breaks, and `""` for a literal quote.
+fn split_rows(csv: &str) -> Vec<(usize, Vec<String>)> {
+    fn take(field: &mut String) -> String {
+        std::mem::take(field).trim().to_owned()
+    }
+
+    let mut rows = vec![];
+    let (mut fields, mut field) = (vec![], String::new());
+    let (mut line, mut start) = (1, 1);
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(take(&mut field)),
+            '\n' if !quoted => {
+                fields.push(take(&mut field));
+                rows.push((start, std::mem::take(&mut fields)));
+                line += 1;
+                start = line;
+            }
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(take(&mut field));
+        rows.push((start, fields));
+    }
+
+    rows
+}
+
+/// The reasons a datamined file can't be read.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ParseDatamineError {
+    /// There's no `#` row naming this column (before the first row of data).
+    MissingColumn(&'static str),
+
+    /// A row of data is missing a field, or has one that isn't a number (or `True` or `False`
+    /// for a boolean column), or whose value is out of range.
+    #[allow(missing_docs)]
+    Malformed { line: usize },
+}
+
+impl Display for ParseDatamineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColumn(name) => write!(f, "There's no \"{}\" column", name),
+            Self::Malformed { line } => write!(f, "Line {} isn't a row of numbers", line),
+        }
+    }
+}
+
+impl Error for ParseDatamineError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Checks the tables against the real `CraftLevelDifference.csv` in the directory named by the
+    /// `FFXIV_DATAMINE_DIR` environment variable, e.g. a SaintCoinach export, printing every
+    /// level difference where they disagree.
+    #[test]
+    #[ignore = "needs the game's files, from the directory in FFXIV_DATAMINE_DIR"]
+    fn datamined_tables_match() {
+        let dir = std::env::var("FFXIV_DATAMINE_DIR")
+            .expect("FFXIV_DATAMINE_DIR should name a directory of datamined CSVs");
+        let csv =
+            std::fs::read_to_string(std::path::Path::new(&dir).join("CraftLevelDifference.csv"))
+                .unwrap();
+
+        let diff = diff_level_mods(&csv).unwrap();
+        assert!(
+            diff.is_empty(),
+            "{}",
+            diff.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    /// Checks the recipe level tables against the real `RecipeLevelTable.csv` in the directory
+    /// named by `FFXIV_DATAMINE_DIR`, printing every recipe level where they disagree.
+    #[test]
+    #[ignore = "needs the game's files, from the directory in FFXIV_DATAMINE_DIR"]
+    fn datamined_recipe_levels_match() {
+        use crate::lookups::RecipeLevelIter;
+
+        let dir = std::env::var("FFXIV_DATAMINE_DIR")
+            .expect("FFXIV_DATAMINE_DIR should name a directory of datamined CSVs");
+        let csv = std::fs::read_to_string(std::path::Path::new(&dir).join("RecipeLevelTable.csv"))
+            .unwrap();
+
+        let mut datamined = HashMap::new();
+        for (_, [rlvl, craftsmanship, progress, quality, durability, conditions]) in read_table(
+            &csv,
+            [
+                "#",
+                "SuggestedCraftsmanship",
+                "Difficulty",
+                "Quality",
+                "Durability",
+                "ConditionsFlag",
+            ],
+        )
+        .unwrap()
+        {
+            let [craftsmanship, progress, quality, durability, conditions] =
+                [craftsmanship, progress, quality, durability, conditions]
+                    .map(|field| field.parse::<u32>().unwrap());
+            datamined.insert(
+                rlvl.parse::<u16>().unwrap(),
+                (craftsmanship, progress, quality, durability, conditions),
+            );
+        }
+
+        let mismatches = RecipeLevelIter::default()
+            .filter_map(|recipe| {
+                let hardcoded = (
+                    recipe.to_recipe_level_craftsmanship() as u32,
+                    recipe.to_recipe_level_progress(),
+                    recipe.to_recipe_level_quality(),
+                    recipe.to_recipe_level_durability() as u32,
+                    recipe.to_recipe_level_conditions().0 as u32,
+                );
+                let datamined = datamined.get(&recipe.to_recipe_level()).copied();
+                (datamined != Some(hardcoded)).then(|| {
+                    format!(
+                        "{:?} (rlvl {}): {:?} -> {:?}",
+                        recipe,
+                        recipe.to_recipe_level(),
+                        hardcoded,
+                        datamined
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+    }
+
+    /// The simulator's own tables, written out in the layout of `CraftLevelDifference.csv`.
+    fn hardcoded_csv() -> String {
+        let mut csv = "\
+key,0,1,2
+#,Difference,ProgressFactor,QualityFactor
+int32,int16,int16,int16
+"
+        .to_owned();
+
+        for (key, (progress, quality)) in LEVEL_MOD_PROGRESS
+            .into_iter()
+            .zip(LEVEL_MOD_QUALITY)
+            .enumerate()
+        {
+            let difference = MIN_DIFFERENCE + key as i16;
+            csv += &format!("{key},{difference},{progress},{quality}\n");
+        }
+
+        csv
+    }
+
+    #[test]
+    fn reports_mismatches() {
+        let csv = hardcoded_csv();
+        assert_eq!(diff_level_mods(&csv), Ok(vec![]));
+
+        let csv = csv
+            .replace("\n1,-29,82,64\n", "\n1,-29,81,64\n")
+            .replace("\n79,49,150,100\n", "\n79,49,150,100\n80,50,150,100\n");
+
+        let diff = diff_level_mods(&csv).unwrap();
+        assert_eq!(
+            diff,
+            [
+                LevelModMismatch {
+                    difference: -29,
+                    hardcoded: Some(LevelMods {
+                        progress: 82,
+                        quality: 64
+                    }),
+                    datamined: Some(LevelMods {
+                        progress: 81,
+                        quality: 64
+                    }),
+                },
+                LevelModMismatch {
+                    difference: 50,
+                    hardcoded: None,
+                    datamined: Some(LevelMods {
+                        progress: 150,
+                        quality: 100
+                    }),
+                },
+            ]
+        );
+        assert_eq!(
+            diff[0].to_string(),
+            "-29: progress 82 -> 81, quality 64 -> 64"
+        );
+        assert_eq!(
+            diff[1].to_string(),
+            "50: progress missing -> 150, quality missing -> 100"
+        );
+    }
+
+    const RECIPES: &str = "\
+key,0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15
+#,CraftType,RecipeLevelTable,Item{Result},Amount{Result},RecipeNotebookList,IsSecondary,DifficultyFactor,QualityFactor,DurabilityFactor,RequiredQuality,RequiredCraftsmanship,RequiredControl,SecretRecipeBook,CanHq,ExpRewarded,IsSpecializationRequired
+int32,CraftType,RecipeLevelTable,Item,byte,RecipeNotebookList,bit&01,uint16,uint16,uint16,uint32,uint16,uint16,SecretRecipeBook,bit&01,bit&01,bit&01
+0,0,0,0,0,0,False,0,0,0,0,0,0,0,False,False,False
+1,1,403,27693,1,1015,False,100,100,100,0,0,0,0,True,True,False
+2,2,403,27693,3,1055,True,50,50,50,0,0,0,0,True,True,False
+3,3,480,27694,2,1015,False,85,75,50,0,1800,1700,12,False,True,True
+4,4,800,36000,1,1015,False,100,100,100,0,0,0,12,True,True,False
+5,5,481,36001,1,1016,False,100,100,100,0,0,0,12,False,False,False
+6,6,20,36002,1,2,False,100,100,100,800,0,0,0,True,True,False
+";
+
+    const ITEMS: &str = "\
+key,0,1,2,3,4,5
+#,Description,Name,Level{Item},CanBeHq,IsCollectable,ClassJob{Repair}
+int32,str,str,uint16,bit&01,bit&01,ClassJob
+0,,,0,False,False,0
+27693,\"A sturdy, \"\"reliable\"\"
+saw.\",Ironwood Saw,430,True,False,9
+27694,,Rarefied Tincture,80,False,True,0
+36001,,Expert Rope,80,False,True,0
+36002,,Bronze Ingot,20,True,False,0
+";
+
+    #[test]
+    fn items() {
+        let items = Item::from_csv(ITEMS).unwrap();
+        assert_eq!(
+            items.iter().map(|item| item.item_id).collect::<Vec<_>>(),
+            [27693, 27694, 36001, 36002]
+        );
+
+        let saw = &items[0];
+        assert_eq!(saw.name, "Ironwood Saw");
+        assert_eq!(saw.ilvl, 430);
+        assert!(saw.can_be_hq);
+        assert!(!saw.is_collectable);
+        assert_eq!(saw.repaired_by, Some(CraftType::Blacksmith));
+        assert_eq!(saw.display_name(false), "Ironwood Saw");
+        assert_eq!(saw.display_name(true), "Ironwood Saw\u{e03c}");
+
+        let tincture = &items[1];
+        assert!(!tincture.can_be_hq);
+        assert!(tincture.is_collectable);
+        assert_eq!(tincture.repaired_by, None);
+
+        // Either way the name can be used to find the recipe
+        let registry = RecipeRegistry::from_csv(RECIPES, ITEMS).unwrap();
+        for hq in [false, true] {
+            assert_eq!(
+                registry.get_by_name(&saw.display_name(hq)).unwrap().item_id,
+                saw.item_id
+            );
+        }
+
+        assert_eq!(
+            Item::from_csv("#,Name,Level{Item}\n1,Saw,1"),
+            Err(ParseDatamineError::MissingColumn("CanBeHq"))
+        );
+        assert_eq!(
+            Item::from_csv(&ITEMS.replace("80,False,True,0", "80,False,1,0")),
+            Err(ParseDatamineError::Malformed { line: 7 })
+        );
+    }
+
+    #[test]
+    fn recipe_lookups() {
+        let registry = RecipeRegistry::from_csv(RECIPES, ITEMS).unwrap();
+        assert_eq!(registry.len(), 5);
+
+        let saw = registry.get_by_item_id(27693).unwrap();
+        assert_eq!(saw.recipe_id, 1);
+        assert_eq!(saw.craft_type, CraftType::Blacksmith);
+        assert_eq!(saw.name, "Ironwood Saw");
+        assert_eq!(
+            saw.recipe,
+            RecipeStats::from_factors(RecipeLevelRanges::from_rlvl(403), 100, 100, 100).unwrap()
+        );
+        assert_eq!(registry.get_by_name("Ironwood Saw"), Some(saw));
+        assert_eq!(registry.get_by_name("ironwood saw"), Some(saw));
+        assert_eq!(registry.get_by_name("Ironwood Saw HQ"), Some(saw));
+        assert_eq!(registry.get_by_name("Ironwood Saw\u{e03c}"), Some(saw));
+        assert!(!saw.is_secondary);
+        assert!(!saw.recipe.specialist_required());
+        assert!(saw.can_hq);
+
+        let tincture = registry.get_by_name("Rarefied Tincture").unwrap();
+        assert_eq!(tincture.recipe_id, 3);
+        assert_eq!(tincture.craft_type, CraftType::Goldsmith);
+        assert_eq!(tincture.item_id, 27694);
+        assert_eq!(tincture.recipe.result_quantity(), 2);
+        assert_eq!(tincture.recipe.max_durability, 35);
+        assert!(tincture.recipe.specialist_required());
+        assert!(!tincture.can_hq);
+
+        // Unknown items, or recipes at levels the simulator doesn't know about
+        assert_eq!(registry.get_by_name("Ironwood"), None);
+        assert_eq!(registry.get_by_item_id(36000), None);
+        assert_eq!(registry.get_by_item_id(0), None);
+    }
+
+    #[test]
+    fn prefers_primary_recipes() {
+        let recipes = RECIPES
+            .replace(",27693,1,1015,False,", ",27693,1,1015,True,")
+            .replace(",27693,3,1055,True,", ",27693,3,1055,False,");
+        let registry = RecipeRegistry::from_csv(&recipes, ITEMS).unwrap();
+        let saw = registry.get_by_name("Ironwood Saw").unwrap();
+        assert!(!saw.is_secondary);
+        assert_eq!(saw.recipe.result_quantity(), 3);
+
+        // With only secondary recipes, the first is kept
+        let recipes = recipes.replace(",27693,3,1055,False,", ",27693,3,1055,True,");
+        let registry = RecipeRegistry::from_csv(&recipes, ITEMS).unwrap();
+        let saw = registry.get_by_name("Ironwood Saw").unwrap();
+        assert!(saw.is_secondary);
+        assert_eq!(saw.recipe.result_quantity(), 1);
+    }
+
+    #[test]
+    fn recipes_by_book() {
+        let registry = RecipeRegistry::from_csv(RECIPES, ITEMS).unwrap();
+        let ids = |recipes: Vec<&DataminedRecipe>| {
+            recipes
+                .into_iter()
+                .map(|recipe| recipe.recipe_id)
+                .collect::<Vec<_>>()
+        };
+
+        // Recipes at levels the simulator doesn't know about are left out
+        assert_eq!(ids(registry.recipes_in_notebook(1015)), [1, 3]);
+        assert_eq!(ids(registry.recipes_in_notebook(1016)), [5]);
+        // Secondary recipes are listed too, even though lookups by item skip them
+        assert_eq!(ids(registry.recipes_in_notebook(1055)), [2]);
+        assert!(registry.recipes_in_notebook(0).is_empty());
+
+        assert_eq!(ids(registry.recipes_in_book(12)), [3, 5]);
+        assert!(registry.recipes_in_book(0).is_empty());
+        assert_eq!(
+            registry.get_by_item_id(27694).unwrap().secret_book,
+            Some(12)
+        );
+        assert_eq!(registry.get_by_item_id(27693).unwrap().secret_book, None);
+    }
+
+    #[test]
+    fn recipes_by_class() {
+        let registry = RecipeRegistry::from_csv(RECIPES, ITEMS).unwrap();
+        let ids = |craft_type| {
+            registry
+                .recipes_for_class(craft_type)
+                .into_iter()
+                .map(|recipe| recipe.recipe_id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(CraftType::Blacksmith), [1]);
+        assert_eq!(ids(CraftType::Alchemist), [6]);
+        assert!(ids(CraftType::Carpenter).is_empty());
+
+        assert_eq!(CraftType::from_id(0), Some(CraftType::Carpenter));
+        assert_eq!(CraftType::from_id(7), Some(CraftType::Culinarian));
+        assert_eq!(CraftType::from_id(8), None);
+    }
+
+    #[test]
+    fn requirements() {
+        let registry = RecipeRegistry::from_csv(RECIPES, ITEMS).unwrap();
+        let character = |craftsmanship, control, char_level| CharacterStats {
+            craftsmanship,
+            control,
+            max_cp: 500,
+            char_level,
+        };
+
+        let tincture = registry.get_by_name("Rarefied Tincture").unwrap();
+        assert_eq!(tincture.required_craftsmanship, 1800);
+        assert_eq!(tincture.required_control, 1700);
+        assert!(tincture
+            .check_requirements(&character(2000, 2000, 80))
+            .is_empty());
+        assert_eq!(
+            tincture.check_requirements(&character(1799, 1700, 79)),
+            [
+                RequirementFailure::Level {
+                    required: 80,
+                    actual: 79
+                },
+                RequirementFailure::Craftsmanship {
+                    required: 1800,
+                    actual: 1799
+                },
+            ]
+        );
+        assert_eq!(
+            tincture.check_requirements(&character(1800, 1000, 80)),
+            [RequirementFailure::Control {
+                required: 1700,
+                actual: 1000
+            }]
+        );
+
+        let ingot = registry.get_by_name("Bronze Ingot").unwrap();
+        assert_eq!(ingot.recipe.required_quality(), 800);
+        assert!(ingot
+            .check_requirements(&character(2000, 2000, 80))
+            .is_empty());
+        let weak = ingot.check_requirements(&character(50, 50, 20));
+        let [RequirementFailure::QualityOutOfReach {
+            required: 800,
+            estimated,
+        }] = weak[..]
+        else {
+            panic!("expected the quality to be out of reach, got {:?}", weak);
+        };
+        assert!(estimated < 800);
+        assert_eq!(
+            weak[0].to_string(),
+            format!(
+                "The recipe requires 800 quality, but Basic Touch alone only reaches about {}",
+                estimated
+            )
+        );
+    }
+
+    #[test]
+    fn leveling_recipes() {
+        let registry = RecipeRegistry::from_csv(RECIPES, ITEMS).unwrap();
+        let character = |char_level| CharacterStats {
+            craftsmanship: 2500,
+            control: 2500,
+            max_cp: 600,
+            char_level,
+        };
+
+        let saw = registry.get_by_name("Ironwood Saw").unwrap();
+        assert!(saw.exp_rewarded);
+        assert!(saw.is_leveling_recipe(&character(75)));
+        assert!(saw.is_leveling_recipe(&character(80)));
+        // Too far below the character to be worth it
+        assert!(!saw.is_leveling_recipe(&character(81)));
+        // Or too far above
+        assert!(!saw.is_leveling_recipe(&character(74)));
+        // Or too hard to finish
+        assert!(!saw.is_leveling_recipe(&CharacterStats {
+            craftsmanship: 10,
+            ..character(75)
+        }));
+
+        let rope = registry.get_by_name("Expert Rope").unwrap();
+        assert!(!rope.exp_rewarded);
+        assert!(!rope.is_leveling_recipe(&character(80)));
+
+        // Specialist recipes never count
+        let tincture = registry.get_by_name("Rarefied Tincture").unwrap();
+        assert!(tincture.exp_rewarded);
+        assert!(!tincture.is_leveling_recipe(&character(80)));
+
+        // Levels past what the simulator has data for are close enough to a max level recipe,
+        // but can't be rated
+        let capstone = DataminedRecipe {
+            exp_rewarded: true,
+            recipe: RecipeStats::from_factors(RecipeLevelRanges::DtMax(0), 100, 100, 100).unwrap(),
+            ..rope.clone()
+        };
+        assert_eq!(
+            capstone.recipe.required_character_level(),
+            CharacterStats::MAX_LEVEL
+        );
+        assert!(capstone.is_leveling_recipe(&character(CharacterStats::MAX_LEVEL)));
+        assert!(!capstone.is_leveling_recipe(&character(CharacterStats::MAX_LEVEL + 1)));
+    }
+
+    #[test]
+    fn simulators_for_recipes() {
+        let registry = RecipeRegistry::from_csv(RECIPES, ITEMS).unwrap();
+        let character = |char_level| CharacterStats {
+            craftsmanship: 2000,
+            control: 2000,
+            max_cp: 500,
+            char_level,
+        };
+
+        let saw = registry.get_by_name("Ironwood Saw").unwrap().recipe;
+        assert_eq!(
+            registry.simulator_for("Ironwood Saw", character(80)),
+            Ok(DataminedSimulator::Hq(ConditionedSimulator::QARegular(
+                CraftingSimulator::new(character(80), saw, QARegularConditions::Normal)
+            )))
+        );
+
+        let tincture = registry.get_by_name("Rarefied Tincture").unwrap().recipe;
+        assert_eq!(
+            registry.simulator_for("rarefied tincture", character(80)),
+            Ok(DataminedSimulator::Collectable(
+                ConditionedSimulator::QARegular(CraftingSimulator::new(
+                    character(80),
+                    tincture,
+                    QARegularConditions::Normal
+                ))
+            ))
+        );
+
+        assert!(matches!(
+            registry.simulator_for("Expert Rope", character(80)),
+            Ok(DataminedSimulator::Collectable(
+                ConditionedSimulator::RelicExpert(_)
+            ))
+        ));
+
+        // Regular conditions depend on whether the character has Quality Assurance yet
+        assert!(matches!(
+            registry.simulator_for("Bronze Ingot", character(QUALITY_ASSURANCE_LEVEL - 1)),
+            Ok(DataminedSimulator::Hq(ConditionedSimulator::NoQARegular(_)))
+        ));
+        assert!(matches!(
+            registry.simulator_for("Bronze Ingot", character(QUALITY_ASSURANCE_LEVEL)),
+            Ok(DataminedSimulator::Hq(ConditionedSimulator::QARegular(_)))
+        ));
+
+        assert_eq!(
+            registry.simulator_for("Ironwood", character(80)),
+            Err(SimulatorForError::UnknownItem)
+        );
+        assert_eq!(
+            registry.simulator_for("Ironwood Saw", character(70)),
+            Err(SimulatorForError::Recipe(
+                RecipeError::CharacterTooLowLevel {
+                    required: 75,
+                    actual: 70
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn malformed_recipes() {
+        assert_eq!(
+            RecipeRegistry::from_csv(RECIPES, "#,Singular\n1,Saw"),
+            Err(ParseDatamineError::MissingColumn("Name"))
+        );
+        assert_eq!(
+            RecipeRegistry::from_csv(&RECIPES.replace(",480,", ",x,"), ITEMS),
+            Err(ParseDatamineError::Malformed { line: 7 })
+        );
+        assert_eq!(
+            RecipeRegistry::from_csv(
+                &RECIPES.replace(",12,False,True,True", ",12,False,True,1"),
+                ITEMS
+            ),
+            Err(ParseDatamineError::Malformed { line: 7 })
+        );
+        assert_eq!(
+            RecipeRegistry::from_csv(&RECIPES.replace(",85,75,50,", ",85,75,500,"), ITEMS),
+            Err(ParseDatamineError::Malformed { line: 7 })
+        );
+        assert_eq!(
+            RecipeRegistry::from_csv(&RECIPES.replace("\n3,3,", "\n3,8,"), ITEMS),
+            Err(ParseDatamineError::Malformed { line: 7 })
+        );
+        assert_eq!(
+            RecipeRegistry::from_csv(
+                RECIPES,
+                &ITEMS.replace(",Rarefied Tincture,80,False,True,0", "")
+            ),
+            Err(ParseDatamineError::Malformed { line: 7 })
+        );
+    }
+
+    #[test]
+    fn malformed_files() {
+        assert_eq!(
+            diff_level_mods("0,-30,80,60"),
+            Err(ParseDatamineError::MissingColumn("Difference"))
+        );
+        assert_eq!(
+            diff_level_mods("#,Difference,ProgressFactor\n0,-30,80"),
+            Err(ParseDatamineError::MissingColumn("QualityFactor"))
+        );
+        assert_eq!(
+            diff_level_mods("#,Difference,ProgressFactor,QualityFactor\n0,-30,80,x"),
+            Err(ParseDatamineError::Malformed { line: 2 })
+        );
+    }
+}
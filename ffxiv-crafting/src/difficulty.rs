@@ -0,0 +1,120 @@
+//! A quick gauge of how hard a recipe is for a character, for things like recipe browsers that
+//! want an answer at a glance without running a solver.
+
+use crate::{
+    conditions::QARegularConditions, quality_map::HQMap, CharacterStats, CraftingSimulator,
+    CraftingState, RecipeStats,
+};
+
+/// How hard a recipe is for a character to complete and HQ, as rated by [`difficulty_rating`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    /// The character could complete and max the quality of the recipe with plain actions using
+    /// at most half of the durability and CP.
+    Trivial,
+
+    /// The character could complete and max the quality of the recipe with plain actions, but
+    /// needs most of their durability or CP to do it.
+    Comfortable,
+
+    /// The character needs buffs, repairs, or luck to either complete the recipe or max its
+    /// quality.
+    Challenging,
+
+    /// Completing the recipe would take more than twice its durability worth of Basic Synthesis,
+    /// which is more than buffs and repairs can reasonably make up for.
+    Impossible,
+}
+
+/// Rates how hard `recipe` is for `character`, without buffs, repairs, or the condition in mind.
+///
+/// This works in terms of plain actions: `P`, the number of Basic Synthesis it takes to fill the
+/// progress bar (via [`base_progress`]), `Q`, the number of Basic Touch it takes to max the quality
+/// (via [`base_quality`], ignoring Inner Quiet), and `D`, the number of actions that can be taken
+/// before the item breaks. Basic Touch costs 18 CP.
+///
+/// - [`Impossible`](Difficulty::Impossible) if the craft [can't be completed] even under the
+///   most optimistic assumptions, and `P > 2D`.
+/// - [`Trivial`](Difficulty::Trivial) if `P + Q <= D / 2` and `18Q` is at most half the
+///   character's CP.
+/// - [`Comfortable`](Difficulty::Comfortable) if `P + Q <= D` and `18Q` is at most the
+///   character's CP.
+/// - [`Challenging`](Difficulty::Challenging) otherwise.
+///
+/// A character whose level is outside 1 to [`MAX_LEVEL`](CharacterStats::MAX_LEVEL) is always
+/// rated [`Impossible`](Difficulty::Impossible), as the simulator has no data for them.
+///
+/// [`base_progress`]: CraftingState::base_progress
+/// [`base_quality`]: CraftingState::base_quality
+/// [can't be completed]: CraftingState::can_still_complete
+pub fn difficulty_rating(recipe: RecipeStats, character: CharacterStats) -> Difficulty {
+    const TOUCH_CP: u32 = 18;
+
+    if !(1..=CharacterStats::MAX_LEVEL).contains(&character.char_level) {
+        return Difficulty::Impossible;
+    }
+
+    // The condition and quality map make no difference to any of the checks
+    let problem_def =
+        CraftingSimulator::<_, HQMap>::new(character, recipe, QARegularConditions::Normal);
+    let state = CraftingState::new_simulation(&problem_def);
+
+    let synths = (recipe.max_progress as f64 / state.base_progress()).ceil() as u32;
+    let touches = (recipe.max_quality as f64 / state.base_quality()).ceil() as u32;
+    let actions = (recipe.max_durability.max(0) / 10) as u32;
+    let cp = character.max_cp.max(0) as u32;
+
+    if synths > 2 * actions && !state.can_still_complete() {
+        Difficulty::Impossible
+    } else if 2 * (synths + touches) <= actions && 2 * TOUCH_CP * touches <= cp {
+        Difficulty::Trivial
+    } else if synths + touches <= actions && TOUCH_CP * touches <= cp {
+        Difficulty::Comfortable
+    } else {
+        Difficulty::Challenging
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{test_util::CLASSICAL_SIMULATOR, RecipeLevelRanges};
+
+    fn rate(max_durability: i8, max_quality: u32, max_progress: u32) -> Difficulty {
+        difficulty_rating(
+            RecipeStats::new(
+                RecipeLevelRanges::ShbLeveling(75),
+                max_durability,
+                max_quality,
+                max_progress,
+            ),
+            CLASSICAL_SIMULATOR.character,
+        )
+    }
+
+    #[test]
+    fn ratings() {
+        assert_eq!(rate(80, 1_000, 600), Difficulty::Trivial);
+        assert_eq!(rate(80, 3_000, 1_500), Difficulty::Comfortable);
+        assert_eq!(rate(80, 10_000, 3_000), Difficulty::Challenging);
+        assert_eq!(rate(20, 1_000, 200_000), Difficulty::Impossible);
+    }
+
+    #[test]
+    fn unknown_levels_are_impossible() {
+        let recipe = RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 80, 1_000, 600);
+        for char_level in [0, CharacterStats::MAX_LEVEL + 1] {
+            let character = CharacterStats {
+                char_level,
+                ..CLASSICAL_SIMULATOR.character
+            };
+            assert_eq!(difficulty_rating(recipe, character), Difficulty::Impossible);
+        }
+    }
+
+    #[test]
+    fn rating_orders_by_difficulty() {
+        assert!(Difficulty::Trivial < Difficulty::Comfortable);
+        assert!(Difficulty::Challenging < Difficulty::Impossible);
+    }
+}
@@ -1,22 +1,48 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
 
-use std::marker::PhantomData;
+// Lets the derive macros refer to `::ffxiv_crafting` whether they're used in this
+// crate or downstream.
+extern crate self as ffxiv_crafting;
 
-use actions::{Action, ActionOutcome, RandomAction, StateDelta};
-use buffs::BuffState;
-use conditions::Condition;
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use actions::{
+    collection::FfxivCraftingActions, Action, ActionLevel, ActionOutcome, CanExecute, CpCost,
+    RandomAction, StateDelta,
+};
+use buffs::{combo::BasicTouchCombo, misc::SpecialistActions, Buff, BuffName, BuffState};
+use conditions::{Condition, ConditionTransitions};
 use derivative::Derivative;
 
 pub mod actions;
 pub mod buffs;
 pub mod conditions;
+pub mod datamine;
+pub mod difficulty;
 pub(crate) mod lookups;
+pub mod macros;
+#[cfg(feature = "mdp")]
+pub mod mdp;
+pub mod plan;
 pub mod quality_map;
+pub mod rotation;
+pub mod trace;
+
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 
 #[doc(inline)]
-pub use lookups::RecipeLevelRanges;
-use quality_map::QualityMap;
+pub use lookups::{ConditionBits, ParseRecipeLevelError, RecipeLevelRanges};
+use quality_map::{HQChance, QualityMap};
+/// The version of `rand` used by this crate, re-exported so the [`Rng`]s passed to actions and
+/// conditions (and the ones named in derived code) are always compatible.
+pub use rand;
 use rand::Rng;
 
 /// The overall simulator problem. This is actually just the definition that gives
@@ -43,9 +69,199 @@ where
     quality_map: PhantomData<M>,
 }
 
+impl<C, M> CraftingSimulator<C, M>
+where
+    M: QualityMap,
+{
+    /// Creates a new problem definition for the given character making the given recipe.
+    pub const fn new(character: CharacterStats, recipe: RecipeStats, conditions: C) -> Self {
+        Self {
+            character,
+            recipe,
+            conditions,
+            quality_map: PhantomData,
+        }
+    }
+
+    /// Like [`new`](CraftingSimulator::new), but checks that the character could actually
+    /// make the recipe, rather than allowing hypothetical setups. This includes the character's
+    /// level being one that exists, from 1 to [`MAX_LEVEL`](CharacterStats::MAX_LEVEL).
+    pub fn checked_new(
+        character: CharacterStats,
+        recipe: RecipeStats,
+        conditions: C,
+    ) -> Result<Self, RecipeError> {
+        if !(1..=CharacterStats::MAX_LEVEL).contains(&character.char_level) {
+            return Err(RecipeError::LevelOutOfRange(character.char_level));
+        }
+
+        let required = recipe.required_character_level();
+        if character.char_level < required {
+            return Err(RecipeError::CharacterTooLowLevel {
+                required,
+                actual: character.char_level,
+            });
+        }
+
+        Ok(Self::new(character, recipe, conditions))
+    }
+}
+
+/// The reasons a character can't make a recipe, as checked by
+/// [`CraftingSimulator::checked_new`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RecipeError {
+    /// The character's level isn't one the simulator has data for.
+    LevelOutOfRange(u8),
+
+    /// The character's level is below the recipe's [required level].
+    ///
+    /// [required level]: RecipeStats::required_character_level
+    #[allow(missing_docs)]
+    CharacterTooLowLevel { required: u8, actual: u8 },
+
+    /// The recipe [requires a specialist], but the crafter isn't one.
+    ///
+    /// [requires a specialist]: RecipeStats::specialist_required
+    SpecialistRequired,
+}
+
+impl Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LevelOutOfRange(level) => write!(
+                f,
+                "Level {} is outside of the supported levels 1 to {}",
+                level,
+                CharacterStats::MAX_LEVEL
+            ),
+            Self::CharacterTooLowLevel { required, actual } => write!(
+                f,
+                "The recipe requires level {}, but the character is level {}",
+                required, actual
+            ),
+            Self::SpecialistRequired => write!(f, "The recipe can only be made by a specialist"),
+        }
+    }
+}
+
+impl Error for RecipeError {}
+
+/// The reasons a recipe's internal factors can't be turned into [`RecipeStats`] by
+/// [`RecipeStats::from_factors`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RecipeFactorsError {
+    /// The recipe's durability would be this, which is higher than the simulator can represent.
+    DurabilityOutOfRange(u32),
+}
+
+impl Display for RecipeFactorsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DurabilityOutOfRange(durability) => write!(
+                f,
+                "The recipe's durability would be {}, but it can be at most {}",
+                durability,
+                i8::MAX
+            ),
+        }
+    }
+}
+
+impl Error for RecipeFactorsError {}
+
+impl<C, M> CraftingSimulator<C, M>
+where
+    C: Condition + Default,
+    M: QualityMap,
+{
+    /// The actions that could open a fresh craft of this problem: those the character has the
+    /// level for, can afford, and can execute on the first step (including first step only actions
+    /// like Muscle Memory, Reflect, and Trained Eye), in the order of [`FfxivCraftingActions::ALL`].
+    pub fn opening_actions(&self) -> Vec<FfxivCraftingActions> {
+        let state = CraftingState::new_simulation(self);
+
+        FfxivCraftingActions::ALL
+            .into_iter()
+            .filter(|action| {
+                action.level() <= self.character.char_level as u16
+                    && action.can_execute(&state)
+                    && state.curr_cp + action.cp_cost(&state) >= 0
+            })
+            .collect()
+    }
+}
+
+impl<C, M> CraftingSimulator<C, M>
+where
+    C: Condition,
+    M: QualityMap,
+{
+    /// A key identifying this problem definition, for caching anything computed from it (such as
+    /// simulation results or solved rotations).
+    ///
+    /// Unlike the [`Hash`] implementation, which depends on the hasher used, this is the same on
+    /// every platform and every build, so it can be persisted. It's made from every field of the
+    /// character and recipe, whether or not it changes how actions play out, since anything
+    /// cached for a problem (like a solver's choice of target) could depend on any of them:
+    ///
+    /// - The character's craftsmanship, control, max CP, and level.
+    /// - The recipe's level (as its index into the `rlvl` table), durability, max quality,
+    ///   max progress, required quality, result quantity, and whether it requires a specialist.
+    /// - The [`raw_bit`](Condition::raw_bit) of `conditions`.
+    ///
+    /// The [`QualityMap`] isn't included, as it's a type rather than a value, so keep separate
+    /// caches for simulators that use different ones.
+    pub fn cache_key(&self) -> u64 {
+        // 64-bit FNV-1a
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let CharacterStats {
+            craftsmanship,
+            control,
+            max_cp,
+            char_level,
+        } = self.character;
+        let RecipeStats {
+            recipe_level,
+            max_durability,
+            max_quality,
+            max_progress,
+            required_quality,
+            result_quantity,
+            specialist_required,
+        } = self.recipe;
+
+        [
+            &craftsmanship.to_le_bytes()[..],
+            &control.to_le_bytes(),
+            &max_cp.to_le_bytes(),
+            &char_level.to_le_bytes(),
+            &(recipe_level.to_rlvl_index() as u64).to_le_bytes(),
+            &max_durability.to_le_bytes(),
+            &max_quality.to_le_bytes(),
+            &max_progress.to_le_bytes(),
+            &required_quality.to_le_bytes(),
+            &result_quantity.to_le_bytes(),
+            &[specialist_required as u8],
+            &self.conditions.raw_bit().to_le_bytes(),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(PRIME)
+        })
+    }
+}
+
 /// The stats of the a FFXIV character - these are *after* any buffs
 /// or food. It can look up `clvl` based on your character level.
+///
+/// To work these out from the character's base stats and their food and medicine, use
+/// [`CharacterStats::builder`].
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CharacterStats {
     /* Character stats */
     #[allow(missing_docs)]
@@ -55,22 +271,246 @@ pub struct CharacterStats {
     #[allow(missing_docs)]
     pub max_cp: i16,
 
-    /// Actual level, 1..<max_char_lvl> (80 in Shb, 90 in EW etc)
+    /// Actual level, 1..<max_char_lvl> (80 in Shb, 90 in EW, 100 in DT)
     pub char_level: u8,
 }
 
 impl CharacterStats {
+    /// The highest character level the simulator has data for.
+    pub const MAX_LEVEL: u8 = lookups::CLVL.len() as u8;
+
     /// Looks up the character's [`clvl`] in the proper reference table.
     ///
-    /// This is entirely based on actual character level.
+    /// This is entirely based on actual character level, so this panics for level 0, as there's
+    /// no such level.
     const fn clvl(&self) -> u16 {
-        lookups::CLVL[self.char_level as usize]
+        assert!(self.char_level != 0, "Characters start at level 1");
+        lookups::CLVL[self.char_level as usize - 1]
+    }
+
+    /// Starts building stats from the character's own stats (i.e. just from their gear), so
+    /// food and medicine can be added on top with the [`CharacterStatsBuilder`].
+    pub const fn builder(
+        craftsmanship: u16,
+        control: u16,
+        max_cp: i16,
+        char_level: u8,
+    ) -> CharacterStatsBuilder {
+        CharacterStatsBuilder {
+            base: Self {
+                craftsmanship,
+                control,
+                max_cp,
+                char_level,
+            },
+            food: Consumable::NONE,
+            medicine: Consumable::NONE,
+        }
+    }
+}
+
+/// Writes the stats in the same compact notation [`FromStr`] reads, e.g.
+/// `cms=3691,ctrl=3664,cp=564,lvl=80`.
+impl Display for CharacterStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cms={},ctrl={},cp={},lvl={}",
+            self.craftsmanship, self.control, self.max_cp, self.char_level
+        )
+    }
+}
+
+/// Reads stats from a compact notation meant for command lines and config files, e.g.
+/// `cms=3691,ctrl=3664,cp=564,lvl=80`. The fields can be given in any order, but each must be
+/// given exactly once, and the level must be from 1 to [`MAX_LEVEL`](CharacterStats::MAX_LEVEL).
+impl FromStr for CharacterStats {
+    type Err = ParseCharacterStatsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use ParseCharacterStatsError::*;
+
+        const FIELDS: [&str; 4] = ["cms", "ctrl", "cp", "lvl"];
+        let mut values: [Option<&str>; 4] = [None; 4];
+
+        for entry in s.split(',') {
+            let (key, value) = entry.split_once('=').ok_or(Malformed)?;
+            let field = FIELDS
+                .iter()
+                .position(|field| *field == key.trim())
+                .ok_or(UnknownField)?;
+
+            if values[field].replace(value.trim()).is_some() {
+                return Err(DuplicateField(FIELDS[field]));
+            }
+        }
+
+        fn parse<T: FromStr>(
+            field: &'static str,
+            value: Option<&str>,
+        ) -> Result<T, ParseCharacterStatsError> {
+            value
+                .ok_or(MissingField(field))?
+                .parse()
+                .map_err(|_| InvalidValue(field))
+        }
+
+        let [craftsmanship, control, max_cp, char_level] = values;
+        let stats = Self {
+            craftsmanship: parse("cms", craftsmanship)?,
+            control: parse("ctrl", control)?,
+            max_cp: parse("cp", max_cp)?,
+            char_level: parse("lvl", char_level)?,
+        };
+
+        if !(1..=Self::MAX_LEVEL).contains(&stats.char_level) {
+            return Err(LevelOutOfRange(stats.char_level));
+        }
+
+        Ok(stats)
+    }
+}
+
+/// The reasons a string can't be parsed into [`CharacterStats`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ParseCharacterStatsError {
+    /// One of the comma separated entries isn't of the form `field=value`.
+    Malformed,
+
+    /// One of the fields isn't `cms`, `ctrl`, `cp`, or `lvl`.
+    UnknownField,
+
+    /// The named field was given more than once.
+    DuplicateField(&'static str),
+
+    /// The named field wasn't given.
+    MissingField(&'static str),
+
+    /// The named field's value isn't a number, or is out of range for its type.
+    InvalidValue(&'static str),
+
+    /// The level isn't one the simulator has data for.
+    LevelOutOfRange(u8),
+}
+
+impl Display for ParseCharacterStatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(
+                f,
+                "Expected stats like \"cms=3691,ctrl=3664,cp=564,lvl=80\""
+            ),
+            Self::UnknownField => write!(f, "Expected only the fields cms, ctrl, cp, and lvl"),
+            Self::DuplicateField(field) => {
+                write!(f, "The field {} was given more than once", field)
+            }
+            Self::MissingField(field) => write!(f, "The field {} is missing", field),
+            Self::InvalidValue(field) => write!(f, "The field {} isn't a valid number", field),
+            Self::LevelOutOfRange(level) => write!(
+                f,
+                "Level {} is outside of the supported levels 1 to {}",
+                level,
+                CharacterStats::MAX_LEVEL
+            ),
+        }
+    }
+}
+
+impl Error for ParseCharacterStatsError {}
+
+/// Builds [`CharacterStats`] from a character's base stats and the food and medicine they're
+/// using, see [`CharacterStats::builder`].
+///
+/// Food and medicine both add onto the base stats, they don't add onto each other.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CharacterStatsBuilder {
+    base: CharacterStats,
+    food: Consumable,
+    medicine: Consumable,
+}
+
+impl CharacterStatsBuilder {
+    /// Sets the food the character has eaten, replacing any set before.
+    pub const fn food(mut self, food: Consumable) -> Self {
+        self.food = food;
+        self
+    }
+
+    /// Sets the medicine the character has taken, replacing any set before.
+    pub const fn medicine(mut self, medicine: Consumable) -> Self {
+        self.medicine = medicine;
+        self
+    }
+
+    /// The character's stats with their food and medicine applied.
+    pub fn build(self) -> CharacterStats {
+        let Self {
+            base,
+            food,
+            medicine,
+        } = self;
+        let cp = base.max_cp.max(0) as u16;
+
+        CharacterStats {
+            craftsmanship: base.craftsmanship
+                + food.craftsmanship.bonus(base.craftsmanship)
+                + medicine.craftsmanship.bonus(base.craftsmanship),
+            control: base.control
+                + food.control.bonus(base.control)
+                + medicine.control.bonus(base.control),
+            max_cp: base.max_cp + (food.cp.bonus(cp) + medicine.cp.bonus(cp)) as i16,
+            ..base
+        }
+    }
+}
+
+/// A food or medicine, which raises each stat by a percentage up to a cap, e.g. +4% CP up to 72.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub struct Consumable {
+    pub craftsmanship: StatBonus,
+    pub control: StatBonus,
+    pub cp: StatBonus,
+}
+
+impl Consumable {
+    /// Doesn't raise any stats, as if nothing was eaten (or taken).
+    pub const NONE: Self = Self {
+        craftsmanship: StatBonus::NONE,
+        control: StatBonus::NONE,
+        cp: StatBonus::NONE,
+    };
+}
+
+/// Raises a stat by `percent` of its base value (rounded down), but by no more than `cap`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub struct StatBonus {
+    pub percent: u16,
+    pub cap: u16,
+}
+
+impl StatBonus {
+    /// Doesn't raise the stat at all.
+    pub const NONE: Self = Self { percent: 0, cap: 0 };
+
+    /// The amount this raises a stat with the given base value by.
+    pub const fn bonus(self, base: u16) -> u16 {
+        let bonus = base as u32 * self.percent as u32 / 100;
+        if bonus < self.cap as u32 {
+            bonus as u16
+        } else {
+            self.cap
+        }
     }
 }
 
 /// The stats of a recipe, containing both its level as well as the
 /// three states that govern a recipe's status.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RecipeStats {
     /// The recipe "level" taking into account level cap recipes
     /// and stars, as well as the subtle distinctions between them.
@@ -88,6 +528,126 @@ pub struct RecipeStats {
     /// The maximum progress of a recipe, when the state hits this value
     /// the recipe is completed.
     max_progress: u32,
+
+    /// The least quality the item needs for it to be accepted, e.g. as a quest turn-in. This is
+    /// 0 for most recipes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    required_quality: u32,
+
+    /// How many of the item a single craft makes.
+    #[cfg_attr(feature = "serde", serde(default = "RecipeStats::one"))]
+    result_quantity: u8,
+
+    /// Whether only specialists can make this recipe.
+    #[cfg_attr(feature = "serde", serde(default))]
+    specialist_required: bool,
+}
+
+impl RecipeStats {
+    /// Creates the stats for a recipe. These should be the final values shown in-game,
+    /// not the recipe's internal factors.
+    pub const fn new(
+        recipe_level: RecipeLevelRanges,
+        max_durability: i8,
+        max_quality: u32,
+        max_progress: u32,
+    ) -> Self {
+        Self {
+            recipe_level,
+            max_durability,
+            max_quality,
+            max_progress,
+            required_quality: 0,
+            result_quantity: 1,
+            specialist_required: false,
+        }
+    }
+
+    /// Creates the stats for a recipe from its internal factors, as found in the game's `Recipe`
+    /// table. Each is a percentage of the base value for the recipe level, rounded down the same
+    /// way the game does.
+    ///
+    /// This errors if the durability comes out higher than the simulator can represent, rather
+    /// than wrapping around.
+    pub const fn from_factors(
+        recipe_level: RecipeLevelRanges,
+        progress_factor: u16,
+        quality_factor: u16,
+        durability_factor: u16,
+    ) -> Result<Self, RecipeFactorsError> {
+        let durability =
+            recipe_level.to_recipe_level_durability() as u32 * durability_factor as u32 / 100;
+        if durability > i8::MAX as u32 {
+            return Err(RecipeFactorsError::DurabilityOutOfRange(durability));
+        }
+
+        Ok(Self::new(
+            recipe_level,
+            durability as i8,
+            recipe_level.to_recipe_level_quality() * quality_factor as u32 / 100,
+            recipe_level.to_recipe_level_progress() * progress_factor as u32 / 100,
+        ))
+    }
+
+    /// The same recipe, but making `result_quantity` items per craft rather than 1.
+    pub const fn with_result_quantity(self, result_quantity: u8) -> Self {
+        Self {
+            result_quantity,
+            ..self
+        }
+    }
+
+    /// How many of the item a single craft makes. This doesn't affect the craft itself, only
+    /// estimates like [`throughput`](rotation::throughput).
+    pub const fn result_quantity(&self) -> u8 {
+        self.result_quantity
+    }
+
+    /// The same recipe, but only accepted if the item is finished with at least
+    /// `required_quality`.
+    pub const fn with_required_quality(self, required_quality: u32) -> Self {
+        Self {
+            required_quality,
+            ..self
+        }
+    }
+
+    /// The least quality the item needs for it to be accepted, or 0 if there is no requirement.
+    pub const fn required_quality(&self) -> u32 {
+        self.required_quality
+    }
+
+    /// The same recipe, but only able to be made by a specialist.
+    pub const fn with_specialist_required(self) -> Self {
+        Self {
+            specialist_required: true,
+            ..self
+        }
+    }
+
+    /// Whether only specialists can make this recipe. This is checked by
+    /// [`CraftingState::check_specialist`], as whether the crafter is a specialist is part of the
+    /// state rather than their [`CharacterStats`].
+    pub const fn specialist_required(&self) -> bool {
+        self.specialist_required
+    }
+
+    /// The class level a character needs to be to make this recipe. For level cap recipes, this
+    /// is the level cap of their expansion, regardless of stars.
+    pub const fn required_character_level(&self) -> u8 {
+        self.recipe_level.to_player_facing_level()
+    }
+
+    /// Whether an item with `curr_quality` would be accepted. Solvers for quality-gated recipes
+    /// may want to aim for this rather than the recipe's maximum quality.
+    pub const fn meets_quality_requirement(&self, curr_quality: u32) -> bool {
+        curr_quality >= self.required_quality
+    }
+
+    #[cfg(feature = "serde")]
+    const fn one() -> u8 {
+        1
+    }
 }
 
 /// The current state of the crafting simulation. The vast majority of types
@@ -114,6 +674,12 @@ where
 
     /// The current [`Condition`] based on the type of recipe this is,
     /// has effects on the potency of actions.
+    ///
+    /// This is ordered by [`Condition::raw_bit`] rather than the condition's own [`Ord`].
+    #[derivative(
+        PartialOrd(compare_with = "conditions::stable_partial_cmp"),
+        Ord(compare_with = "conditions::stable_cmp")
+    )]
     pub condition: C,
 
     /// The current quality value, this can go over the actual maximum, but will
@@ -141,8 +707,168 @@ where
 
     /// Determines if several first turn-only actions are usable. Technically
     /// FFXIV itself uses "step count", but that makes state ranking harder to
-    /// reason about. Solver implementors may want to track that themselves.
+    /// reason about. Solver implementors may want to track that themselves, or
+    /// enable the `step-count` feature.
+    ///
+    /// Like in the game, actions that stop time (see [`TimePassing`]) such as Final Appraisal
+    /// don't use up the first step, so e.g. Muscle Memory can still follow them.
+    ///
+    /// [`TimePassing`]: crate::actions::TimePassing
     first_step: bool,
+
+    /// How many actions that pass time have been used so far, behind the `step-count` feature.
+    /// Like `problem_def`, this is ignored when comparing and hashing states, so states that
+    /// only differ in how long it took to reach them are still treated as the same.
+    #[cfg(feature = "step-count")]
+    #[derivative(
+        Hash = "ignore",
+        PartialEq = "ignore",
+        PartialOrd = "ignore",
+        Ord = "ignore"
+    )]
+    steps: u16,
+}
+
+impl<'a, C, M> CraftingState<'a, C, M>
+where
+    C: Condition + Default,
+    M: QualityMap,
+{
+    /// Creates the starting state of a craft of `problem_def`: full durability and CP, no
+    /// progress or quality, no buffs, and the default (Normal) condition. This is where every
+    /// craft begins, and the state to pass to actions or [`run_traced`] to simulate one.
+    ///
+    /// The buffs start out with the crafter not being a [specialist], so specialist actions
+    /// can't be used. Use [`new_specialist_simulation`] for a specialist.
+    ///
+    /// Non-specialists can't make recipes that [require a specialist], so this panics for those.
+    ///
+    /// [`run_traced`]: CraftingState::run_traced
+    /// [specialist]: crate::buffs::misc::SpecialistActions
+    /// [`new_specialist_simulation`]: CraftingState::new_specialist_simulation
+    /// [require a specialist]: RecipeStats::specialist_required
+    pub fn new_simulation(problem_def: &'a CraftingSimulator<C, M>) -> Self {
+        let state = Self::new_unchecked(problem_def, SpecialistActions::NotSpecialist);
+        if let Err(err) = state.check_specialist() {
+            panic!("{}, use `new_specialist_simulation`", err);
+        }
+
+        state
+    }
+
+    /// Creates the starting state of a craft like [`new_simulation`], but for a specialist with
+    /// the given number of crafter's delineations, so specialist actions such as
+    /// [`CarefulObservation`] and [`HeartAndSoul`] can be used. A specialist with no
+    /// delineations can't use them at all.
+    ///
+    /// Only 3 delineations can be used per craft, so in debug mode this panics if
+    /// `delineations` is more than that.
+    ///
+    /// [`new_simulation`]: CraftingState::new_simulation
+    /// [`CarefulObservation`]: crate::actions::misc::CarefulObservation
+    /// [`HeartAndSoul`]: crate::actions::buffs::HeartAndSoul
+    pub fn new_specialist_simulation(
+        problem_def: &'a CraftingSimulator<C, M>,
+        delineations: u8,
+    ) -> Self {
+        debug_assert!(
+            delineations <= 3,
+            "Too many crafters delineations - we're constrained to 3 per craft."
+        );
+
+        Self::new_unchecked(
+            problem_def,
+            match delineations {
+                0 => SpecialistActions::Unavailable,
+                delineations => SpecialistActions::Availalble(delineations),
+            },
+        )
+    }
+
+    /// The starting state of a craft, without checking the crafter can make the recipe.
+    fn new_unchecked(
+        problem_def: &'a CraftingSimulator<C, M>,
+        specialist_actions: SpecialistActions,
+    ) -> Self {
+        Self {
+            problem_def,
+            condition: C::default(),
+            curr_quality: 0,
+            curr_progress: 0,
+            curr_durability: problem_def.recipe.max_durability,
+            curr_cp: problem_def.character.max_cp,
+            buffs: BuffState {
+                specialist_actions,
+                ..BuffState::default()
+            },
+            first_step: true,
+            #[cfg(feature = "step-count")]
+            steps: 0,
+        }
+    }
+}
+
+/// The game's formula for base quality, in floats.
+fn float_base_quality(control: u16, rlvl_control: u16, level_mod: u16) -> f64 {
+    let control = control as f64;
+
+    let quality = control * 35. / 100. + 35.;
+    let quality = quality * (control + 10_000.) / (rlvl_control as f64 + 10_000.);
+    quality * level_mod as f64 / 100.
+}
+
+/// The game's formula for base progress, in floats.
+fn float_base_progress(craftsmanship: u16, rlvl_craftsmanship: u16, level_mod: u16) -> f64 {
+    let craftsmanship = craftsmanship as f64;
+
+    let progress = craftsmanship * 21. / 100. + 2.;
+    let progress = progress * (craftsmanship + 10_000.) / (rlvl_craftsmanship as f64 + 10_000.);
+    progress * level_mod as f64 / 100.
+}
+
+/// The floor of [`float_base_quality`] times the condition's modifier, computed as the exact
+/// fraction `(control * 35 + 3500) * (control + 10000) * level_mod * condition_mod` over
+/// `100 * (rlvl_control + 10000) * 100 * 100`. Even at the max of every type involved, this fits
+/// comfortably in a [`u64`].
+fn conditioned_base_quality(
+    control: u16,
+    rlvl_control: u16,
+    level_mod: u16,
+    condition_mod: u16,
+) -> u32 {
+    let stat = control as u64;
+    let num = (stat * 35 + 3_500) * (stat + 10_000) * level_mod as u64 * condition_mod as u64;
+    let den = 100 * (rlvl_control as u64 + 10_000) * 100 * 100;
+
+    floor_like_floats(num, den, || {
+        let quality = float_base_quality(control, rlvl_control, level_mod);
+        (quality * condition_mod as u64 as f64 / 100.).floor()
+    })
+}
+
+/// The floor of [`float_base_progress`], computed as the exact fraction
+/// `(craftsmanship * 21 + 200) * (craftsmanship + 10000) * level_mod` over
+/// `100 * (rlvl_craftsmanship + 10000) * 100`.
+fn floored_base_progress(craftsmanship: u16, rlvl_craftsmanship: u16, level_mod: u16) -> u32 {
+    let stat = craftsmanship as u64;
+    let num = (stat * 21 + 200) * (stat + 10_000) * level_mod as u64;
+    let den = 100 * (rlvl_craftsmanship as u64 + 10_000) * 100;
+
+    floor_like_floats(num, den, || {
+        float_base_progress(craftsmanship, rlvl_craftsmanship, level_mod).floor()
+    })
+}
+
+/// Rounds `num / den` down the way the float version of the same formula does. Away from whole
+/// numbers the floats' error can't change the result, but right next to one they can land on
+/// either side of it, so those rare cases fall back to `floored_float`.
+fn floor_like_floats(num: u64, den: u64, floored_float: impl FnOnce() -> f64) -> u32 {
+    let remainder = num % den;
+    if remainder.min(den - remainder) * 1_000_000 < den {
+        floored_float() as u32
+    } else {
+        (num / den) as u32
+    }
 }
 
 impl<'a, C, M> CraftingState<'a, C, M>
@@ -152,80 +878,790 @@ where
 {
     /// The base quality that any action operating on `quality` will modify with its `efficiency`.
     pub fn base_quality(&self) -> f64 {
-        let control = self.problem_def.character.control as f64;
+        let (control, rlvl_control, level_mod) = self.quality_factors();
+        float_base_quality(control, rlvl_control, level_mod)
+    }
+
+    /// The base progress that any action operating on `progress` will modify with its `efficiency`.
+    pub fn base_progress(&self) -> f64 {
+        let (craftsmanship, rlvl_craftsmanship, level_mod) = self.progress_factors();
+        float_base_progress(craftsmanship, rlvl_craftsmanship, level_mod)
+    }
+
+    /// The [`base_quality`](CraftingState::base_quality) with the current condition's modifier
+    /// applied, rounded down, which is what quality actions apply their `efficiency` to.
+    ///
+    /// This is done in integers, which is faster than going through floats, but gives exactly
+    /// the same result, including where the floats round a whole number just below itself.
+    pub fn conditioned_base_quality(&self) -> u32 {
+        let (control, rlvl_control, level_mod) = self.quality_factors();
+        conditioned_base_quality(
+            control,
+            rlvl_control,
+            level_mod,
+            self.condition.to_quality_modifier() as u16,
+        )
+    }
+
+    /// The [`base_progress`](CraftingState::base_progress) rounded down, which is what progress
+    /// actions apply the condition and their `efficiency` to. Like
+    /// [`conditioned_base_quality`](CraftingState::conditioned_base_quality), this is done in
+    /// integers but matches the floats exactly.
+    pub fn floored_base_progress(&self) -> u32 {
+        let (craftsmanship, rlvl_craftsmanship, level_mod) = self.progress_factors();
+        floored_base_progress(craftsmanship, rlvl_craftsmanship, level_mod)
+    }
 
+    /// The character's control, the recipe level's control, and the quality level modifier.
+    fn quality_factors(&self) -> (u16, u16, u16) {
         let rlvl = self.problem_def.recipe.recipe_level;
         let clvl = self.problem_def.character.clvl();
 
-        let quality = control * 35. / 100. + 35.;
-        let quality =
-            quality * (control + 10_000.) / (rlvl.to_recipe_level_control() as f64 + 10_000.);
-        quality * rlvl.to_quality_level_mod(clvl) as f64 / 100.
+        (
+            self.problem_def.character.control,
+            rlvl.to_recipe_level_control(),
+            rlvl.to_quality_level_mod(clvl),
+        )
     }
 
-    /// The base progress that any action operating on `progress` will modify with its `efficiency`.
-    pub fn base_progress(&self) -> f64 {
-        let craftsmanship = self.problem_def.character.craftsmanship as f64;
-
+    /// The character's craftsmanship, the recipe level's craftsmanship, and the progress level
+    /// modifier.
+    fn progress_factors(&self) -> (u16, u16, u16) {
         let rlvl = self.problem_def.recipe.recipe_level;
         let clvl = self.problem_def.character.clvl();
 
-        let progress = craftsmanship * 21. / 100. + 2.;
-        let progress = progress * (craftsmanship + 10_000.)
-            / (rlvl.to_recipe_level_craftsmanship() as f64 / 10_000.);
-        progress * rlvl.to_progress_level_mod(clvl) as f64 / 100.
+        (
+            self.problem_def.character.craftsmanship,
+            rlvl.to_recipe_level_craftsmanship(),
+            rlvl.to_progress_level_mod(clvl),
+        )
     }
 
-    /// Generates the next state from the given delta, including sampling the new condition.
-    pub fn gen_succ<R: Rng>(self, delta: StateDelta, condition_rng: &mut R) -> Self {
-        Self {
-            condition: self.condition.sample(condition_rng),
-            ..self + delta
-        }
+    /// How much more progress is needed to complete the craft, or 0 if it's already complete
+    /// (including if the progress has gone over the recipe's maximum).
+    pub fn progress_remaining(&self) -> u32 {
+        self.problem_def
+            .recipe
+            .max_progress
+            .saturating_sub(self.curr_progress)
     }
 
-    /// Performs an action on the current state, yielding an [`Outcome`] value that corresponds to
-    /// the [`ActionOutcome`]. This uses [`act_random`] under the hood, and thus will panic if the action
-    /// cannot execute due to being out of CP or an action being used in an illegal state.
-    ///
-    /// [`Action`]s and [`Condition`]s use different [`Rng`]s for maximum reproducibility, despite
-    /// the minor inconvenience of managing two.
-    ///
-    /// The [`Condition`] is only rerolled if the [`Outcome`] is [`InProgress`], otherwise the generated successor
-    /// state inherits the [`Condition`] of this state.
+    /// How much more quality is needed to reach `target_quality`, or 0 if it's already been
+    /// reached. Since quality can go over the recipe's maximum, this doesn't clamp
+    /// `target_quality` to it, so pass the maximum (or [`required_quality`]) in if that's the goal.
     ///
-    /// [`act_random`]: crate::actions::Action::act_random
-    /// [`InProgress`]: Outcome::InProgress
-    pub fn act<A: Action + RandomAction, R1: Rng, R2: Rng>(
-        self,
-        action: A,
-        action_rng: &mut R1,
-        condition_rng: &mut R2,
-    ) -> Outcome<'a, C, M> {
-        let outcome = action.act_random(action_rng, &self).unwrap();
+    /// [`required_quality`]: RecipeStats::required_quality
+    pub fn quality_remaining_to(&self, target_quality: u32) -> u32 {
+        target_quality.saturating_sub(self.curr_quality)
+    }
 
-        match outcome {
-            ActionOutcome::Completed(delta) => Outcome::Completed {
-                state: self + delta,
-                delta,
-                outcome: outcome.map_quality(&self).unwrap(),
-            },
-            ActionOutcome::Failure(delta) => Outcome::Failure {
-                state: self + delta,
-                delta,
-            },
-            ActionOutcome::InProgress(delta) => Outcome::InProgress {
-                state: self.gen_succ(delta, condition_rng),
-                delta,
-            },
+    /// How far along the progress bar the craft is, from `0.0` to `1.0`. Progress over the
+    /// recipe's maximum is clamped to `1.0`, as is a recipe that needs no progress at all.
+    pub fn progress_fraction(&self) -> f64 {
+        let max = self.problem_def.recipe.max_progress;
+        if self.curr_progress >= max {
+            1.
+        } else {
+            self.curr_progress as f64 / max as f64
         }
     }
-}
 
-/// The outcome of executing an [`Action`] on a given [`CraftingState`]. This is analogous to
-/// [`ActionOutcome`]. In each of the variants, `state` is the next state, and `delta`
-/// is the [`StateDelta`] which was applied to the previous state to create it.
-pub enum Outcome<'a, C, M>
+    /// How far along the quality bar the craft is, from `0.0` to `1.0`, with the same clamping
+    /// as [`progress_fraction`](CraftingState::progress_fraction).
+    pub fn quality_fraction(&self) -> f64 {
+        let max = self.problem_def.recipe.max_quality;
+        if self.curr_quality >= max {
+            1.
+        } else {
+            self.curr_quality as f64 / max as f64
+        }
+    }
+
+    /// How many actions that pass time have been used so far, which is the step count the
+    /// game shows. Actions that stop time, like Final Appraisal, don't count.
+    #[cfg(feature = "step-count")]
+    pub fn steps(&self) -> u16 {
+        self.steps
+    }
+
+    /// Whether [`Observe`] was used last turn, so that [`FocusedSynthesis`] and [`FocusedTouch`]
+    /// are guaranteed to succeed right now. This is the same state their fail rate reads.
+    ///
+    /// [`Observe`]: crate::actions::misc::Observe
+    /// [`FocusedSynthesis`]: crate::actions::progress::FocusedSynthesis
+    /// [`FocusedTouch`]: crate::actions::quality::FocusedTouch
+    pub fn observe_combo_active(&self) -> bool {
+        self.buffs.combo.observation.is_active()
+    }
+
+    /// How far along the [`BasicTouch`] combo the craft is, i.e. whether [`StandardTouch`] or
+    /// [`AdvancedTouch`] would get their combo bonus right now. This is the same state their
+    /// CP costs read.
+    ///
+    /// [`BasicTouch`]: crate::actions::quality::BasicTouch
+    /// [`StandardTouch`]: crate::actions::quality::StandardTouch
+    /// [`AdvancedTouch`]: crate::actions::quality::AdvancedTouch
+    pub fn basic_touch_combo(&self) -> BasicTouchCombo {
+        self.buffs.combo.basic_touch
+    }
+
+    /// Lists the buffs the given action would consume or refresh if it were used in this state.
+    /// Buffs that simply tick down (or expire) because time passes aren't included.
+    ///
+    /// This runs the [`BuffAction`] stages of the action on a scratch copy of the
+    /// buffs, so it doesn't care whether the action can actually be executed.
+    ///
+    /// [`BuffAction`]: crate::actions::buffs::BuffAction
+    pub fn buffs_consumed_by<A: Action>(&self, action: A) -> Vec<BuffName> {
+        let mut natural = self.buffs;
+        let mut scratch = self.buffs;
+
+        action.deactivate_buff(self, &mut scratch);
+
+        if action.time_passed(self) {
+            natural.decay();
+            scratch.decay();
+        } else {
+            natural.combo.decay();
+            scratch.combo.decay();
+        }
+
+        action.buff(self, &mut scratch);
+
+        self.buffs.consumed_or_refreshed(&natural, &scratch)
+    }
+
+    /// Whether `action` can be used right now: the character has the level for it, it can be
+    /// executed in this state, and there's enough CP for it.
+    fn can_use(&self, action: FfxivCraftingActions) -> bool {
+        action.level() <= self.problem_def.character.char_level as u16
+            && action.can_execute(self)
+            && self.curr_cp + action.cp_cost(self) >= 0
+    }
+
+    /// For every action that can be used in this state (i.e. the character has the level for it,
+    /// it can be executed, and it's affordable), computes the quality and progress it
+    /// would add per point of CP it costs, taking into account buffs and the current condition.
+    /// Actions that cost no CP (or restore it) report their raw gain instead. Actions that can fail
+    /// are assumed to succeed.
+    ///
+    /// Each entry is `(action, quality_per_cp, progress_per_cp)`, in the order of
+    /// [`FfxivCraftingActions::ALL`].
+    pub fn action_efficiency_metrics(&self) -> Vec<(FfxivCraftingActions, f64, f64)> {
+        FfxivCraftingActions::ALL
+            .into_iter()
+            // Prospectively executing some invalid actions panics, e.g. consuming missing buffs
+            .filter(|&action| self.can_use(action))
+            .filter_map(|action| {
+                let next = *self + action.prospective_act(self).ok()?.outcome();
+                let cost = match -action.cp_cost(self) {
+                    cost if cost > 0 => cost as f64,
+                    _ => 1.,
+                };
+
+                Some((
+                    action,
+                    (next.curr_quality - self.curr_quality) as f64 / cost,
+                    (next.curr_progress - self.curr_progress) as f64 / cost,
+                ))
+            })
+            .collect()
+    }
+
+    /// A quick recommendation for the next action, for when running a solver is overkill. If the
+    /// quality isn't maxed yet, this is the action with the best quality per CP according to
+    /// [`action_efficiency_metrics`](CraftingState::action_efficiency_metrics), otherwise the one
+    /// with the best progress per CP. Actions that would break the item are never recommended.
+    ///
+    /// This is entirely greedy and is **not** optimal. It doesn't look ahead at all, so it will
+    /// happily spend all the durability on quality and leave none for progress, and like the
+    /// metrics it's based on it assumes actions that can fail will succeed. Returns [`None`] if no
+    /// affordable action adds anything useful.
+    pub fn recommend_action(&self) -> Option<FfxivCraftingActions> {
+        let safe = |action: &FfxivCraftingActions| {
+            !matches!(action.prospective_act(self), Ok(ActionOutcome::Failure(_)))
+        };
+        let metrics = self.action_efficiency_metrics();
+        let best = |gain: fn(&(FfxivCraftingActions, f64, f64)) -> f64| {
+            metrics
+                .iter()
+                .filter(|metric| gain(metric) > 0. && safe(&metric.0))
+                .max_by(|a, b| gain(a).total_cmp(&gain(b)))
+                .map(|(action, _, _)| *action)
+        };
+
+        if self.curr_quality < self.problem_def.recipe.max_quality {
+            best(|(_, quality, _)| *quality).or_else(|| best(|(_, _, progress)| *progress))
+        } else {
+            best(|(_, _, progress)| *progress)
+        }
+    }
+
+    /// The quality and progress `action` is expected to add in this state, weighting what it adds
+    /// on success and on failure by its [`fail_rate`](RandomAction::fail_rate). For actions that
+    /// can't fail this is simply what they add.
+    ///
+    /// This doesn't check whether the action can actually be executed.
+    pub fn expected_gain<A: RandomAction>(&self, action: A) -> (f64, f64) {
+        let gain = |outcome: ActionOutcome| {
+            let next = *self + outcome.outcome();
+            (
+                (next.curr_quality - self.curr_quality) as f64,
+                (next.curr_progress - self.curr_progress) as f64,
+            )
+        };
+
+        // Actions that can't fail panic if asked for their failure action
+        let fail_rate = action.fail_rate(self);
+        if fail_rate == 0 {
+            return gain(action.act(self));
+        }
+
+        let fail_chance = fail_rate as f64 / 100.;
+        let (fail_quality, fail_progress) = gain(action.fail_action().act(self));
+        let (quality, progress) = gain(action.act(self));
+
+        (
+            quality * (1. - fail_chance) + fail_quality * fail_chance,
+            progress * (1. - fail_chance) + fail_progress * fail_chance,
+        )
+    }
+
+    /// Checks whether the progress bar could still be filled from this state, ignoring quality.
+    ///
+    /// This is optimistic (admissible): it only returns `false` if no sequence of actions the
+    /// character has the level for can complete the craft, so it's safe to prune with. Rather than
+    /// searching, it over-estimates what the craft has left in it. Every synthesis is given the
+    /// best learned efficiency, Veneration, and the best condition, Waste Not is treated as always
+    /// being up, and all the CP left is spent on whichever learned repair buys the most durability
+    /// per CP, ignoring the recipe's maximum durability. CP is assumed to only ever go down, so
+    /// Tricks of the Trade is ignored.
+    pub fn can_still_complete(&self) -> bool {
+        use actions::{misc::MastersMend, progress::ProgressAction, DurabilityFactor};
+        use buffs::{
+            durability::{Manipulation, WasteNot},
+            progress::{ProgressEfficiencyMod, Veneration},
+            DurationalBuff,
+        };
+        use lookups::{DurabilityModifier, ProgressModifier, StatusDurationModifier};
+
+        let recipe = &self.problem_def.recipe;
+        if self.curr_progress >= recipe.max_progress {
+            return true;
+        }
+        if self.curr_durability <= 0 {
+            return false;
+        }
+
+        let learned = |action: FfxivCraftingActions| {
+            action.level() <= self.problem_def.character.char_level as u16
+        };
+        let synths: Vec<_> = FfxivCraftingActions::ALL
+            .into_iter()
+            .filter(|&action| learned(action) && action.base_efficiency(self) > 0)
+            .collect();
+
+        let Some(efficiency) = synths
+            .iter()
+            .map(|action| action.base_efficiency(self))
+            .max()
+        else {
+            return false;
+        };
+        // Usage is negative, so the cheapest synthesis is the largest
+        let usage = synths
+            .iter()
+            .map(|action| action.metadata().durability)
+            .max()
+            .unwrap_or_default();
+
+        // Expert recipes can be Malleable, Sturdy, or Primed on any step
+        let (progress_mod, durability_mod, duration_bonus) = if C::EXPERT {
+            (
+                ProgressModifier::Malleable,
+                DurabilityModifier::Sturdy,
+                StatusDurationModifier::Primed as u8,
+            )
+        } else {
+            (
+                self.condition.to_progress_modifier(),
+                self.condition.to_durability_modifier(),
+                0,
+            )
+        };
+
+        let waste_not_mod = if learned(FfxivCraftingActions::WasteNot)
+            || self.buffs.durability.waste_not.is_active()
+        {
+            WasteNot::DISCOUNT
+        } else {
+            100
+        };
+        let step_cost =
+            (-usage as f64 * durability_mod as u64 as f64 / 100. * waste_not_mod as f64 / 100.)
+                .ceil()
+                .max(1.);
+
+        // A Manipulation that's already up is paid for, so it only adds to the durability left
+        let manipulation_repair =
+            |duration: u8| (duration as f64 + 1.) * Manipulation::REPAIR_VALUE as f64;
+        let mut durability = self.curr_durability as f64;
+        if let Manipulation::Active(duration) = self.buffs.durability.manipulation {
+            durability += manipulation_repair(duration);
+        }
+
+        let repairs = [
+            (
+                FfxivCraftingActions::MastersMend,
+                MastersMend::DURABILITY_USAGE as f64,
+            ),
+            (
+                FfxivCraftingActions::Manipulation,
+                manipulation_repair(Manipulation::BASE_DURATION + duration_bonus),
+            ),
+            (
+                FfxivCraftingActions::ImmaculateMend,
+                recipe.max_durability as f64,
+            ),
+        ];
+        let per_cp = repairs
+            .into_iter()
+            .filter(|&(action, _)| learned(action))
+            .map(|(action, repair)| repair / -action.metadata().cp_cost as f64)
+            .fold(0., f64::max);
+        durability += self.curr_cp.max(0) as f64 * per_cp;
+
+        let mut steps = (durability / step_cost).ceil();
+        if learned(FfxivCraftingActions::TrainedPerfection)
+            && (self.buffs.durability.trained_perfection.is_available()
+                || self.buffs.durability.trained_perfection.is_active())
+        {
+            steps += 1.;
+        }
+
+        let veneration = if learned(FfxivCraftingActions::Veneration)
+            || self.buffs.progress.veneration.is_active()
+        {
+            Veneration::MODIFIER
+        } else {
+            0
+        };
+        let base = self.floored_base_progress() as f64 * progress_mod as u64 as f64 / 100.
+            * (100 + veneration) as f64
+            / 100.;
+
+        let mut reachable = steps * base * efficiency as f64 / 100.;
+        // Muscle Memory's bonus only ever applies to a single synthesis
+        if self.buffs.progress.muscle_memory.is_active()
+            || (learned(FfxivCraftingActions::MuscleMemory)
+                && FfxivCraftingActions::MuscleMemory.can_execute(self))
+        {
+            reachable += base;
+        }
+
+        reachable >= (recipe.max_progress - self.curr_progress) as f64
+    }
+
+    /// Checks whether the craft is stuck, i.e. it hasn't been completed and [`can_still_complete`]
+    /// says it can't be, even by spending the CP left on the repairs the character has learned.
+    ///
+    /// Since that check is optimistic, a craft this reports as stuck will fail no matter what is
+    /// done with it, so it's safe to give up on. CP doesn't come back over the course of a craft
+    /// other than through Tricks of the Trade on a Good or Excellent condition, which is ignored.
+    ///
+    /// [`can_still_complete`]: CraftingState::can_still_complete
+    pub fn is_stuck(&self) -> bool {
+        self.curr_progress < self.problem_def.recipe.max_progress && !self.can_still_complete()
+    }
+
+    /// Checks that the crafter is a specialist if the recipe [requires one]. A specialist with no
+    /// delineations left still counts.
+    ///
+    /// This isn't checked by [`CraftingSimulator::checked_new`], since whether the crafter is a
+    /// specialist is set on the state's buffs, not the character. [`new_simulation`] checks it
+    /// for the starting state, so this is for states whose buffs were changed afterwards.
+    ///
+    /// [`new_simulation`]: CraftingState::new_simulation
+    ///
+    /// [requires one]: RecipeStats::specialist_required
+    pub fn check_specialist(&self) -> Result<(), RecipeError> {
+        if self.problem_def.recipe.specialist_required
+            && !self.buffs.specialist_actions.is_specialist()
+        {
+            Err(RecipeError::SpecialistRequired)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The same state, but for a different problem, e.g. to see how a rotation that got this far
+    /// would fare on a harder recipe. Since the condition type is part of both types, the problem
+    /// is guaranteed to use the same kind of conditions.
+    ///
+    /// Nothing is re-clamped, so the durability, CP, quality and progress may be over the new
+    /// problem's maximums (or already enough to finish it). This is intentional, so the state is an
+    /// exact copy.
+    pub fn rebind<'b>(&self, problem_def: &'b CraftingSimulator<C, M>) -> CraftingState<'b, C, M> {
+        CraftingState {
+            problem_def,
+            condition: self.condition,
+            curr_quality: self.curr_quality,
+            curr_progress: self.curr_progress,
+            curr_durability: self.curr_durability,
+            curr_cp: self.curr_cp,
+            buffs: self.buffs,
+            first_step: self.first_step,
+            #[cfg(feature = "step-count")]
+            steps: self.steps,
+        }
+    }
+
+    /// Copies everything about the state except its problem definition, so it can be saved (with
+    /// the `serde` feature) or kept around without borrowing the problem. The state can be
+    /// brought back with [`CraftingSnapshot::rehydrate`].
+    pub fn snapshot(&self) -> CraftingSnapshot<C> {
+        CraftingSnapshot {
+            condition: self.condition,
+            curr_quality: self.curr_quality,
+            curr_progress: self.curr_progress,
+            curr_durability: self.curr_durability,
+            curr_cp: self.curr_cp,
+            buffs: self.buffs,
+            first_step: self.first_step,
+            #[cfg(feature = "step-count")]
+            steps: self.steps,
+        }
+    }
+
+    /// A compact, single line summary of the state for logging, such as
+    /// `[P 1200/3900 Q 4500/10920 D 50/70 CP 300/564 Normal t7]`.
+    ///
+    /// The state doesn't count steps itself without the `step-count` feature (see `first_step`),
+    /// so the caller passes in `step`.
+    /// Quality and progress over the recipe's maximum are shown along with the overshoot, e.g.
+    /// `Q 11000(+80)/10920`.
+    pub fn summary(&self, step: usize) -> String
+    where
+        C: Debug,
+    {
+        fn bar(curr: u32, max: u32) -> String {
+            if curr > max {
+                format!("{}(+{})/{}", curr, curr - max, max)
+            } else {
+                format!("{}/{}", curr, max)
+            }
+        }
+
+        let recipe = &self.problem_def.recipe;
+        format!(
+            "[P {} Q {} D {}/{} CP {}/{} {:?} t{}]",
+            bar(self.curr_progress, recipe.max_progress),
+            bar(self.curr_quality, recipe.max_quality),
+            self.curr_durability,
+            recipe.max_durability,
+            self.curr_cp,
+            self.problem_def.character.max_cp,
+            self.condition,
+            step,
+        )
+    }
+
+    /// Generates the next state from the given delta, including sampling the new condition.
+    ///
+    /// As in the game, the condition is kept as-is if the delta came from a [time stopping]
+    /// action, since no step has passed. An Excellent condition is thus held over a time stop for
+    /// the next action, and only proceeds to Poor once that action takes up a step. The exception
+    /// is [`CarefulObservation`], which stops time but still rolls a new condition.
+    ///
+    /// [time stopping]: crate::actions::TimePassing
+    /// [`CarefulObservation`]: crate::actions::misc::CarefulObservation
+    pub fn gen_succ<R: Rng>(self, delta: StateDelta, condition_rng: &mut R) -> Self {
+        let condition = if delta.condition_changed() {
+            self.condition.sample(condition_rng)
+        } else {
+            self.condition
+        };
+
+        Self {
+            condition,
+            ..self + delta
+        }
+    }
+
+    /// Performs an action on the current state, yielding an [`Outcome`] value that corresponds to
+    /// the [`ActionOutcome`]. This uses [`act_random`] under the hood, and thus will panic if the action
+    /// cannot execute due to being out of CP or an action being used in an illegal state.
+    ///
+    /// [`Action`]s and [`Condition`]s use different [`Rng`]s for maximum reproducibility, despite
+    /// the minor inconvenience of managing two.
+    ///
+    /// The [`Condition`] is only rerolled if the [`Outcome`] is [`InProgress`], otherwise the generated successor
+    /// state inherits the [`Condition`] of this state.
+    ///
+    /// [`act_random`]: crate::actions::Action::act_random
+    /// [`InProgress`]: Outcome::InProgress
+    pub fn act<A: Action + RandomAction, R1: Rng, R2: Rng>(
+        self,
+        action: A,
+        action_rng: &mut R1,
+        condition_rng: &mut R2,
+    ) -> Outcome<'a, C, M> {
+        let outcome = action.act_random(action_rng, &self).unwrap();
+
+        self.resolve(outcome, |state, delta| state.gen_succ(delta, condition_rng))
+    }
+
+    /// Performs every action of `rotation` in turn with [`act`](CraftingState::act), recording
+    /// what happened at each step in a [`Trace`]. This stops early if the craft completes or
+    /// fails, and like `act` it panics if an action can't be executed when it comes up.
+    ///
+    /// [`Trace`]: trace::Trace
+    pub fn run_traced<A, I, R1, R2>(
+        self,
+        rotation: I,
+        action_rng: &mut R1,
+        condition_rng: &mut R2,
+    ) -> trace::Trace<A, C, M::Outcome>
+    where
+        A: Action + RandomAction + Copy,
+        I: IntoIterator<Item = A>,
+        R1: Rng,
+        R2: Rng,
+    {
+        let mut steps = vec![];
+        let mut state = self;
+
+        for action in rotation {
+            let (next, delta, outcome) = match state.act(action, action_rng, condition_rng) {
+                Outcome::InProgress { state, delta } => (state, delta, OutcomeRank::InProgress),
+                Outcome::Completed {
+                    state,
+                    delta,
+                    outcome,
+                } => (state, delta, OutcomeRank::Completed(outcome)),
+                Outcome::Failure { state, delta } => (state, delta, OutcomeRank::Failure),
+            };
+
+            let finished = !matches!(outcome, OutcomeRank::InProgress);
+            steps.push(trace::TraceStep {
+                action,
+                delta,
+                condition: next.condition,
+                outcome,
+            });
+            state = next;
+
+            if finished {
+                break;
+            }
+        }
+
+        trace::Trace { steps }
+    }
+
+    /// Performs every action of `rotation` in turn with [`act`](CraftingState::act), stopping
+    /// early if the craft completes or fails. Like `act` this panics if an action can't be
+    /// executed when it comes up.
+    ///
+    /// This is [`run_traced`](CraftingState::run_traced) for when only where the craft ended up
+    /// matters, rather than everything along the way.
+    pub fn run_rotation<A, I, R1, R2>(
+        self,
+        rotation: I,
+        action_rng: &mut R1,
+        condition_rng: &mut R2,
+    ) -> RotationResult<'a, A, C, M>
+    where
+        A: Action + RandomAction + Copy,
+        I: IntoIterator<Item = A>,
+        R1: Rng,
+        R2: Rng,
+    {
+        let mut trace = self.run_traced(rotation, action_rng, condition_rng);
+        let state = trace.replay(self).pop().unwrap_or(self);
+        let deltas = trace.iter().map(|step| step.delta).collect();
+        let last_step = trace.len().saturating_sub(1);
+
+        let (outcome, finished_by) = match trace.steps.pop() {
+            None => (None, None),
+            Some(trace::TraceStep {
+                delta,
+                outcome: OutcomeRank::InProgress,
+                ..
+            }) => (Some(Outcome::InProgress { state, delta }), None),
+            Some(trace::TraceStep {
+                action,
+                delta,
+                outcome: OutcomeRank::Completed(outcome),
+                ..
+            }) => (
+                Some(Outcome::Completed {
+                    state,
+                    delta,
+                    outcome,
+                }),
+                Some((last_step, action)),
+            ),
+            Some(trace::TraceStep {
+                action,
+                delta,
+                outcome: OutcomeRank::Failure,
+                ..
+            }) => (
+                Some(Outcome::Failure { state, delta }),
+                Some((last_step, action)),
+            ),
+        };
+
+        RotationResult {
+            outcome,
+            finished_by,
+            deltas,
+        }
+    }
+
+    /// Turns an [`ActionOutcome`] from this state into an [`Outcome`], using `succ` to generate
+    /// the next state if the craft is still in progress.
+    fn resolve(
+        self,
+        outcome: ActionOutcome,
+        succ: impl FnOnce(Self, StateDelta) -> Self,
+    ) -> Outcome<'a, C, M> {
+        match outcome {
+            ActionOutcome::Completed(delta) => Outcome::Completed {
+                state: self + delta,
+                delta,
+                outcome: outcome.map_quality(&self).unwrap(),
+            },
+            ActionOutcome::Failure(delta) => Outcome::Failure {
+                state: self + delta,
+                delta,
+            },
+            ActionOutcome::InProgress(delta) => Outcome::InProgress {
+                state: succ(self, delta),
+                delta,
+            },
+        }
+    }
+}
+
+impl<'a, C, M> CraftingState<'a, C, M>
+where
+    C: ConditionTransitions + Default + PartialEq,
+    M: QualityMap,
+{
+    /// [`act`](CraftingState::act), but deterministically assuming the worst at every turn: an
+    /// action that can fail **always** fails, and the condition always moves to the least
+    /// favorable one that can follow the current one (e.g. Excellent always becomes Poor, and
+    /// Normal never becomes Good). Like [`gen_succ`](CraftingState::gen_succ), time stopping
+    /// actions keep the condition.
+    ///
+    /// Running a rotation through this gives a lower bound on how it can turn out, so if it
+    /// doesn't fail here it never will. Like [`act`](CraftingState::act), this panics if the
+    /// action can't be executed.
+    pub fn act_worst<A: Action + RandomAction>(self, action: A) -> Outcome<'a, C, M> {
+        // Actions that can't fail panic if asked for their failure action
+        let outcome = if action.fail_rate(&self) > 0 {
+            action.fail_action().act(&self)
+        } else {
+            action.act(&self)
+        };
+
+        self.resolve(outcome, |state, delta| Self {
+            condition: if delta.condition_changed() {
+                state.worst_condition()
+            } else {
+                state.condition
+            },
+            ..state + delta
+        })
+    }
+
+    /// The condition that can follow this one with the lowest quality modifier, preferring
+    /// Normal over anything else with the same modifier since every other condition helps in
+    /// some way.
+    fn worst_condition(&self) -> C {
+        self.condition
+            .transitions()
+            .into_iter()
+            .map(|(condition, _)| condition)
+            .min_by_key(|condition| {
+                (
+                    condition.to_quality_modifier() as u64,
+                    *condition != C::default(),
+                )
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl<'a, C, M> CraftingState<'a, C, M>
+where
+    C: Condition,
+    M: QualityMap<Outcome = HQChance>,
+{
+    /// The chance from 1-100 that the item comes out HQ at the current quality, as it would be
+    /// if the craft were completed now. Quality over the recipe's max (which is allowed, to see
+    /// how much a rotation overshoots by) counts as exactly the max.
+    pub fn hq_chance(&self) -> u8 {
+        M::convert(self.curr_quality, self.problem_def.recipe.max_quality).0
+    }
+}
+
+/// A [`CraftingState`] without its problem definition, made by [`CraftingState::snapshot`]. Unlike
+/// the state itself, this owns everything in it, so it can be serialized with the `serde` feature,
+/// e.g. to save a craft partway through.
+///
+/// Each field is the same as on [`CraftingState`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub struct CraftingSnapshot<C> {
+    pub condition: C,
+    pub curr_quality: u32,
+    pub curr_progress: u32,
+    pub curr_durability: i8,
+    pub curr_cp: i16,
+    pub buffs: BuffState,
+    first_step: bool,
+    #[cfg(feature = "step-count")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    steps: u16,
+}
+
+impl<C> CraftingSnapshot<C>
+where
+    C: Condition,
+{
+    /// Turns this back into a [`CraftingState`] of `problem_def`. Like
+    /// [`rebind`](CraftingState::rebind), nothing is checked against the problem, so this should
+    /// be the same problem the snapshot was taken from.
+    pub fn rehydrate<'a, M>(
+        self,
+        problem_def: &'a CraftingSimulator<C, M>,
+    ) -> CraftingState<'a, C, M>
+    where
+        M: QualityMap,
+    {
+        CraftingState {
+            problem_def,
+            condition: self.condition,
+            curr_quality: self.curr_quality,
+            curr_progress: self.curr_progress,
+            curr_durability: self.curr_durability,
+            curr_cp: self.curr_cp,
+            buffs: self.buffs,
+            first_step: self.first_step,
+            #[cfg(feature = "step-count")]
+            steps: self.steps,
+        }
+    }
+}
+
+/// The outcome of executing an [`Action`] on a given [`CraftingState`]. This is analogous to
+/// [`ActionOutcome`]. In each of the variants, `state` is the next state, and `delta`
+/// is the [`StateDelta`] which was applied to the previous state to create it.
+pub enum Outcome<'a, C, M>
 where
     C: Condition,
     M: QualityMap,
@@ -266,3 +1702,1630 @@ where
         delta: StateDelta,
     },
 }
+
+impl<'a, C, M> Outcome<'a, C, M>
+where
+    C: Condition,
+    M: QualityMap,
+{
+    /// The state the action led to, whichever way it turned out.
+    pub fn state(&self) -> &CraftingState<'a, C, M> {
+        match self {
+            Self::InProgress { state, .. }
+            | Self::Completed { state, .. }
+            | Self::Failure { state, .. } => state,
+        }
+    }
+
+    /// Which way the craft went, without the state, e.g. for matching on or comparing in tests.
+    pub fn kind(&self) -> OutcomeKind<M::Outcome>
+    where
+        M::Outcome: Copy,
+    {
+        match self {
+            Self::InProgress { .. } => OutcomeKind::InProgress,
+            Self::Completed { outcome, .. } => OutcomeKind::Completed(*outcome),
+            Self::Failure { .. } => OutcomeKind::Failure,
+        }
+    }
+}
+
+impl<'a, C, M> Outcome<'a, C, M>
+where
+    C: Condition,
+    M: QualityMap,
+    M::Outcome: Ord + Copy,
+{
+    /// A key for ranking outcomes, e.g. to pick the best of many simulations with
+    /// [`max_by_key`](Iterator::max_by_key).
+    ///
+    /// Completed crafts rank above everything else, ordered by their HQ chance (or collectability).
+    /// A craft that's still in progress ranks above a failed one, since it could still be completed,
+    /// but how far along it is doesn't matter.
+    pub fn rank(&self) -> OutcomeRank<M::Outcome> {
+        match self {
+            Self::Failure { .. } => OutcomeRank::Failure,
+            Self::InProgress { .. } => OutcomeRank::InProgress,
+            Self::Completed { outcome, .. } => OutcomeRank::Completed(*outcome),
+        }
+    }
+}
+
+/// Where a rotation run with [`CraftingState::run_rotation`] ended up.
+pub struct RotationResult<'a, A, C, M>
+where
+    C: Condition,
+    M: QualityMap,
+{
+    /// The outcome of the last action performed, or [`None`] if the rotation was empty.
+    pub outcome: Option<Outcome<'a, C, M>>,
+
+    /// If the craft completed or failed, the index of the action that did it, and the action.
+    pub finished_by: Option<(usize, A)>,
+
+    /// The delta of every action performed, in order.
+    pub deltas: Vec<StateDelta>,
+}
+
+/// The ranking of an [`Outcome`] returned by [`Outcome::rank`], from worst to best.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutcomeRank<O> {
+    /// The craft failed.
+    Failure,
+
+    /// The craft is still in progress.
+    InProgress,
+
+    /// The craft completed with the given HQ chance or collectability.
+    Completed(O),
+}
+
+/// Which way an [`Outcome`] went, without the state it led to, as returned by [`Outcome::kind`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutcomeKind<O> {
+    /// The craft is still in progress.
+    InProgress,
+
+    /// The craft completed with the given HQ chance or collectability.
+    Completed(O),
+
+    /// The craft failed.
+    Failure,
+}
+
+/// Checks which way the craft went without caring about the state, e.g.
+/// `outcome == OutcomeKind::Completed(HQChance(87))`.
+impl<'a, C, M> PartialEq<OutcomeKind<M::Outcome>> for Outcome<'a, C, M>
+where
+    C: Condition,
+    M: QualityMap,
+    M::Outcome: PartialEq,
+{
+    fn eq(&self, other: &OutcomeKind<M::Outcome>) -> bool {
+        match (self, other) {
+            (Self::Failure { .. }, OutcomeKind::Failure)
+            | (Self::InProgress { .. }, OutcomeKind::InProgress) => true,
+            (Self::Completed { outcome, .. }, OutcomeKind::Completed(other)) => outcome == other,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        actions::{
+            buffs::{FinalAppraisal, Manipulation},
+            errors::UndoError,
+            misc::{CarefulObservation, DelicateSynthesis, Observe},
+            progress::{BasicSynthesis, ProgressAction},
+            quality::{BasicTouch, HastyTouch, QualityAction},
+        },
+        buffs::{misc::SpecialistActions, Buff, DurationalBuff},
+        conditions::QARegularConditions,
+        quality_map::{CollectabilityMap, HQChance, HQMap},
+        test_util::{FixedRng, CLASSICAL_SIMULATOR, DAWNTRAIL_SIMULATOR},
+    };
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn problem_is_send_sync() {
+        assert_send_sync::<CharacterStats>();
+        assert_send_sync::<RecipeStats>();
+        assert_send_sync::<CraftingSimulator<QARegularConditions, HQMap>>();
+        assert_send_sync::<CraftingSimulator<QARegularConditions, CollectabilityMap>>();
+        assert_send_sync::<CraftingState<'static, QARegularConditions, HQMap>>();
+    }
+
+    #[test]
+    fn base_stats_follow_game_formula() {
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        assert_eq!(state.progress_factors(), (2000, 1498, 147));
+        assert_eq!(state.quality_factors(), (2000, 1387, 100));
+
+        // (2000 * 21 / 100 + 2) * (2000 + 10000) / (1498 + 10000) * 147 / 100 = 647.42...
+        assert!((state.base_progress() - 647.4239).abs() < 1e-4);
+        assert_eq!(state.floored_base_progress(), 647);
+
+        // (2000 * 35 / 100 + 35) * (2000 + 10000) / (1387 + 10000) * 100 / 100 = 774.57...
+        assert!((state.base_quality() - 774.5675).abs() < 1e-4);
+        assert_eq!(state.conditioned_base_quality(), 774);
+    }
+
+    #[test]
+    fn new_simulation_starts_fresh() {
+        fn check<M: QualityMap>(problem_def: &CraftingSimulator<QARegularConditions, M>) {
+            let state = CraftingState::new_simulation(problem_def);
+            assert_eq!(state.condition, QARegularConditions::Normal);
+            assert_eq!((state.curr_quality, state.curr_progress), (0, 0));
+            assert_eq!(state.curr_durability, problem_def.recipe.max_durability);
+            assert_eq!(state.curr_cp, problem_def.character.max_cp);
+            assert_eq!(state.buffs, BuffState::default());
+            assert_eq!(
+                state.buffs.specialist_actions,
+                SpecialistActions::NotSpecialist
+            );
+            assert!(state.first_step);
+        }
+
+        check(&CLASSICAL_SIMULATOR);
+        check(&CraftingSimulator::<_, CollectabilityMap>::new(
+            CLASSICAL_SIMULATOR.character,
+            CLASSICAL_SIMULATOR.recipe,
+            QARegularConditions::Normal,
+        ));
+    }
+
+    #[test]
+    fn recipe_from_factors() {
+        // rlvl 403
+        let level = RecipeLevelRanges::ShbLeveling(75);
+
+        let recipe = RecipeStats::from_factors(level, 100, 100, 100);
+        assert_eq!(recipe, Ok(RecipeStats::new(level, 80, 14_482, 3_407)));
+
+        // Progress and quality are rounded down
+        let recipe = RecipeStats::from_factors(level, 85, 75, 50);
+        assert_eq!(recipe, Ok(RecipeStats::new(level, 40, 10_861, 2_895)));
+
+        // Durability has to fit, rather than wrapping around to a negative value
+        let recipe = RecipeStats::from_factors(level, 100, 100, 158);
+        assert_eq!(recipe.map(|recipe| recipe.max_durability), Ok(126));
+        assert_eq!(
+            RecipeStats::from_factors(level, 100, 100, 160),
+            Err(RecipeFactorsError::DurabilityOutOfRange(128))
+        );
+        assert_eq!(
+            RecipeStats::from_factors(level, 100, 100, u16::MAX),
+            Err(RecipeFactorsError::DurabilityOutOfRange(52_428))
+        );
+    }
+
+    #[test]
+    fn food_and_medicine() {
+        let food = Consumable {
+            control: StatBonus {
+                percent: 26,
+                cap: 112,
+            },
+            cp: StatBonus {
+                percent: 6,
+                cap: 78,
+            },
+            ..Consumable::NONE
+        };
+        let medicine = Consumable {
+            cp: StatBonus {
+                percent: 6,
+                cap: 21,
+            },
+            ..Consumable::NONE
+        };
+
+        // The cap binds on control and the medicine, but not on the food's CP
+        let stats = CharacterStats::builder(3_000, 3_000, 500, 80)
+            .food(food)
+            .medicine(medicine)
+            .build();
+        assert_eq!(
+            stats,
+            CharacterStats {
+                craftsmanship: 3_000,
+                control: 3_112,
+                max_cp: 500 + 30 + 21,
+                char_level: 80,
+            }
+        );
+
+        // Low enough that the percentage binds, and rounds down
+        let stats = CharacterStats::builder(300, 301, 500, 80)
+            .food(food)
+            .build();
+        assert_eq!(stats.control, 301 + 78);
+
+        let base = CharacterStats::builder(3_000, 3_000, 500, 80);
+        assert_eq!(base.build(), base.food(Consumable::NONE).build());
+        assert_eq!(
+            base.build(),
+            CharacterStats {
+                craftsmanship: 3_000,
+                control: 3_000,
+                max_cp: 500,
+                char_level: 80,
+            }
+        );
+    }
+
+    #[test]
+    fn hq_chance_from_state() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let max = CLASSICAL_SIMULATOR.recipe.max_quality;
+
+        assert_eq!(state.hq_chance(), 1);
+
+        state.curr_quality = max / 2;
+        assert_eq!(state.hq_chance(), 15);
+
+        state.curr_quality = max;
+        assert_eq!(state.hq_chance(), 100);
+
+        state.curr_quality = max * 2;
+        assert_eq!(state.hq_chance(), 100);
+    }
+
+    #[test]
+    fn rotation_stops_on_completion() {
+        let mut start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        start.curr_progress = CLASSICAL_SIMULATOR.recipe.max_progress - 1;
+
+        let result = start.run_rotation(
+            [
+                FfxivCraftingActions::BasicTouch,
+                FfxivCraftingActions::BasicSynthesis,
+                FfxivCraftingActions::BasicTouch,
+            ],
+            &mut FixedRng::highest(),
+            &mut FixedRng::highest(),
+        );
+        assert_eq!(result.deltas.len(), 2);
+        assert_eq!(
+            result.finished_by,
+            Some((1, FfxivCraftingActions::BasicSynthesis))
+        );
+
+        let outcome = result.outcome.unwrap();
+        assert!(matches!(outcome, Outcome::Completed { .. }));
+        assert_eq!(
+            *outcome.state(),
+            result
+                .deltas
+                .iter()
+                .fold(start, |state, delta| state + *delta)
+        );
+
+        let result = start.run_rotation::<FfxivCraftingActions, _, _, _>(
+            [],
+            &mut FixedRng::highest(),
+            &mut FixedRng::highest(),
+        );
+        assert!(result.outcome.is_none());
+        assert!(result.finished_by.is_none());
+        assert!(result.deltas.is_empty());
+    }
+
+    #[test]
+    fn rotation_stops_on_breaking() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let touches = CLASSICAL_SIMULATOR.recipe.max_durability as usize / 10;
+
+        let result = start.run_rotation(
+            vec![FfxivCraftingActions::BasicTouch; touches + 3],
+            &mut FixedRng::highest(),
+            &mut FixedRng::highest(),
+        );
+        assert_eq!(result.deltas.len(), touches);
+        assert_eq!(
+            result.finished_by,
+            Some((touches - 1, FfxivCraftingActions::BasicTouch))
+        );
+
+        let outcome = result.outcome.unwrap();
+        assert!(matches!(outcome, Outcome::Failure { .. }));
+        assert_eq!(outcome.state().curr_durability, 0);
+
+        // Still going when the rotation runs out
+        let result = start.run_rotation(
+            [FfxivCraftingActions::BasicTouch],
+            &mut FixedRng::highest(),
+            &mut FixedRng::highest(),
+        );
+        assert!(matches!(result.outcome, Some(Outcome::InProgress { .. })));
+        assert!(result.finished_by.is_none());
+    }
+
+    #[test]
+    fn specialist_simulation_spends_delineations() {
+        use crate::actions::misc::CarefulObservation;
+
+        let mut state = CraftingState::new_specialist_simulation(&CLASSICAL_SIMULATOR, 2);
+        assert_eq!(
+            state.buffs.specialist_actions,
+            SpecialistActions::Availalble(2)
+        );
+
+        assert!(CarefulObservation.can_execute(&state));
+        state += CarefulObservation.act(&state).outcome();
+        assert_eq!(
+            state.buffs.specialist_actions,
+            SpecialistActions::Availalble(1)
+        );
+        assert!(state.first_step);
+
+        assert!(CarefulObservation.can_execute(&state));
+        state += CarefulObservation.act(&state).outcome();
+        assert_eq!(
+            state.buffs.specialist_actions,
+            SpecialistActions::Unavailable
+        );
+        assert!(!CarefulObservation.can_execute(&state));
+
+        let state = CraftingState::new_specialist_simulation(&CLASSICAL_SIMULATOR, 0);
+        assert!(!CarefulObservation.can_execute(&state));
+    }
+
+    #[test]
+    fn quick_innovation_once_per_craft() {
+        use crate::{
+            actions::{buffs::QuickInnovation, TimePassing},
+            buffs::quality::Innovation,
+        };
+
+        let state = CraftingState::new_specialist_simulation(&DAWNTRAIL_SIMULATOR, 3);
+        assert!(QuickInnovation.can_execute(&state));
+
+        let outcome = QuickInnovation.act(&state);
+        assert!(!QuickInnovation.time_passed(&state));
+        let next = state + outcome.outcome();
+        assert!(next.first_step);
+        assert_eq!(next.buffs.quality.innovation, Innovation::Active(1));
+        assert!(state
+            .buffs
+            .specialist_actions
+            .used_delineation(&next.buffs.specialist_actions));
+
+        // There are delineations to spare, but it's already been used this craft
+        assert!(next.buffs.specialist_actions.actions_available());
+        assert!(!QuickInnovation.can_execute(&next));
+
+        // The Innovation only lasts for the next action
+        let after = next + BasicTouch.act(&next).outcome();
+        assert!(BasicTouch.quality(&next) > BasicTouch.quality(&state));
+        assert!(after.buffs.quality.innovation.is_inactive());
+        assert!(!QuickInnovation.can_execute(&after));
+
+        let state = CraftingState::new_simulation(&DAWNTRAIL_SIMULATOR);
+        assert!(!QuickInnovation.can_execute(&state));
+
+        // Nor can a specialist use it before learning it
+        let usable = |problem_def| {
+            CraftingState::new_specialist_simulation(problem_def, 3)
+                .action_efficiency_metrics()
+                .iter()
+                .any(|&(action, ..)| action == FfxivCraftingActions::QuickInnovation)
+        };
+        assert!(usable(&DAWNTRAIL_SIMULATOR));
+        assert!(!usable(&CLASSICAL_SIMULATOR));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Too many crafters delineations")]
+    fn specialist_simulation_limits_delineations() {
+        CraftingState::new_specialist_simulation(&CLASSICAL_SIMULATOR, 4);
+    }
+
+    #[test]
+    fn identical_simulators_share_keys() {
+        use std::{
+            collections::hash_map::DefaultHasher,
+            hash::{Hash, Hasher},
+        };
+
+        fn hash<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let rebuilt = CraftingSimulator::<_, HQMap>::new(
+            CharacterStats {
+                craftsmanship: 2000,
+                control: 2000,
+                max_cp: 500,
+                char_level: 80,
+            },
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 80, 10_000, 3_000),
+            QARegularConditions::Normal,
+        );
+
+        assert_eq!(rebuilt, CLASSICAL_SIMULATOR);
+        assert_eq!(hash(&rebuilt), hash(&CLASSICAL_SIMULATOR));
+        assert_eq!(rebuilt.cache_key(), CLASSICAL_SIMULATOR.cache_key());
+
+        let different = CraftingSimulator::<_, HQMap>::new(
+            rebuilt.character,
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 80, 10_000, 3_001),
+            QARegularConditions::Normal,
+        );
+        assert_ne!(different.cache_key(), CLASSICAL_SIMULATOR.cache_key());
+
+        // Every recipe field is part of the key, even those that don't change the simulation
+        let recipe = CLASSICAL_SIMULATOR.recipe;
+        for recipe in [
+            recipe.with_required_quality(5_000),
+            recipe.with_result_quantity(3),
+            recipe.with_specialist_required(),
+        ] {
+            let different = CraftingSimulator { recipe, ..rebuilt };
+            assert_ne!(different.cache_key(), CLASSICAL_SIMULATOR.cache_key());
+        }
+    }
+
+    #[test]
+    fn character_stats_round_trip() {
+        let stats = CLASSICAL_SIMULATOR.character;
+        assert_eq!(stats.to_string(), "cms=2000,ctrl=2000,cp=500,lvl=80");
+        assert_eq!(stats.to_string().parse(), Ok(stats));
+        assert_eq!(" lvl = 80,cp=500, ctrl=2000,cms=2000".parse(), Ok(stats));
+
+        let parse = |s: &str| s.parse::<CharacterStats>();
+        assert_eq!(
+            parse("cms=2000"),
+            Err(ParseCharacterStatsError::MissingField("ctrl"))
+        );
+        assert_eq!(parse("cms"), Err(ParseCharacterStatsError::Malformed));
+        assert_eq!(
+            parse("cms=1,ctrl=1,cp=1,lvl=1,food=1"),
+            Err(ParseCharacterStatsError::UnknownField)
+        );
+        assert_eq!(
+            parse("cms=1,cms=2,ctrl=1,cp=1,lvl=1"),
+            Err(ParseCharacterStatsError::DuplicateField("cms"))
+        );
+        assert_eq!(
+            parse("cms=-1,ctrl=1,cp=1,lvl=1"),
+            Err(ParseCharacterStatsError::InvalidValue("cms"))
+        );
+        assert_eq!(
+            parse("cms=1,ctrl=1,cp=1,lvl=0"),
+            Err(ParseCharacterStatsError::LevelOutOfRange(0))
+        );
+        assert_eq!(
+            parse("cms=1,ctrl=1,cp=1,lvl=101"),
+            Err(ParseCharacterStatsError::LevelOutOfRange(101))
+        );
+    }
+
+    #[test]
+    fn efficiency_per_cp() {
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let metrics = state.action_efficiency_metrics();
+        let metric = |action| {
+            metrics
+                .iter()
+                .find(|(a, _, _)| *a == action)
+                .map(|(_, quality, progress)| (*quality, *progress))
+        };
+
+        let touch = metric(FfxivCraftingActions::BasicTouch).unwrap();
+        assert_eq!(touch, (BasicTouch.quality(&state) as f64 / 18., 0.));
+
+        // Free, so the raw gain is reported
+        let synth = metric(FfxivCraftingActions::BasicSynthesis).unwrap();
+        assert_eq!(synth, (0., BasicSynthesis.progress(&state) as f64));
+
+        // Needs Inner Quiet stacks
+        assert_eq!(metric(FfxivCraftingActions::ByregotsBlessing), None);
+    }
+
+    #[test]
+    fn metrics_only_cover_usable_actions() {
+        let mut problem = CLASSICAL_SIMULATOR;
+        problem.character.char_level = 5;
+
+        let state = CraftingState::new_simulation(&problem);
+        let metrics = state.action_efficiency_metrics();
+        assert!(!metrics.is_empty());
+        assert!(metrics.iter().all(|(action, _, _)| action.level() <= 5));
+        assert!(state.recommend_action().unwrap().level() <= 5);
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.curr_cp = 0;
+        let metrics = state.action_efficiency_metrics();
+        assert!(!metrics.is_empty());
+        assert!(metrics
+            .iter()
+            .all(|(action, _, _)| action.cp_cost(&state) >= 0));
+
+        // Hasty Touch is free, so it's the only touch left
+        assert_eq!(
+            state.recommend_action(),
+            Some(FfxivCraftingActions::HastyTouch)
+        );
+    }
+
+    #[test]
+    fn recommends_quality_then_progress() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let gain = |state: &CraftingState<_, _>, action: FfxivCraftingActions| {
+            let next = *state + action.act(state).outcome();
+            (
+                next.curr_quality - state.curr_quality,
+                next.curr_progress - state.curr_progress,
+            )
+        };
+
+        let touch = state.recommend_action().unwrap();
+        assert!(gain(&state, touch).0 > 0);
+
+        state.curr_quality = CLASSICAL_SIMULATOR.recipe.max_quality;
+        let synth = state.recommend_action().unwrap();
+        assert!(gain(&state, synth).1 > 0);
+
+        // Most touches would break the item here
+        state.curr_quality = 0;
+        state.curr_durability = 10;
+        state.curr_progress = CLASSICAL_SIMULATOR.recipe.max_progress - 1;
+        let last = state.recommend_action().unwrap();
+        assert!(!matches!(last.act(&state), ActionOutcome::Failure(_)));
+    }
+
+    #[test]
+    fn worst_case_fails_and_turns_poor() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+
+        let Outcome::InProgress { state: next, .. } = state.act_worst(HastyTouch) else {
+            panic!("Hasty Touch shouldn't finish the craft");
+        };
+        assert_eq!(next.curr_quality, 0);
+        assert_eq!(next.condition, QARegularConditions::Normal);
+
+        state.condition = QARegularConditions::Excellent;
+        let Outcome::InProgress { state: next, .. } = state.act_worst(BasicTouch) else {
+            panic!("Basic Touch shouldn't finish the craft");
+        };
+        assert!(next.curr_quality > 0);
+        assert_eq!(next.condition, QARegularConditions::Poor);
+
+        state.condition = QARegularConditions::Good;
+        let Outcome::InProgress { state: next, .. } = state.act_worst(BasicSynthesis) else {
+            panic!("Basic Synthesis shouldn't finish the craft");
+        };
+        assert_eq!(next.condition, QARegularConditions::Normal);
+    }
+
+    #[test]
+    fn expected_gain_weights_failure() {
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+
+        // Same efficiency as Basic Touch, but fails 40% of the time
+        let (quality, progress) = state.expected_gain(HastyTouch);
+        assert!((quality - BasicTouch.quality(&state) as f64 * 0.6).abs() < 1e-9);
+        assert_eq!(progress, 0.);
+
+        let (quality, progress) = state.expected_gain(BasicSynthesis);
+        assert_eq!(quality, 0.);
+        assert_eq!(progress, BasicSynthesis.progress(&state) as f64);
+    }
+
+    #[test]
+    fn outcomes_rank_by_hq() {
+        let mut rng = FixedRng::highest();
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+
+        let mut nearly_done = state;
+        nearly_done.curr_progress = CLASSICAL_SIMULATOR.recipe.max_progress - 1;
+        let nq = nearly_done.act(BasicSynthesis, &mut rng, &mut FixedRng::highest());
+
+        nearly_done.curr_quality = CLASSICAL_SIMULATOR.recipe.max_quality;
+        let hq = nearly_done.act(BasicSynthesis, &mut rng, &mut FixedRng::highest());
+
+        let in_progress = state.act(BasicSynthesis, &mut rng, &mut FixedRng::highest());
+
+        let mut breaking = state;
+        breaking.curr_durability = 10;
+        let failed = breaking.act(BasicTouch, &mut rng, &mut FixedRng::highest());
+
+        assert!(matches!(failed, Outcome::Failure { .. }));
+        assert!(failed.rank() < in_progress.rank());
+        assert!(in_progress.rank() < nq.rank());
+        assert!(nq.rank() < hq.rank());
+        assert_eq!(hq.rank(), OutcomeRank::Completed(HQChance(100)));
+    }
+
+    #[test]
+    fn outcomes_compare_to_kinds() {
+        let mut rng = FixedRng::highest();
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+
+        let mut nearly_done = state;
+        nearly_done.curr_progress = CLASSICAL_SIMULATOR.recipe.max_progress - 1;
+        nearly_done.curr_quality = CLASSICAL_SIMULATOR.recipe.max_quality;
+        let hq = nearly_done.act(BasicSynthesis, &mut rng, &mut FixedRng::highest());
+
+        assert_eq!(hq.kind(), OutcomeKind::Completed(HQChance(100)));
+        assert!(hq == OutcomeKind::Completed(HQChance(100)));
+        assert!(hq != OutcomeKind::Completed(HQChance(99)));
+        assert!(hq != OutcomeKind::InProgress);
+        assert!(hq.state().curr_progress >= CLASSICAL_SIMULATOR.recipe.max_progress);
+
+        let in_progress = state.act(BasicSynthesis, &mut rng, &mut FixedRng::highest());
+        assert_eq!(in_progress.kind(), OutcomeKind::InProgress);
+        assert!(in_progress == OutcomeKind::InProgress);
+        assert!(in_progress != OutcomeKind::Failure);
+        assert_eq!(
+            in_progress.state().curr_durability,
+            CLASSICAL_SIMULATOR.recipe.max_durability - 10
+        );
+
+        let mut breaking = state;
+        breaking.curr_durability = 10;
+        let failed = breaking.act(BasicTouch, &mut rng, &mut FixedRng::highest());
+        assert_eq!(failed.kind(), OutcomeKind::Failure);
+        assert!(failed == OutcomeKind::Failure);
+    }
+
+    #[test]
+    fn rebind_keeps_progression() {
+        let harder = CraftingSimulator::new(
+            CLASSICAL_SIMULATOR.character,
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(79), 40, 5_000, 2_000),
+            QARegularConditions::Normal,
+        );
+
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let state = state + BasicTouch.act(&state).outcome();
+        let rebound = state.rebind(&harder);
+
+        assert_eq!(rebound, state);
+        assert!(!rebound.first_step);
+        assert_eq!(rebound.problem_def.recipe.max_quality, 5_000);
+        assert!(rebound.curr_durability > rebound.problem_def.recipe.max_durability);
+    }
+
+    #[test]
+    fn snapshots_rehydrate() {
+        use crate::{actions::buffs::Innovation, buffs::durability};
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state += Innovation.act(&state).outcome();
+        state += BasicTouch.act(&state).outcome();
+        state.buffs.durability.manipulation = durability::Manipulation::Active(3);
+        state.condition = QARegularConditions::Excellent;
+
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.condition, QARegularConditions::Excellent);
+        assert_eq!(snapshot.buffs, state.buffs);
+
+        let rehydrated = snapshot.rehydrate(&CLASSICAL_SIMULATOR);
+        assert_eq!(rehydrated, state);
+        assert!(!rehydrated.first_step);
+        assert_eq!(
+            rehydrated.buffs.active(),
+            [
+                BuffName::Innovation,
+                BuffName::Manipulation,
+                BuffName::BasicTouchCombo
+            ]
+        );
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&snapshot).unwrap();
+            let loaded: CraftingSnapshot<QARegularConditions> =
+                serde_json::from_str(&json).unwrap();
+            assert_eq!(loaded, snapshot);
+            assert_eq!(loaded.rehydrate(&CLASSICAL_SIMULATOR), state);
+        }
+    }
+
+    #[test]
+    fn summary_shows_overshoot() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let recipe = CLASSICAL_SIMULATOR.recipe;
+        let cp = CLASSICAL_SIMULATOR.character.max_cp;
+        assert_eq!(
+            state.summary(0),
+            format!(
+                "[P 0/{} Q 0/{} D {d}/{d} CP {cp}/{cp} Normal t0]",
+                recipe.max_progress,
+                recipe.max_quality,
+                d = recipe.max_durability,
+                cp = cp
+            )
+        );
+
+        state.curr_quality = recipe.max_quality + 80;
+        assert!(state.summary(7).contains(&format!(
+            "Q {}(+80)/{} ",
+            recipe.max_quality + 80,
+            recipe.max_quality
+        )));
+        assert!(state.summary(7).ends_with(" t7]"));
+    }
+
+    #[test]
+    fn remaining_clamps_at_completion() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let recipe = CLASSICAL_SIMULATOR.recipe;
+
+        assert_eq!(state.progress_remaining(), recipe.max_progress);
+        assert_eq!(
+            state.quality_remaining_to(recipe.max_quality),
+            recipe.max_quality
+        );
+        assert_eq!(state.progress_fraction(), 0.);
+        assert_eq!(state.quality_fraction(), 0.);
+
+        state.curr_progress = recipe.max_progress / 2;
+        state.curr_quality = recipe.max_quality / 4;
+        assert_eq!(
+            state.progress_remaining(),
+            recipe.max_progress - recipe.max_progress / 2
+        );
+        assert_eq!(state.quality_remaining_to(recipe.max_quality / 4 + 1), 1);
+        assert!((state.progress_fraction() - 0.5).abs() < 0.01);
+        assert!((state.quality_fraction() - 0.25).abs() < 0.01);
+
+        state.curr_progress = recipe.max_progress;
+        state.curr_quality = recipe.max_quality;
+        assert_eq!(state.progress_remaining(), 0);
+        assert_eq!(state.quality_remaining_to(recipe.max_quality), 0);
+        assert_eq!(state.progress_fraction(), 1.);
+        assert_eq!(state.quality_fraction(), 1.);
+
+        state.curr_progress = recipe.max_progress + 300;
+        state.curr_quality = recipe.max_quality + 80;
+        assert_eq!(state.progress_remaining(), 0);
+        assert_eq!(state.quality_remaining_to(recipe.max_quality), 0);
+        assert_eq!(state.quality_remaining_to(recipe.max_quality + 100), 20);
+        assert_eq!(state.progress_fraction(), 1.);
+        assert_eq!(state.quality_fraction(), 1.);
+    }
+
+    #[test]
+    fn opening_actions_depend_on_level() {
+        use FfxivCraftingActions::*;
+
+        let opening = CLASSICAL_SIMULATOR.opening_actions();
+        for action in [MuscleMemory, Reflect, BasicSynthesis, BasicTouch, Observe] {
+            assert!(opening.contains(&action), "{:?}", action);
+        }
+        for action in [
+            TrainedEye,
+            ByregotsBlessing,
+            PrudentSynthesis,
+            TrainedFinesse,
+        ] {
+            assert!(!opening.contains(&action), "{:?}", action);
+        }
+
+        let mut character = CLASSICAL_SIMULATOR.character;
+        character.char_level = 90;
+        let problem_def = CraftingSimulator::<_, HQMap>::new(
+            character,
+            CLASSICAL_SIMULATOR.recipe,
+            QARegularConditions::Normal,
+        );
+        assert!(problem_def.opening_actions().contains(&TrainedEye));
+
+        character.char_level = 50;
+        let problem_def = CraftingSimulator::<_, HQMap>::new(
+            character,
+            CLASSICAL_SIMULATOR.recipe,
+            QARegularConditions::Normal,
+        );
+        let opening = problem_def.opening_actions();
+        assert!(opening.contains(&BasicSynthesis));
+        assert!(!opening.contains(&MuscleMemory));
+        assert!(!opening.contains(&Reflect));
+    }
+
+    #[test]
+    fn dawntrail_actions_need_their_level() {
+        use FfxivCraftingActions::*;
+
+        let usable = |problem_def| {
+            let mut state = CraftingState::new_simulation(problem_def);
+            state.curr_durability = 20;
+            state.buffs.quality.expedience = buffs::quality::Expedience::Active(1);
+            state
+                .action_efficiency_metrics()
+                .into_iter()
+                .map(|(action, ..)| action)
+                .collect::<Vec<_>>()
+        };
+
+        let shb = usable(&CLASSICAL_SIMULATOR);
+        let dt = usable(&DAWNTRAIL_SIMULATOR);
+        for action in [RefinedTouch, DaringTouch, ImmaculateMend, TrainedPerfection] {
+            assert!(!shb.contains(&action), "{:?}", action);
+            assert!(dt.contains(&action), "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn quality_requirement() {
+        let recipe = CLASSICAL_SIMULATOR.recipe;
+        assert_eq!(recipe.required_quality(), 0);
+        assert!(recipe.meets_quality_requirement(0));
+
+        let recipe = recipe.with_required_quality(4_000);
+        assert_eq!(recipe.required_quality(), 4_000);
+        assert!(!recipe.meets_quality_requirement(3_999));
+        assert!(recipe.meets_quality_requirement(4_000));
+    }
+
+    #[test]
+    fn checked_new_requires_level() {
+        let CraftingSimulator {
+            character,
+            recipe,
+            conditions,
+            ..
+        } = CLASSICAL_SIMULATOR;
+
+        assert!(CraftingSimulator::<_, HQMap>::checked_new(character, recipe, conditions).is_ok());
+
+        let character = CharacterStats {
+            char_level: recipe.required_character_level() - 1,
+            ..character
+        };
+        assert_eq!(
+            CraftingSimulator::<_, HQMap>::checked_new(character, recipe, conditions),
+            Err(RecipeError::CharacterTooLowLevel {
+                required: recipe.required_character_level(),
+                actual: character.char_level,
+            })
+        );
+
+        for char_level in [0, CharacterStats::MAX_LEVEL + 1, u8::MAX] {
+            let character = CharacterStats {
+                char_level,
+                ..character
+            };
+            let recipe = RecipeStats {
+                recipe_level: RecipeLevelRanges::ArrLeveling(1),
+                ..recipe
+            };
+            assert_eq!(
+                CraftingSimulator::<_, HQMap>::checked_new(character, recipe, conditions),
+                Err(RecipeError::LevelOutOfRange(char_level))
+            );
+        }
+    }
+
+    #[test]
+    fn specialist_recipes() {
+        let mut problem = CLASSICAL_SIMULATOR;
+        assert_eq!(
+            CraftingState::new_simulation(&problem).check_specialist(),
+            Ok(())
+        );
+
+        problem.recipe = problem.recipe.with_specialist_required();
+        assert!(problem.recipe.specialist_required());
+        let mut state = CraftingState::new_specialist_simulation(&problem, 3);
+        assert_eq!(state.check_specialist(), Ok(()));
+        state.buffs.specialist_actions = SpecialistActions::Unavailable;
+        assert_eq!(state.check_specialist(), Ok(()));
+
+        state.buffs.specialist_actions = SpecialistActions::NotSpecialist;
+        assert_eq!(
+            state.check_specialist(),
+            Err(RecipeError::SpecialistRequired)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The recipe can only be made by a specialist")]
+    fn non_specialists_cant_start_specialist_recipes() {
+        let mut problem = CLASSICAL_SIMULATOR;
+        problem.recipe = problem.recipe.with_specialist_required();
+        CraftingState::new_simulation(&problem);
+    }
+
+    #[test]
+    fn undo_reverses_actions() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let mut history = vec![];
+        let mut state = start;
+
+        for action in [
+            FfxivCraftingActions::MuscleMemory,
+            FfxivCraftingActions::Veneration,
+            FfxivCraftingActions::BasicTouch,
+            FfxivCraftingActions::StandardTouch,
+            FfxivCraftingActions::BasicSynthesis,
+        ] {
+            let delta = action.prospective_act(&state).unwrap().outcome();
+            history.push((state, delta));
+            state += delta;
+        }
+
+        for (prev, delta) in history.into_iter().rev() {
+            state = state.undo(delta, prev.buffs).unwrap();
+            assert_eq!(state, prev);
+        }
+
+        assert_eq!(state, start);
+        assert!(state.first_step);
+    }
+
+    #[test]
+    fn undo_stops_at_the_start() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let undo = |action: FfxivCraftingActions| {
+            let delta = action.prospective_act(&start).unwrap().outcome();
+            start.undo(delta, start.buffs)
+        };
+
+        assert_eq!(
+            undo(FfxivCraftingActions::BasicTouch),
+            Err(UndoError::QualityUnderflow)
+        );
+        assert_eq!(
+            undo(FfxivCraftingActions::BasicSynthesis),
+            Err(UndoError::ProgressUnderflow)
+        );
+        assert_eq!(
+            undo(FfxivCraftingActions::Veneration),
+            Err(UndoError::CpOutOfRange)
+        );
+
+        // Undoing a delta from the state it was added to is fine
+        let delta = FfxivCraftingActions::BasicSynthesis
+            .prospective_act(&start)
+            .unwrap()
+            .outcome();
+        let prev = (start + delta).undo(delta, start.buffs);
+        assert_eq!(prev, Ok(start));
+
+        // But not twice
+        assert_eq!(
+            prev.unwrap().undo(delta, start.buffs),
+            Err(UndoError::ProgressUnderflow)
+        );
+    }
+
+    #[test]
+    fn integer_base_stats_match_floats() {
+        use crate::lookups::{CLVL, RLVL};
+        use std::collections::BTreeSet;
+
+        // Every combination of recipe and level modifiers a character can actually run into
+        let mut quality_factors = BTreeSet::new();
+        let mut progress_factors = BTreeSet::new();
+        for rlvl in RLVL.map(RecipeLevelRanges::from_rlvl) {
+            for clvl in CLVL {
+                quality_factors.insert((
+                    rlvl.to_recipe_level_control(),
+                    rlvl.to_quality_level_mod(clvl),
+                ));
+                progress_factors.insert((
+                    rlvl.to_recipe_level_craftsmanship(),
+                    rlvl.to_progress_level_mod(clvl),
+                ));
+            }
+        }
+
+        let condition_mods = [
+            QARegularConditions::Normal,
+            QARegularConditions::Good,
+            QARegularConditions::Excellent,
+            QARegularConditions::Poor,
+        ]
+        .map(|condition| condition.to_quality_modifier() as u16);
+
+        for stat in 0..=u16::MAX {
+            for &(rlvl_control, level_mod) in &quality_factors {
+                let quality = float_base_quality(stat, rlvl_control, level_mod);
+                for condition_mod in condition_mods {
+                    assert_eq!(
+                        conditioned_base_quality(stat, rlvl_control, level_mod, condition_mod),
+                        (quality * condition_mod as u64 as f64 / 100.).floor() as u32,
+                        "control {}, rlvl control {}, level mod {}, condition mod {}",
+                        stat,
+                        rlvl_control,
+                        level_mod,
+                        condition_mod
+                    );
+                }
+            }
+
+            for &(rlvl_craftsmanship, level_mod) in &progress_factors {
+                assert_eq!(
+                    floored_base_progress(stat, rlvl_craftsmanship, level_mod),
+                    float_base_progress(stat, rlvl_craftsmanship, level_mod).floor() as u32,
+                    "craftsmanship {}, rlvl craftsmanship {}, level mod {}",
+                    stat,
+                    rlvl_craftsmanship,
+                    level_mod
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn clvl_matches_the_game() {
+        // The game's crafting levels are the level itself up to 50, then jump every expansion
+        for (char_level, clvl) in [
+            (1, 1),
+            (50, 50),
+            (51, 120),
+            (60, 150),
+            (61, 260),
+            (70, 290),
+            (71, 390),
+            (80, 420),
+            (81, 517),
+            (90, 560),
+            (91, 650),
+            (100, 690),
+        ] {
+            let character = CharacterStats {
+                char_level,
+                ..CLASSICAL_SIMULATOR.character
+            };
+            assert_eq!(character.clvl(), clvl, "level {char_level}");
+        }
+
+        // So a recipe of the character's own level has no level modifier, where looking up the
+        // level above made a level 49 character count as a level 50 one
+        for (char_level, recipe) in [
+            (1, RecipeLevelRanges::ArrLeveling(1)),
+            (49, RecipeLevelRanges::ArrLeveling(49)),
+        ] {
+            let character = CharacterStats {
+                char_level,
+                ..CLASSICAL_SIMULATOR.character
+            };
+            assert_eq!(recipe.to_progress_level_mod(character.clvl()), 100);
+            assert_eq!(recipe.to_quality_level_mod(character.clvl()), 100);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Characters start at level 1")]
+    fn clvl_rejects_level_zero() {
+        let character = CharacterStats {
+            char_level: 0,
+            ..CLASSICAL_SIMULATOR.character
+        };
+        character.clvl();
+    }
+
+    #[test]
+    fn fresh_craft_can_complete() {
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        assert!(state.can_still_complete());
+    }
+
+    #[test]
+    fn no_durability_cannot_complete() {
+        // Far more than a single lucky Rapid Synthesis could finish
+        let mut problem = CLASSICAL_SIMULATOR;
+        problem.recipe.max_progress = 20_000;
+
+        let mut state = CraftingState::new_simulation(&problem);
+        state.curr_durability = 10;
+        state.curr_cp = 0;
+        assert!(!state.can_still_complete());
+
+        // CP left over can buy more durability
+        state.curr_cp = problem.character.max_cp;
+        assert!(state.can_still_complete());
+
+        state.curr_cp = 0;
+        state.curr_progress = problem.recipe.max_progress - 1;
+        assert!(state.can_still_complete());
+    }
+
+    #[test]
+    fn can_still_complete_is_optimistic() {
+        // Rapid Synthesis fails half the time, but a success with Veneration would finish this
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.curr_durability = 10;
+        state.curr_cp = 0;
+        assert!(state.can_still_complete());
+    }
+
+    #[test]
+    fn can_still_complete_needs_the_level() {
+        let mut problem = CLASSICAL_SIMULATOR;
+        assert!(CraftingState::new_simulation(&problem).can_still_complete());
+
+        // Nothing but Basic Synthesis, without Waste Not, Veneration, or any repairs
+        problem.character.char_level = 5;
+        assert!(!CraftingState::new_simulation(&problem).can_still_complete());
+    }
+
+    #[test]
+    fn out_of_cp_is_stuck() {
+        let mut problem = CLASSICAL_SIMULATOR;
+        problem.recipe.max_progress = 20_000;
+
+        let mut state = CraftingState::new_simulation(&problem);
+        state.curr_durability = 10;
+        assert!(!state.is_stuck());
+
+        state.curr_cp = 10;
+        assert!(state.is_stuck());
+
+        state.curr_progress = problem.recipe.max_progress - 1;
+        assert!(!state.is_stuck());
+    }
+
+    #[test]
+    fn stuck_without_learned_repairs() {
+        use crate::actions::misc::MastersMend;
+
+        let mut problem = CLASSICAL_SIMULATOR;
+        problem.character.char_level = 5;
+        let base = CraftingState::new_simulation(&problem).floored_base_progress();
+        problem.recipe.max_progress = 5 * base;
+
+        // Master's Mend is affordable, but only learned at level 7
+        let mut state = CraftingState::new_simulation(&problem);
+        state.curr_durability = 10;
+        assert!(state.curr_cp + MastersMend.cp_cost(&state) >= 0);
+        assert!(state.is_stuck());
+
+        problem.character.char_level = 7;
+        let mut state = CraftingState::new_simulation(&problem);
+        state.curr_durability = 10;
+        assert!(!state.is_stuck());
+    }
+
+    #[test]
+    fn delicate_synthesis_consumes_strides_and_muscle_memory() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.quality.great_strides.activate_in_place(0);
+        state.buffs.progress.muscle_memory.activate_in_place(0);
+        state.buffs.progress.veneration.activate_in_place(0);
+
+        assert_eq!(
+            state.buffs_consumed_by(DelicateSynthesis),
+            vec![BuffName::GreatStrides, BuffName::MuscleMemory]
+        );
+    }
+
+    #[test]
+    fn decay_is_not_consumption() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.quality.great_strides.activate_in_place(0);
+
+        assert_eq!(state.buffs_consumed_by(Observe), vec![]);
+    }
+
+    #[test]
+    fn great_strides_is_only_consumed_by_quality() {
+        use crate::{
+            actions::{buffs::GreatStrides as UseGreatStrides, collection::FfxivCraftingActions},
+            buffs::quality::{GreatStrides, InnerQuiet},
+        };
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state += UseGreatStrides.act(&state).outcome();
+        let duration = GreatStrides::BASE_DURATION;
+        assert_eq!(
+            state.buffs.quality.great_strides,
+            GreatStrides::Active(duration)
+        );
+
+        // Stopping time neither consumes nor decays it
+        state += FinalAppraisal.act(&state).outcome();
+        assert_eq!(
+            state.buffs.quality.great_strides,
+            GreatStrides::Active(duration)
+        );
+
+        // Passing time without touching only decays it
+        state += Observe.act(&state).outcome();
+        assert_eq!(
+            state.buffs.quality.great_strides,
+            GreatStrides::Active(duration - 1)
+        );
+        state += BasicSynthesis.act(&state).outcome();
+        assert_eq!(
+            state.buffs.quality.great_strides,
+            GreatStrides::Active(duration - 2)
+        );
+
+        // While any touch used right after it uses it up entirely, and gets the bonus
+        for touch in [
+            FfxivCraftingActions::BasicTouch,
+            FfxivCraftingActions::StandardTouch,
+            FfxivCraftingActions::PreciseTouch,
+            FfxivCraftingActions::ByregotsBlessing,
+            FfxivCraftingActions::HastyTouch,
+        ] {
+            let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+            state.condition = QARegularConditions::Good;
+            state.buffs.quality.inner_quiet = InnerQuiet::Active(1);
+            state += UseGreatStrides.act(&state).outcome();
+
+            let boosted = touch.quality(&state);
+            let mut unboosted = state;
+            unboosted.buffs.quality.great_strides = GreatStrides::Inactive;
+            assert!(boosted > touch.quality(&unboosted), "{touch:?}");
+
+            state.condition = QARegularConditions::Good;
+            state += touch.act(&state).outcome();
+            assert_eq!(
+                state.buffs.quality.great_strides,
+                GreatStrides::Inactive,
+                "{touch:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn reapplying_is_refreshing() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.durability.manipulation.activate_in_place(0);
+        state.buffs.durability.manipulation.decay_in_place();
+
+        assert_eq!(
+            state.buffs_consumed_by(Manipulation),
+            vec![BuffName::Manipulation]
+        );
+    }
+
+    #[test]
+    fn primed_reapplication_resets_duration() {
+        use crate::{
+            actions::buffs::WasteNot, buffs::durability, conditions::RestoExpertConditions,
+        };
+
+        let problem_def = CraftingSimulator::<_, HQMap>::new(
+            CLASSICAL_SIMULATOR.character,
+            CLASSICAL_SIMULATOR.recipe,
+            RestoExpertConditions::Normal,
+        );
+        let mut state = CraftingState::new_simulation(&problem_def);
+        let primed = durability::WasteNot::BASE_DURATION + 2;
+
+        state.condition = RestoExpertConditions::Primed;
+        state += WasteNot.act(&state).outcome();
+        assert_eq!(
+            state.buffs.durability.waste_not,
+            durability::WasteNot::WasteNot(primed)
+        );
+
+        // Primed again on the very next turn, the bonus applies to a fresh duration rather than
+        // stacking onto what's left
+        state.condition = RestoExpertConditions::Primed;
+        state += WasteNot.act(&state).outcome();
+        assert_eq!(
+            state.buffs.durability.waste_not,
+            durability::WasteNot::WasteNot(primed)
+        );
+
+        state.condition = RestoExpertConditions::Primed;
+        state += Manipulation.act(&state).outcome();
+        assert_eq!(
+            state.buffs.durability.manipulation,
+            durability::Manipulation::Active(durability::Manipulation::BASE_DURATION + 2)
+        );
+        assert_eq!(
+            state.buffs.durability.waste_not,
+            durability::WasteNot::WasteNot(primed - 1)
+        );
+    }
+
+    #[test]
+    fn manipulation_repairs_after_failure_check() {
+        use crate::{actions::misc::MastersMend, buffs::durability};
+
+        let max = CLASSICAL_SIMULATOR.recipe.max_durability;
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.durability.manipulation = durability::Manipulation::Active(4);
+
+        // At max durability the repair makes up for half the touch, rather than being lost
+        assert_eq!(
+            (state + BasicTouch.act(&state).outcome()).curr_durability,
+            max - 5
+        );
+
+        // Mending on top of the repair is capped at the max
+        state.curr_durability = max - 20;
+        assert_eq!(
+            (state + MastersMend.act(&state).outcome()).curr_durability,
+            max
+        );
+
+        // The repair would keep the item intact, but it breaks before it's applied
+        state.curr_durability = 10;
+        let outcome = BasicTouch.act(&state);
+        assert!(matches!(outcome, ActionOutcome::Failure(_)));
+        assert_eq!((state + outcome.outcome()).curr_durability, 0);
+        assert_eq!(BasicTouch.prospective_act(&state), Ok(outcome));
+
+        // Nothing is repaired while time is stopped
+        state.curr_durability = max - 20;
+        let outcome = FinalAppraisal.act(&state);
+        assert_eq!((state + outcome.outcome()).curr_durability, max - 20);
+        assert_eq!(FinalAppraisal.prospective_act(&state), Ok(outcome));
+    }
+
+    #[test]
+    fn trained_perfection_groundwork() {
+        use crate::{
+            actions::{
+                buffs::{TrainedPerfection, Veneration},
+                progress::Groundwork,
+                DurabilityFactor,
+            },
+            buffs::durability,
+        };
+
+        let mut state = CraftingState::new_simulation(&DAWNTRAIL_SIMULATOR);
+        state += TrainedPerfection.act(&state).outcome();
+        assert_eq!(
+            state.buffs.durability.trained_perfection,
+            durability::TrainedPerfection::Active
+        );
+        assert!(!TrainedPerfection.can_execute(&state));
+
+        // Neither of these would use durability, so they don't use up the buff
+        state += Observe.act(&state).outcome();
+        state += Veneration.act(&state).outcome();
+        assert!(state.buffs.durability.trained_perfection.is_active());
+
+        // Not enough durability left for Groundwork, which would normally halve its efficiency
+        state.curr_durability = 10;
+        let mut untouched = state;
+        untouched.curr_durability = DAWNTRAIL_SIMULATOR.recipe.max_durability;
+        untouched.buffs.durability.trained_perfection = durability::TrainedPerfection::Available;
+
+        assert_eq!(Groundwork.durability(&state.buffs, &state.condition), 0);
+        assert_eq!(Groundwork.progress(&state), Groundwork.progress(&untouched));
+
+        let outcome = Groundwork.act(&state);
+        assert!(matches!(outcome, ActionOutcome::InProgress(_)));
+        let after = state + outcome.outcome();
+        assert_eq!(after.curr_durability, 10);
+        assert_eq!(
+            after.curr_progress,
+            state.curr_progress + Groundwork.progress(&untouched)
+        );
+        assert_eq!(
+            after.buffs.durability.trained_perfection,
+            durability::TrainedPerfection::Spent
+        );
+        assert!(!TrainedPerfection.can_execute(&after));
+    }
+
+    #[test]
+    fn act_is_the_success_branch() {
+        use crate::{
+            actions::{progress::RapidSynthesis, RollOutcome},
+            test_util::FixedRng,
+        };
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.quality.inner_quiet = crate::buffs::quality::InnerQuiet::Active(2);
+
+        assert_eq!(BasicTouch.fail_rate(&state), 0);
+        // Even the lowest roll can't fail it
+        let RollOutcome::Success(success) = BasicTouch.act_random(&mut FixedRng::lowest(), &state)
+        else {
+            panic!("Basic Touch can't fail")
+        };
+        assert_eq!(BasicTouch.act(&state), success);
+
+        assert_eq!(HastyTouch.fail_rate(&state), 40);
+        let [(_, RollOutcome::Failure(fail)), (_, RollOutcome::Success(success))] =
+            HastyTouch.act_and_fail(&state)
+        else {
+            unreachable!()
+        };
+        assert_eq!(HastyTouch.act(&state), success);
+        assert_ne!(HastyTouch.act(&state), fail);
+
+        // Rapid Synthesis fails half the time, but `act` never rolls for it
+        assert_eq!(RapidSynthesis.fail_rate(&state), 50);
+        let RollOutcome::Failure(fail) = RapidSynthesis.act_random(&mut FixedRng::lowest(), &state)
+        else {
+            panic!("The lowest roll should fail")
+        };
+        let RollOutcome::Success(success) =
+            RapidSynthesis.act_random(&mut FixedRng::highest(), &state)
+        else {
+            panic!("The highest roll should succeed")
+        };
+        let forced = RapidSynthesis.act(&state);
+        assert_eq!(forced, success);
+        assert_ne!(forced, fail);
+        assert!((state + forced.outcome()).curr_progress > 0);
+        assert_eq!((state + fail.outcome()).curr_progress, 0);
+    }
+
+    #[test]
+    fn failures_spend_trained_perfection() {
+        use crate::{actions::RollOutcome, buffs::durability};
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.durability.trained_perfection = durability::TrainedPerfection::Active;
+
+        let [(_, RollOutcome::Failure(fail)), _] = HastyTouch.act_and_fail(&state) else {
+            unreachable!()
+        };
+        let after = state + fail.outcome();
+        assert_eq!(after.curr_durability, state.curr_durability);
+        assert_eq!(
+            after.buffs.durability.trained_perfection,
+            durability::TrainedPerfection::Spent
+        );
+    }
+
+    #[test]
+    fn durability_after_repair_skips_finished_crafts() {
+        use crate::buffs::durability;
+
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.buffs.durability.manipulation = durability::Manipulation::Active(4);
+
+        // Touching on the last 10 durability breaks the item before Manipulation can repair it
+        state.curr_durability = 10;
+        let outcome = BasicTouch.act(&state);
+        assert!(matches!(outcome, ActionOutcome::Failure(_)));
+        assert_eq!(outcome.durability_after_repair(&state), None);
+        assert_eq!((state + outcome.outcome()).curr_durability, 0);
+
+        // With any more left, the repair is included
+        state.curr_durability = 15;
+        let outcome = BasicTouch.act(&state);
+        assert!(matches!(outcome, ActionOutcome::InProgress(_)));
+        assert_eq!(outcome.durability_after_repair(&state), Some(10));
+        assert_eq!((state + outcome.outcome()).curr_durability, 10);
+
+        // And capped like the state itself
+        state.curr_durability = CLASSICAL_SIMULATOR.recipe.max_durability;
+        let outcome = Observe.act(&state);
+        assert_eq!(
+            outcome.durability_after_repair(&state),
+            Some(CLASSICAL_SIMULATOR.recipe.max_durability)
+        );
+    }
+
+    #[test]
+    fn time_stopping_actions_keep_first_step() {
+        use crate::actions::progress::MuscleMemory;
+
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let state = start + FinalAppraisal.act(&start).outcome();
+        assert!(state.first_step);
+        assert!(MuscleMemory.can_execute(&state));
+
+        let state = state + Observe.act(&state).outcome();
+        assert!(!state.first_step);
+        assert!(!MuscleMemory.can_execute(&state));
+    }
+
+    #[cfg(feature = "step-count")]
+    #[test]
+    fn step_count_skips_time_stopping_actions() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        assert_eq!(start.steps(), 0);
+
+        let mut state = start + FinalAppraisal.act(&start).outcome();
+        assert_eq!(state.steps(), 0);
+        assert!(state.first_step);
+
+        let delta = BasicTouch.act(&state).outcome();
+        let prev_buffs = state.buffs;
+        state += delta;
+        assert_eq!(state.steps(), 1);
+        assert_eq!((state + Observe.act(&state).outcome()).steps(), 2);
+        assert_eq!(state.undo(delta, prev_buffs).unwrap().steps(), 0);
+
+        // The counter doesn't distinguish states
+        let mut other = state;
+        other.steps = 7;
+        assert_eq!(other, state);
+    }
+
+    #[test]
+    fn combo_previews_match_fail_rates() {
+        use crate::actions::{progress::FocusedSynthesis, RandomAction};
+
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        assert!(!state.observe_combo_active());
+        assert_eq!(state.basic_touch_combo(), BasicTouchCombo::Inactive);
+        assert_ne!(FocusedSynthesis.fail_rate(&state), 0);
+
+        let state = state + Observe.act(&state).outcome();
+        assert!(state.observe_combo_active());
+        assert_eq!(FocusedSynthesis.fail_rate(&state), 0);
+
+        let state = state + BasicTouch.act(&state).outcome();
+        assert!(!state.observe_combo_active());
+        assert_ne!(FocusedSynthesis.fail_rate(&state), 0);
+        assert_eq!(state.basic_touch_combo(), BasicTouchCombo::BasicTouch);
+
+        let state = state + BasicSynthesis.act(&state).outcome();
+        assert_eq!(state.basic_touch_combo(), BasicTouchCombo::Inactive);
+    }
+
+    #[test]
+    fn excellent_held_across_time_stop() {
+        let mut state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        state.condition = QARegularConditions::Excellent;
+
+        // Even the rng that would move a Normal condition away doesn't touch it
+        let outcome = state.act(
+            FinalAppraisal,
+            &mut FixedRng::highest(),
+            &mut FixedRng::lowest(),
+        );
+
+        let next = match outcome {
+            Outcome::InProgress { state, .. } => state,
+            _ => panic!("Final Appraisal should not end the craft"),
+        };
+
+        assert_eq!(next.condition, QARegularConditions::Excellent);
+        assert!(next.buffs.progress.final_appraisal.is_active());
+        assert_eq!(
+            next.act_worst(FinalAppraisal).state().condition,
+            next.condition
+        );
+
+        // The next action to take up a step gets the Excellent, and can't keep it
+        let mut normal = next;
+        normal.condition = QARegularConditions::Normal;
+        assert!(BasicTouch.quality(&next) > BasicTouch.quality(&normal));
+
+        let outcome = next.act(
+            BasicTouch,
+            &mut FixedRng::highest(),
+            &mut FixedRng::highest(),
+        );
+        match outcome {
+            Outcome::InProgress { state, .. } => {
+                assert_eq!(state.condition, QARegularConditions::Poor)
+            }
+            _ => panic!("Basic Touch should not end the craft"),
+        }
+    }
+
+    #[test]
+    fn careful_observation_changes_condition() {
+        let mut state = CraftingState::new_specialist_simulation(&CLASSICAL_SIMULATOR, 3);
+        state.condition = QARegularConditions::Excellent;
+
+        let outcome = state.act(
+            CarefulObservation,
+            &mut FixedRng::highest(),
+            &mut FixedRng::highest(),
+        );
+        let next = match outcome {
+            Outcome::InProgress { state, .. } => state,
+            _ => panic!("Careful Observation should not end the craft"),
+        };
+
+        // Time doesn't pass, but the condition still moves on
+        assert!(next.first_step);
+        assert_eq!(next.condition, QARegularConditions::Poor);
+        assert_eq!(
+            state.act_worst(CarefulObservation).state().condition,
+            QARegularConditions::Poor
+        );
+    }
+}
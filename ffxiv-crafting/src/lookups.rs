@@ -1,5 +1,13 @@
 #![allow(dead_code)]
 
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    sync::OnceLock,
+};
+
+use crate::conditions::ConditionKind;
+
 // It's 101 because it goes from [0-100], not [1-100]
 pub(crate) const HQ: [u8; 101] = [
     1, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 7, 8, 8, 8,
@@ -17,64 +25,81 @@ pub(crate) const fn lookup_hq(quality: u32, recipe_quality: u32) -> u8 {
     HQ[if raw_chance > 100 { 100 } else { raw_chance } as usize]
 }
 
-pub(crate) const CLVL: [u16; 80] = [
+/// How many recipe levels the simulator has data for: every level up to 100, the four extra star
+/// ratings of the max level in ARR, HW, and StB, and the extra max level recipes in ShB, EW, and
+/// DT.
+pub(crate) const RLVL_COUNT: usize = 100 + 4 * 3 + 9 + 9 + 3;
+
+pub(crate) const CLVL: [u16; 100] = [
     1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
     27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50,
     120, 125, 130, 133, 136, 139, 142, 145, 148, 150, 260, 265, 270, 273, 276, 279, 282, 285, 288,
-    290, 390, 395, 400, 403, 406, 409, 412, 415, 418, 420,
+    290, 390, 395, 400, 403, 406, 409, 412, 415, 418, 420, 517, 520, 525, 530, 535, 540, 545, 550,
+    555, 560, 650, 653, 656, 660, 665, 670, 675, 680, 685, 690,
 ];
 
-pub(crate) const RLVL: [u16; 80 + 4 * 3 + 9] = [
+pub(crate) const RLVL: [u16; RLVL_COUNT] = [
     1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
     27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50,
     55, 70, 90, 110, 115, 125, 130, 133, 136, 139, 142, 145, 148, 150, 160, 180, 210, 220, 250,
     255, 265, 270, 273, 276, 279, 282, 285, 288, 290, 300, 320, 350, 380, 390, 395, 400, 403, 406,
-    409, 412, 415, 418, 430, 440, 450, 480, 481, 490, 511, 512, 513,
+    409, 412, 415, 418, 430, 440, 450, 480, 481, 490, 511, 512, 513, 517, 520, 525, 530, 535, 540,
+    545, 550, 555, 560, 570, 580, 590, 610, 620, 621, 630, 640, 641, 650, 653, 656, 660, 665, 670,
+    675, 680, 685, 690, 700, 710, 720,
 ];
 
-pub(crate) const RLVL_CRAFTSMANSHIP: [u16; 80 + 4 * 3 + 9] = [
+pub(crate) const RLVL_CRAFTSMANSHIP: [u16; RLVL_COUNT] = [
     22, 22, 22, 22, 50, 50, 50, 59, 59, 59, 67, 67, 67, 67, 67, 78, 78, 78, 82, 94, 94, 94, 99, 99,
     99, 99, 99, 106, 106, 106, 121, 121, 121, 129, 129, 129, 129, 129, 136, 136, 136, 150, 150,
     150, 150, 150, 161, 161, 161, 176, 325, 325, 391, 451, 468, 502, 519, 529, 539, 550, 560, 570,
     580, 587, 620, 718, 850, 870, 995, 1006, 1027, 1037, 1044, 1050, 1056, 1063, 1069, 1075, 1079,
     1100, 1320, 1500, 1650, 1320, 1388, 1457, 1498, 1539, 1580, 1621, 1662, 1702, 1866, 2000, 2140,
-    2480, 2484, 2520, 2620, 2620, 2620,
+    2480, 2484, 2520, 2620, 2620, 2620, 2650, 2700, 2750, 2800, 2850, 2900, 2950, 3000, 3050, 3100,
+    3200, 3300, 3400, 3600, 3700, 3700, 3800, 3900, 3900, 3950, 4000, 4050, 4100, 4150, 4200, 4250,
+    4300, 4350, 4400, 4500, 4600, 4700,
 ];
 
-pub(crate) const RLVL_CONTROL: [u16; 80 + 4 * 3 + 9] = [
+pub(crate) const RLVL_CONTROL: [u16; RLVL_COUNT] = [
     11, 11, 11, 11, 25, 25, 25, 29, 29, 29, 33, 33, 33, 33, 33, 39, 39, 39, 41, 47, 47, 47, 49, 49,
     49, 49, 49, 53, 53, 53, 60, 60, 60, 64, 64, 64, 64, 64, 68, 68, 68, 75, 75, 75, 75, 75, 80, 80,
     80, 88, 325, 325, 374, 407, 426, 462, 480, 491, 502, 513, 524, 535, 546, 553, 589, 695, 820,
     835, 955, 968, 993, 1005, 1013, 1020, 1028, 1035, 1043, 1050, 1055, 1080, 1220, 1350, 1600,
     1220, 1284, 1348, 1387, 1425, 1464, 1502, 1541, 1579, 1733, 1860, 1990, 2195, 2206, 2305, 2540,
-    2540, 2540,
+    2540, 2540, 2560, 2600, 2640, 2680, 2720, 2760, 2800, 2840, 2880, 2920, 3020, 3120, 3220, 3400,
+    3500, 3500, 3600, 3700, 3700, 3750, 3800, 3850, 3900, 3950, 4000, 4050, 4100, 4150, 4200, 4300,
+    4400, 4500,
 ];
 
-pub(crate) const RLVL_PROGRESS: [u32; 80 + 4 * 3 + 9] = [
+pub(crate) const RLVL_PROGRESS: [u32; RLVL_COUNT] = [
     19, 20, 20, 21, 33, 36, 37, 41, 42, 45, 48, 53, 54, 54, 55, 63, 66, 67, 68, 74, 75, 75, 79, 85,
     89, 90, 91, 100, 101, 102, 106, 110, 111, 115, 123, 124, 128, 129, 137, 138, 143, 144, 155,
     156, 158, 159, 167, 172, 174, 186, 195, 233, 445, 586, 339, 503, 586, 641, 697, 752, 808, 863,
     919, 956, 982, 1033, 1106, 1234, 1476, 1116, 1263, 1476, 1586, 1697, 1808, 1919, 2029, 2140,
     2214, 2361, 2657, 2760, 2900, 3149, 3248, 3348, 3407, 3467, 3526, 3586, 3645, 3705, 3943, 4143,
-    4343, 4943, 4963, 5143, 5563, 5583, 5603,
+    4343, 4943, 4963, 5143, 5563, 5583, 5603, 5010, 5100, 5200, 5290, 5390, 5480, 5580, 5670, 5760,
+    6510, 6720, 6930, 7140, 7560, 7770, 7770, 7980, 8190, 8190, 7470, 7560, 7650, 7750, 7840, 7940,
+    8030, 8130, 8220, 9240, 9450, 9660, 9870,
 ];
 
-pub(crate) const RLVL_QUALITY: [u32; 80 + 4 * 3 + 9] = [
+pub(crate) const RLVL_QUALITY: [u32; RLVL_COUNT] = [
     312, 325, 339, 352, 451, 474, 492, 526, 545, 629, 665, 702, 726, 751, 807, 866, 898, 939, 982,
     1053, 1090, 1122, 1169, 1239, 1296, 1332, 1368, 1498, 1544, 1584, 1670, 1697, 1757, 1811, 1853,
     1882, 1905, 1961, 2026, 2050, 2109, 2147, 2251, 2277, 2309, 2372, 2421, 2524, 2551, 2641, 2646,
     2921, 4980, 5783, 3951, 5172, 5783, 6042, 6301, 6561, 6820, 7080, 7339, 7851, 7874, 8015, 8298,
     8742, 9230, 8377, 8581, 9186, 9657, 10023, 10389, 10755, 11121, 11490, 11736, 11960, 12511,
     13144, 14267, 13086, 13660, 14062, 14482, 14902, 15322, 15742, 16162, 16582, 18262, 19662,
-    23395, 25863, 25945, 26686, 28414, 28496, 28578,
+    23395, 25863, 25945, 26686, 28414, 28496, 28578, 24210, 24570, 24930, 25300, 25660, 26030,
+    26390, 26750, 27120, 31710, 32760, 33810, 34860, 36750, 37800, 37800, 38850, 39900, 39900,
+    35040, 35490, 35940, 36400, 36860, 37310, 37760, 38220, 38680, 45150, 46200, 47250, 48300,
 ];
 
-pub(crate) const RLVL_DURABILITY: [u8; 80 + 4 * 3 + 9] = [
+pub(crate) const RLVL_DURABILITY: [u8; RLVL_COUNT] = [
     60, 60, 60, 60, 60, 60, 60, 60, 60, 60, 60, 60, 60, 60, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70,
     70, 70, 70, 70, 70, 70, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80,
     80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 80, 80, 80,
     80, 80, 80, 80, 80, 80, 80, 70, 70, 70, 70, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 70, 70, 70,
-    70, 70, 70, 70, 70,
+    70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 70,
+    80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80, 80,
 ];
 
 /* RLVL conditions are at the bottom because it's long */
@@ -83,9 +108,17 @@ pub(crate) const RLVL_DURABILITY: [u8; 80 + 4 * 3 + 9] = [
 ///
 /// This is likely incomplete, but was taken from the spreadsheet linked in the
 /// README. Note that for the max levels it corresponds to the number of stars,
-/// except for ShB which has an array of very specific `rlvls` that denote a bunch
+/// except for ShB and later which have an array of very specific `rlvls` that denote a bunch
 /// of things like expert crafts and minor differences in difficulty.
+///
+/// The Endwalker and Dawntrail rows haven't been checked against the game's files yet: their
+/// suggested stats, progress, quality, and durability are extrapolated from the Shadowbringers
+/// rows, and the simulator still uses the base progress and quality formulas from before
+/// Endwalker reworked them, so crafts at those levels are only approximate. The
+/// `datamined_recipe_levels_match` test in [`datamine`](crate::datamine) checks the tables
+/// against a real `RecipeLevelTable.csv`.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum RecipeLevelRanges {
     ArrLeveling(u8),
@@ -96,6 +129,10 @@ pub enum RecipeLevelRanges {
     StbMax(u8),
     ShbLeveling(u8),
     ShbMax(u8),
+    EwLeveling(u8),
+    EwMax(u8),
+    DtLeveling(u8),
+    DtMax(u8),
 }
 
 impl RecipeLevelRanges {
@@ -107,21 +144,78 @@ impl RecipeLevelRanges {
                 | Self::HwLeveling(51..=59)
                 | Self::StbLeveling(61..=69)
                 | Self::ShbLeveling(71..=79)
+                | Self::EwLeveling(81..=89)
+                | Self::DtLeveling(91..=99)
                 | Self::ArrMax(0..=4)
                 | Self::HwMax(0..=4)
                 | Self::StbMax(0..=4)
                 | Self::ShbMax(0..=9)
+                | Self::EwMax(0..=9)
+                | Self::DtMax(0..=3)
         )
     }
 
     /// Returns the corresponding user-facing level variant given an internal `rlvl`
-    /// value. This just does a linear search right now but could be modified to a lookup
-    /// table in the future.
+    /// value. This uses a lookup table built the first time it's called, if you need
+    /// this in a `const` context use [`from_rlvl_const`](RecipeLevelRanges::from_rlvl_const).
+    ///
+    /// This panics if the simulator has no data for `rlvl`, see
+    /// [`try_from_rlvl`](RecipeLevelRanges::try_from_rlvl) for a version that doesn't.
     pub fn from_rlvl(rlvl: u16) -> Self {
-        // There's definitely many faster ways to do this but they're really not worth it at the moment IMO
-        RecipeLevelIter::default()
-            .find(|recipe| recipe.to_recipe_level() == rlvl)
-            .unwrap_or_else(|| panic!("Invalid rlvl {}", rlvl))
+        Self::try_from_rlvl(rlvl).unwrap_or_else(|| panic!("Invalid rlvl {}", rlvl))
+    }
+
+    /// [`from_rlvl`](RecipeLevelRanges::from_rlvl), but returning [`None`] for an `rlvl` the
+    /// simulator has no data for, such as any from recipes newer than its tables.
+    pub fn try_from_rlvl(rlvl: u16) -> Option<Self> {
+        static REVERSE_RLVL: OnceLock<Vec<Option<RecipeLevelRanges>>> = OnceLock::new();
+
+        let table = REVERSE_RLVL.get_or_init(|| {
+            let max = RLVL.iter().copied().max().unwrap_or_default() as usize;
+            let mut table = vec![None; max + 1];
+            for recipe in RecipeLevelIter::default() {
+                table[recipe.to_recipe_level() as usize] = Some(recipe);
+            }
+            table
+        });
+
+        table.get(rlvl as usize).copied().flatten()
+    }
+
+    /// The `const` version of [`from_rlvl`](RecipeLevelRanges::from_rlvl). This just does a linear
+    /// search over the internal RLVL table, so prefer [`from_rlvl`](RecipeLevelRanges::from_rlvl) for
+    /// non-`const` code.
+    pub const fn from_rlvl_const(rlvl: u16) -> Self {
+        let mut i = 0;
+        while i < RLVL.len() {
+            if RLVL[i] == rlvl {
+                return Self::from_rlvl_index(i);
+            }
+            i += 1;
+        }
+
+        panic!("Invalid rlvl")
+    }
+
+    /// The inverse of [`to_rlvl_index`](RecipeLevelRanges::to_rlvl_index).
+    const fn from_rlvl_index(index: usize) -> Self {
+        let raw_lvl = (index + 1) as u8;
+
+        match raw_lvl {
+            1..=49 => Self::ArrLeveling(raw_lvl),
+            50..=54 => Self::ArrMax(raw_lvl - 50),
+            55..=63 => Self::HwLeveling(raw_lvl - 4),
+            64..=68 => Self::HwMax(raw_lvl - 64),
+            69..=77 => Self::StbLeveling(raw_lvl - 8),
+            78..=82 => Self::StbMax(raw_lvl - 78),
+            83..=91 => Self::ShbLeveling(raw_lvl - 12),
+            92..=101 => Self::ShbMax(raw_lvl - 92),
+            102..=110 => Self::EwLeveling(raw_lvl - 21),
+            111..=120 => Self::EwMax(raw_lvl - 111),
+            121..=129 => Self::DtLeveling(raw_lvl - 30),
+            130..=133 => Self::DtMax(raw_lvl - 130),
+            _ => panic!("Invalid rlvl index"),
+        }
     }
 
     /// Calculates the index into the internal RLVL table.
@@ -137,10 +231,15 @@ impl RecipeLevelRanges {
                 // Four stars of recipes every 10 levels (until ShB max...)
                 (lvl + modifier * 4) as usize
             }
+            // ...after which each max level has its own number of recipes
+            Self::EwLeveling(lvl) => lvl as usize + 12 + 9,
+            Self::DtLeveling(lvl) => lvl as usize + 12 + 9 + 9,
             Self::ArrMax(stars) => 50 + stars as usize,
             Self::HwMax(stars) => 60 + 4 + stars as usize,
             Self::StbMax(stars) => 70 + 8 + stars as usize,
             Self::ShbMax(stars) => 80 + 12 + stars as usize,
+            Self::EwMax(stars) => 90 + 12 + 9 + stars as usize,
+            Self::DtMax(stars) => 100 + 12 + 9 + 9 + stars as usize,
         };
 
         raw_lvl - 1
@@ -149,7 +248,7 @@ impl RecipeLevelRanges {
     /// Returns the current most difficult (highest rlvl) recipe
     #[cfg(test)]
     const fn max_level_recipe() -> Self {
-        Self::ShbMax(9)
+        Self::DtMax(3)
     }
 
     /// Returns the current easiest (rlvl 1) recipe.
@@ -168,11 +267,15 @@ impl RecipeLevelRanges {
             Self::ArrLeveling(lvl)
             | Self::HwLeveling(lvl)
             | Self::StbLeveling(lvl)
-            | Self::ShbLeveling(lvl) => lvl,
+            | Self::ShbLeveling(lvl)
+            | Self::EwLeveling(lvl)
+            | Self::DtLeveling(lvl) => lvl,
             Self::ArrMax(_) => 50,
             Self::HwMax(_) => 60,
             Self::StbMax(_) => 70,
             Self::ShbMax(_) => 80,
+            Self::EwMax(_) => 90,
+            Self::DtMax(_) => 100,
         }
     }
 
@@ -211,10 +314,19 @@ impl RecipeLevelRanges {
     /// Probably use the stuff in [`Condition`] instead.
     ///
     /// [`Condition`]: crate::Condition
-    pub(crate) const fn to_recipe_level_conditions(self) -> ConditionBits {
+    pub const fn to_recipe_level_conditions(self) -> ConditionBits {
         RLVL_CONDITIONS[self.to_rlvl_index()]
     }
 
+    /// The family of conditions this recipe uses, i.e. which [`Condition`] type to simulate it
+    /// with.
+    ///
+    /// [`Condition`]: crate::conditions::Condition
+    pub fn condition_kind(self) -> ConditionKind {
+        ConditionKind::try_from(self.to_recipe_level_conditions())
+            .expect("every recipe level has a known set of conditions")
+    }
+
     /// Computes the index into the level modifier table between
     /// this recipes `rlvl` and the given `clvl` of the crafter.
     const fn to_level_mod_index(self, clvl: u16) -> usize {
@@ -241,6 +353,99 @@ impl RecipeLevelRanges {
     }
 }
 
+/// Parses the player-facing notation for a recipe's level, i.e. the level followed by its number of
+/// stars, if any. The stars can be written out (`"50**"` or `"50★★"`) or counted
+/// (`"50 2star"`, `"50 2-stars"`).
+///
+/// Stars are only supported for the max levels before ShB (50, 60, and 70), where the variant
+/// counts them directly. [`ShbMax`](RecipeLevelRanges::ShbMax) and later instead index a list of
+/// specific `rlvl`s, so levels 80, 90, and 100 can only be parsed without stars (for
+/// [`ShbMax(0)`] and so on), other recipes need [`from_rlvl`](RecipeLevelRanges::from_rlvl).
+///
+/// [`ShbMax(0)`]: RecipeLevelRanges::ShbMax
+impl TryFrom<&str> for RecipeLevelRanges {
+    type Error = ParseRecipeLevelError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+        let split = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let (level, stars) = value.split_at(split);
+
+        let level = level
+            .parse::<u8>()
+            .map_err(|_| ParseRecipeLevelError::Malformed)?;
+        let stars = parse_stars(stars.trim()).ok_or(ParseRecipeLevelError::Malformed)?;
+
+        let recipe = match (level, stars) {
+            (50, _) => Self::ArrMax(stars),
+            (60, _) => Self::HwMax(stars),
+            (70, _) => Self::StbMax(stars),
+            (80, 0) => Self::ShbMax(0),
+            (90, 0) => Self::EwMax(0),
+            (100, 0) => Self::DtMax(0),
+            (1..=49, 0) => Self::ArrLeveling(level),
+            (51..=59, 0) => Self::HwLeveling(level),
+            (61..=69, 0) => Self::StbLeveling(level),
+            (71..=79, 0) => Self::ShbLeveling(level),
+            (81..=89, 0) => Self::EwLeveling(level),
+            (91..=99, 0) => Self::DtLeveling(level),
+            (1..=100, _) => return Err(ParseRecipeLevelError::NoSuchStars { level, stars }),
+            _ => return Err(ParseRecipeLevelError::NoSuchLevel(level)),
+        };
+
+        if recipe.verify_leveling() {
+            Ok(recipe)
+        } else {
+            Err(ParseRecipeLevelError::NoSuchStars { level, stars })
+        }
+    }
+}
+
+/// Parses everything after the level in [`RecipeLevelRanges`]' string notation into a number of
+/// stars.
+fn parse_stars(stars: &str) -> Option<u8> {
+    if stars.chars().all(|c| c == '*' || c == '★') {
+        return u8::try_from(stars.chars().count()).ok();
+    }
+
+    let stars = stars.to_ascii_lowercase();
+    let stars = stars.strip_suffix('s').unwrap_or(&stars);
+    let count = stars.strip_suffix("star")?;
+    let count = count.strip_suffix('-').unwrap_or(count);
+
+    count.trim().parse().ok()
+}
+
+/// The reasons a string can't be parsed into a [`RecipeLevelRanges`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ParseRecipeLevelError {
+    /// The string isn't a level followed by some stars.
+    Malformed,
+
+    /// There are no recipes at this level.
+    NoSuchLevel(u8),
+
+    /// There are recipes at this level, but none with this many stars.
+    #[allow(missing_docs)]
+    NoSuchStars { level: u8, stars: u8 },
+}
+
+impl Display for ParseRecipeLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "Expected a recipe level like \"50\" or \"70**\""),
+            Self::NoSuchLevel(level) => write!(f, "There are no recipes at level {}", level),
+            Self::NoSuchStars { level, stars } => {
+                write!(f, "There are no {} star recipes at level {}", stars, level)
+            }
+        }
+    }
+}
+
+impl Error for ParseRecipeLevelError {}
+
 /// Iterates over all the recipe levels possible.
 pub struct RecipeLevelIter {
     curr: RecipeLevelRanges,
@@ -270,10 +475,14 @@ impl Iterator for RecipeLevelIter {
             | &mut HwLeveling(ref mut val @ 51..=58)
             | &mut StbLeveling(ref mut val @ 61..=68)
             | &mut ShbLeveling(ref mut val @ 71..=78)
+            | &mut EwLeveling(ref mut val @ 81..=88)
+            | &mut DtLeveling(ref mut val @ 91..=98)
             | &mut ArrMax(ref mut val @ 0..=3)
             | &mut HwMax(ref mut val @ 0..=3)
             | &mut StbMax(ref mut val @ 0..=3)
-            | &mut ShbMax(ref mut val @ 0..=8) => {
+            | &mut ShbMax(ref mut val @ 0..=8)
+            | &mut EwMax(ref mut val @ 0..=8)
+            | &mut DtMax(ref mut val @ 0..=2) => {
                 *val += 1;
             }
             &mut ArrLeveling(49) => {
@@ -288,6 +497,12 @@ impl Iterator for RecipeLevelIter {
             &mut ShbLeveling(79) => {
                 self.curr = ShbMax(0);
             }
+            &mut EwLeveling(89) => {
+                self.curr = EwMax(0);
+            }
+            &mut DtLeveling(99) => {
+                self.curr = DtMax(0);
+            }
             &mut ArrMax(4) => {
                 self.curr = HwLeveling(51);
             }
@@ -298,6 +513,12 @@ impl Iterator for RecipeLevelIter {
                 self.curr = ShbLeveling(71);
             }
             &mut ShbMax(9) => {
+                self.curr = EwLeveling(81);
+            }
+            &mut EwMax(9) => {
+                self.curr = DtLeveling(91);
+            }
+            &mut DtMax(3) => {
                 self.done = true;
                 return None;
             }
@@ -321,7 +542,11 @@ const fn clamp(min: i16, med: i16, max: i16) -> i16 {
     }
 }
 
-pub(crate) enum RawConditions {
+/// The bit each condition occupies in the game's condition flags. Each variant is named after
+/// the condition it represents.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(missing_docs)]
+pub enum RawConditions {
     Normal = 0x01,
     Good = 0x02,
     Excellent = 0x04,
@@ -333,7 +558,7 @@ pub(crate) enum RawConditions {
     Primed = 0x100,
 }
 
-const LEVEL_MOD_PROGRESS: [u16; 80] = [
+pub(crate) const LEVEL_MOD_PROGRESS: [u16; 80] = [
     80, 82, 84, 86, 88, 90, 92, 94, 96, 98, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100,
     100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 105, 110, 115, 120, 125, 127, 129, 131, 133,
     135, 137, 139, 141, 143, 145, 147, 147, 148, 149, 150, 150, 150, 150, 150, 150, 150, 150, 150,
@@ -341,7 +566,7 @@ const LEVEL_MOD_PROGRESS: [u16; 80] = [
     150, 150,
 ];
 
-const LEVEL_MOD_QUALITY: [u16; 80] = [
+pub(crate) const LEVEL_MOD_QUALITY: [u16; 80] = [
     60, 64, 68, 72, 76, 80, 84, 88, 92, 96, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100,
     100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100,
     100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100, 100,
@@ -441,10 +666,15 @@ pub(crate) const EXPERT_CRAFT_2: u16 = RawConditions::Normal as u16
     | RawConditions::Malleable as u16
     | RawConditions::Primed as u16;
 
+/// The game's condition flags for a recipe level, with a bit set for every condition that can
+/// come up while crafting it. Convert it to a [`ConditionKind`] to find the [`Condition`] type
+/// to simulate with.
+///
+/// [`Condition`]: crate::conditions::Condition
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-pub(crate) struct ConditionBits(pub u16);
+pub struct ConditionBits(pub u16);
 
-pub(crate) const RLVL_CONDITIONS: [ConditionBits; 80 + 4 * 3 + 9] = [
+pub(crate) const RLVL_CONDITIONS: [ConditionBits; RLVL_COUNT] = [
     ConditionBits(NORMAL_CONDITIONS),
     ConditionBits(NORMAL_CONDITIONS),
     ConditionBits(NORMAL_CONDITIONS),
@@ -546,18 +776,51 @@ pub(crate) const RLVL_CONDITIONS: [ConditionBits; 80 + 4 * 3 + 9] = [
     ConditionBits(EXPERT_CRAFT_1),
     ConditionBits(EXPERT_CRAFT_1),
     ConditionBits(EXPERT_CRAFT_2),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(EXPERT_CRAFT_2),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(EXPERT_CRAFT_2),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
+    ConditionBits(NORMAL_CONDITIONS),
 ];
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    const RLVL_CONDITIONS_RAW: [u16; 80 + 4 * 3 + 9] = [
+    const RLVL_CONDITIONS_RAW: [u16; RLVL_COUNT] = [
         15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
         15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
         15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
         15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
-        15, 15, 15, 15, 115, 15, 115, 115, 483,
+        15, 15, 15, 15, 115, 15, 115, 115, 483, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+        15, 15, 483, 15, 15, 483, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
     ];
 
     #[test]
@@ -593,7 +856,7 @@ mod test {
     fn all_levels_match() {
         let iter = RecipeLevelIter::default();
         iter.map(|v| v.to_recipe_level())
-            .zip(RLVL.into_iter())
+            .zip(RLVL)
             .for_each(|(got, expected)| assert_eq!(got, expected));
     }
 
@@ -601,15 +864,97 @@ mod test {
     fn all_conditions_match() {
         let iter = RecipeLevelIter::default();
         iter.map(|v| v.to_recipe_level_conditions())
-            .zip(RLVL_CONDITIONS.into_iter())
+            .zip(RLVL_CONDITIONS)
             .for_each(|(got, expected)| assert_eq!(got, expected));
     }
 
+    #[test]
+    fn condition_kinds_agree_with_conditions() {
+        use crate::conditions::{
+            QARegularConditions, RelicExpertConditions, RestoExpertConditions,
+        };
+
+        for recipe in RecipeLevelIter::default() {
+            let bits = recipe.to_recipe_level_conditions();
+            let kind = recipe.condition_kind();
+
+            assert_eq!(
+                QARegularConditions::try_from(bits).is_ok(),
+                kind == ConditionKind::Regular
+            );
+            assert_eq!(
+                RelicExpertConditions::try_from(bits).is_ok(),
+                kind == ConditionKind::RelicExpert
+            );
+            assert_eq!(
+                RestoExpertConditions::try_from(bits).is_ok(),
+                kind == ConditionKind::RestoExpert
+            );
+        }
+
+        assert_eq!(
+            RecipeLevelRanges::ShbLeveling(75).condition_kind(),
+            ConditionKind::Regular
+        );
+    }
+
+    #[test]
+    fn parses_player_facing_levels() {
+        use RecipeLevelRanges::*;
+
+        let parse = RecipeLevelRanges::try_from;
+        assert_eq!(parse("35"), Ok(ArrLeveling(35)));
+        assert_eq!(parse("75"), Ok(ShbLeveling(75)));
+        assert_eq!(parse(" 70** "), Ok(StbMax(2)));
+        assert_eq!(parse("60★★★★"), Ok(HwMax(4)));
+        assert_eq!(parse("50 2star"), Ok(ArrMax(2)));
+        assert_eq!(parse("50 3-Stars"), Ok(ArrMax(3)));
+        assert_eq!(parse("50"), Ok(ArrMax(0)));
+        assert_eq!(parse("80"), Ok(ShbMax(0)));
+
+        assert_eq!(
+            parse("50*****"),
+            Err(ParseRecipeLevelError::NoSuchStars {
+                level: 50,
+                stars: 5
+            })
+        );
+        assert_eq!(
+            parse("45*"),
+            Err(ParseRecipeLevelError::NoSuchStars {
+                level: 45,
+                stars: 1
+            })
+        );
+        assert_eq!(
+            parse("80 3-star"),
+            Err(ParseRecipeLevelError::NoSuchStars {
+                level: 80,
+                stars: 3
+            })
+        );
+        assert_eq!(parse("90"), Ok(EwMax(0)));
+        assert_eq!(parse("85"), Ok(EwLeveling(85)));
+        assert_eq!(parse("100"), Ok(DtMax(0)));
+        assert_eq!(parse("91"), Ok(DtLeveling(91)));
+        assert_eq!(
+            parse("90**"),
+            Err(ParseRecipeLevelError::NoSuchStars {
+                level: 90,
+                stars: 2
+            })
+        );
+        assert_eq!(parse("101"), Err(ParseRecipeLevelError::NoSuchLevel(101)));
+        assert_eq!(parse("0"), Err(ParseRecipeLevelError::NoSuchLevel(0)));
+        assert_eq!(parse("level 50"), Err(ParseRecipeLevelError::Malformed));
+        assert_eq!(parse("50 stars"), Err(ParseRecipeLevelError::Malformed));
+    }
+
     #[test]
     fn all_controls_match() {
         let iter = RecipeLevelIter::default();
         iter.map(|v| v.to_recipe_level_control())
-            .zip(RLVL_CONTROL.into_iter())
+            .zip(RLVL_CONTROL)
             .for_each(|(got, expected)| assert_eq!(got, expected));
     }
 
@@ -617,7 +962,7 @@ mod test {
     fn all_craftsmanship_match() {
         let iter = RecipeLevelIter::default();
         iter.map(|v| v.to_recipe_level_craftsmanship())
-            .zip(RLVL_CRAFTSMANSHIP.into_iter())
+            .zip(RLVL_CRAFTSMANSHIP)
             .for_each(|(got, expected)| assert_eq!(got, expected));
     }
 
@@ -625,7 +970,7 @@ mod test {
     fn all_durability_match() {
         let iter = RecipeLevelIter::default();
         iter.map(|v| v.to_recipe_level_durability())
-            .zip(RLVL_DURABILITY.into_iter())
+            .zip(RLVL_DURABILITY)
             .for_each(|(got, expected)| assert_eq!(got, expected));
     }
 
@@ -633,7 +978,7 @@ mod test {
     fn all_quality_match() {
         let iter = RecipeLevelIter::default();
         iter.map(|v| v.to_recipe_level_quality())
-            .zip(RLVL_QUALITY.into_iter())
+            .zip(RLVL_QUALITY)
             .for_each(|(got, expected)| assert_eq!(got, expected));
     }
 
@@ -685,6 +1030,49 @@ mod test {
         assert_eq!(hqs, lookup);
     }
 
+    #[test]
+    fn rlvl_lookups_agree() {
+        for rlvl in RLVL {
+            assert_eq!(
+                RecipeLevelRanges::from_rlvl(rlvl),
+                RecipeLevelRanges::from_rlvl_const(rlvl)
+            );
+        }
+    }
+
+    #[test]
+    fn dawntrail_levels() {
+        assert_eq!(RecipeLevelRanges::DtMax(0).to_recipe_level(), 690);
+        assert_eq!(RecipeLevelRanges::DtLeveling(91).to_recipe_level(), 650);
+        assert_eq!(RecipeLevelRanges::EwMax(0).to_recipe_level(), 560);
+        assert_eq!(RecipeLevelRanges::EwLeveling(81).to_recipe_level(), 517);
+        assert_eq!(
+            RecipeLevelRanges::from_rlvl(690),
+            RecipeLevelRanges::DtMax(0)
+        );
+        assert_eq!(RecipeLevelRanges::DtMax(0).to_player_facing_level(), 100);
+
+        // The levels just past the end of each expansion
+        assert!(!RecipeLevelRanges::EwLeveling(90).verify_leveling());
+        assert!(!RecipeLevelRanges::DtMax(4).verify_leveling());
+    }
+
+    #[test]
+    fn rlvl_index_round_trips() {
+        for recipe in RecipeLevelIter::default() {
+            assert_eq!(
+                RecipeLevelRanges::from_rlvl_index(recipe.to_rlvl_index()),
+                recipe
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_rlvl() {
+        RecipeLevelRanges::from_rlvl(4000);
+    }
+
     #[test]
     fn rlvl_to_recipe() {
         let recipes = RecipeLevelIter::default();
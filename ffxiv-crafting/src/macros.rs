@@ -0,0 +1,280 @@
+//! Turns a rotation into text macros that can be pasted into the game, so a solved rotation can
+//! actually be used, and reads existing macros (e.g. from Teamcraft) back into rotations so they
+//! can be simulated.
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+use crate::actions::collection::FfxivCraftingActions;
+
+/// The most lines the game allows in a single macro.
+pub const MAX_MACRO_LINES: usize = 15;
+
+/// The highest sound effect the game has, `<se.16>`.
+const MAX_SOUND_EFFECT: usize = 16;
+
+/// How long to wait after an action, in seconds. Actions that don't pass time (see
+/// [`TimePassing`]) finish sooner than everything else, so they only need 2 seconds rather than 3.
+///
+/// [`TimePassing`]: crate::actions::TimePassing
+pub fn wait_for(action: FfxivCraftingActions) -> u8 {
+    if action.metadata().time_passed {
+        3
+    } else {
+        2
+    }
+}
+
+/// Writes `actions` as in-game macros, one per string, each with at most `lines_per_block` lines.
+/// Every action is an `/ac "Action Name" <wait.N>` line (see [`wait_for`]), and every macro ends
+/// with an `/echo` line saying which macro it was, so it's clear when to start the next one.
+///
+/// If `with_sound` is set, the `/echo` lines also play a sound effect, `<se.N>` for the `N`th
+/// macro (up to `<se.16>`, the highest the game has).
+///
+/// This panics if `lines_per_block` doesn't leave room for both an action and the `/echo`, or is
+/// more than the [`MAX_MACRO_LINES`] the game allows.
+pub fn to_game_macro(
+    actions: &[FfxivCraftingActions],
+    lines_per_block: usize,
+    with_sound: bool,
+) -> Vec<String> {
+    assert!(
+        (2..=MAX_MACRO_LINES).contains(&lines_per_block),
+        "Macros need between 2 and {} lines, got {}",
+        MAX_MACRO_LINES,
+        lines_per_block
+    );
+
+    let blocks = actions.chunks(lines_per_block - 1);
+    let count = blocks.len();
+
+    blocks
+        .enumerate()
+        .map(|(i, block)| {
+            let mut lines = block
+                .iter()
+                .map(|action| {
+                    format!(
+                        "/ac \"{}\" <wait.{}>",
+                        action.metadata().name,
+                        wait_for(*action)
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let mut echo = format!("/echo Macro #{}/{} complete", i + 1, count);
+            if with_sound {
+                echo += &format!(" <se.{}>", (i + 1).min(MAX_SOUND_EFFECT));
+            }
+            lines.push(echo);
+
+            lines.join("\n")
+        })
+        .collect()
+}
+
+/// Reads the actions out of in-game macros, such as those made by [`to_game_macro`]. Action
+/// names may be quoted (`/ac "Basic Touch" <wait.3>`) or not (`/ac Basic Touch <wait.3>`), and
+/// `/action` works the same as `/ac`. Only English names are understood.
+///
+/// Every other line, such as `/echo`, `/wait`, or anything that isn't a command at all, is
+/// skipped, so several macros can be pasted in one after the other.
+pub fn from_game_macro(input: &str) -> Result<Vec<FfxivCraftingActions>, MacroParseError> {
+    let mut actions = vec![];
+
+    for (line, text) in input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| (i + 1, text.trim()))
+    {
+        let Some(rest) = ["/ac ", "/action "]
+            .into_iter()
+            .find_map(|command| text.strip_prefix(command))
+        else {
+            continue;
+        };
+        let rest = rest.trim_start();
+
+        let name = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.split('"').next().unwrap_or_default(),
+            // Everything up to the first placeholder, e.g. `<wait.3>`
+            None => rest.split('<').next().unwrap_or_default(),
+        }
+        .trim();
+
+        let action = find_action(name).ok_or_else(|| MacroParseError::UnknownAction {
+            line,
+            name: name.to_owned(),
+        })?;
+        actions.push(action);
+    }
+
+    Ok(actions)
+}
+
+/// Finds the action with the given in-game name, ignoring case as the game does.
+fn find_action(name: &str) -> Option<FfxivCraftingActions> {
+    FfxivCraftingActions::ALL
+        .into_iter()
+        .find(|action| action.metadata().name.eq_ignore_ascii_case(name))
+}
+
+/// The reasons a macro can't be read by [`from_game_macro`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MacroParseError {
+    /// An action line names an action that doesn't exist (or is misspelled, or isn't in English).
+    #[allow(missing_docs)]
+    UnknownAction { line: usize, name: String },
+}
+
+impl Display for MacroParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownAction { line, name } => {
+                write!(f, "Line {} uses \"{}\", which isn't an action", line, name)
+            }
+        }
+    }
+}
+
+impl Error for MacroParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use FfxivCraftingActions::*;
+
+    #[test]
+    fn round_trips_rotation() {
+        let rotation = [
+            MuscleMemory,
+            Veneration,
+            WasteNot2,
+            Groundwork,
+            FinalAppraisal,
+            ByregotsBlessing,
+            TricksOfTheTrade,
+            BasicSynthesis,
+        ];
+
+        let blocks = to_game_macro(&rotation, MAX_MACRO_LINES, false);
+        assert_eq!(blocks.len(), 1);
+
+        let lines = blocks[0].lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), rotation.len() + 1);
+        assert_eq!(lines[0], "/ac \"Muscle Memory\" <wait.3>");
+        assert_eq!(lines[1], "/ac \"Veneration\" <wait.3>");
+        assert_eq!(lines[2], "/ac \"Waste Not II\" <wait.3>");
+        assert_eq!(lines[4], "/ac \"Final Appraisal\" <wait.2>");
+        assert_eq!(lines[5], "/ac \"Byregot's Blessing\" <wait.3>");
+        assert_eq!(lines[6], "/ac \"Tricks of the Trade\" <wait.3>");
+        assert_eq!(lines[8], "/echo Macro #1/1 complete");
+
+        assert_eq!(from_game_macro(&blocks[0]), Ok(rotation.to_vec()));
+    }
+
+    #[test]
+    fn names_are_unique() {
+        for (i, action) in FfxivCraftingActions::ALL.into_iter().enumerate() {
+            assert_eq!(find_action(action.metadata().name), Some(action));
+            assert!(FfxivCraftingActions::ALL[..i]
+                .iter()
+                .all(|other| other.metadata().name != action.metadata().name));
+        }
+    }
+
+    #[test]
+    fn splits_into_blocks() {
+        // 14 actions and the echo fill a macro exactly
+        let blocks = to_game_macro(&[BasicTouch; 14], MAX_MACRO_LINES, true);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].lines().count(), MAX_MACRO_LINES);
+        assert_eq!(
+            blocks[0].lines().last(),
+            Some("/echo Macro #1/1 complete <se.1>")
+        );
+
+        let blocks = to_game_macro(&[BasicTouch; 15], MAX_MACRO_LINES, true);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].lines().count(), MAX_MACRO_LINES);
+        assert_eq!(
+            blocks[0].lines().last(),
+            Some("/echo Macro #1/2 complete <se.1>")
+        );
+        assert_eq!(
+            blocks[1].lines().collect::<Vec<_>>(),
+            [
+                "/ac \"Basic Touch\" <wait.3>",
+                "/echo Macro #2/2 complete <se.2>"
+            ]
+        );
+
+        assert!(to_game_macro(&[], MAX_MACRO_LINES, true).is_empty());
+        assert_eq!(to_game_macro(&[BasicTouch; 3], 2, false).len(), 3);
+    }
+
+    #[test]
+    fn parses_mixed_macros() {
+        let input = "
+            /macrolock
+            /ac \"Muscle Memory\" <wait.3>
+            /ac Veneration <wait.2>
+            /action waste not ii
+            Comments and blank lines are skipped
+
+            /wait 1
+            /ac \"Byregot's Blessing\"<wait.3>
+            /echo Macro #1/2 complete <se.1>
+            /ac   Basic Synthesis   <wait.3> <se.2>
+        ";
+
+        assert_eq!(
+            from_game_macro(input),
+            Ok(vec![
+                MuscleMemory,
+                Veneration,
+                WasteNot2,
+                ByregotsBlessing,
+                BasicSynthesis
+            ])
+        );
+
+        let blocks = to_game_macro(&[BasicTouch; 20], MAX_MACRO_LINES, true);
+        assert_eq!(
+            from_game_macro(&blocks.join("\n")),
+            Ok(vec![BasicTouch; 20])
+        );
+    }
+
+    #[test]
+    fn reports_unknown_actions() {
+        let err = from_game_macro("/ac \"Basic Touch\" <wait.3>\n/ac \"Basic Tuoch\" <wait.3>")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            MacroParseError::UnknownAction {
+                line: 2,
+                name: "Basic Tuoch".to_owned()
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Line 2 uses \"Basic Tuoch\", which isn't an action"
+        );
+
+        assert!(matches!(
+            from_game_macro("/ac <wait.3>"),
+            Err(MacroParseError::UnknownAction { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Macros need between 2 and 15 lines")]
+    fn too_many_lines() {
+        to_game_macro(&[BasicTouch], MAX_MACRO_LINES + 1, false);
+    }
+}
@@ -0,0 +1,1006 @@
+//! Adapts a craft into a [`State`] for the solvers in [`structured_rewards`], available under the
+//! `mdp` feature. This treats the craft as a Markov Decision Process: every action that can be
+//! executed is a choice, and its outcome branches on both the action's [success rate] and the
+//! next [`Condition`]. The only reward is the [`OutcomeValue`] of the finished item, so a solver
+//! maximizing it will find the rotation with the best expected HQ chance (or collectability).
+//!
+//! Alternatively, the same states can be solved with a [`CompletionFirstQ`], which ranks rotations
+//! by their chance of finishing the craft before caring about quality at all, or with a
+//! [`GoodProcsQ`], which breaks ties in the outcome by how many Good or Excellent conditions the
+//! rotation makes use of.
+//!
+//! The state space of a full craft is enormous, so in practice you'll want to restrict the actions
+//! considered to a handful that make sense for the recipe at hand.
+//!
+//! [success rate]: crate::actions::RandomAction::fail_rate
+
+use std::{cmp::Ordering, collections::HashMap, hash::Hash};
+
+use derivative::Derivative;
+use structured_rewards::{
+    prelude::*,
+    rewards::{CountQ, CountReward, NoDiscountReward, SimpleQ},
+    simple_solvers::{ConvergenceMeasure, SolvedPolicy, State},
+};
+
+use crate::{
+    actions::{Action, ActionOutcome, RandomAction},
+    buffs::Buff,
+    conditions::{Condition, ConditionTransitions},
+    quality_map::{Collectability, HQChance, QualityMap},
+    CraftingSimulator, CraftingState,
+};
+
+/// Converts the outcome of a finished craft into the reward a solver maximizes.
+pub trait OutcomeValue {
+    /// The reward for finishing the craft with this outcome. Higher is better.
+    fn value(&self) -> i64;
+}
+
+impl OutcomeValue for HQChance {
+    fn value(&self) -> i64 {
+        self.0 as i64
+    }
+}
+
+impl OutcomeValue for Collectability {
+    fn value(&self) -> i64 {
+        self.0 as i64
+    }
+}
+
+/// A [`CraftingState`] along with the actions the solver is allowed to choose from. Two states
+/// are equal if their crafting states are, the set of actions is assumed to be shared.
+///
+/// Once the craft completes or fails, the state is `finished` and has no more actions available.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""))]
+#[derivative(Hash, PartialEq, Eq, Debug)]
+pub struct CraftingMdp<'a, A, C, M>
+where
+    C: Condition,
+    M: QualityMap,
+{
+    /// The current state of the craft.
+    pub state: CraftingState<'a, C, M>,
+
+    #[derivative(Hash = "ignore", PartialEq = "ignore", Debug = "ignore")]
+    actions: &'a [A],
+
+    #[derivative(Hash = "ignore", PartialEq = "ignore", Debug = "ignore")]
+    prune_observe: bool,
+
+    #[derivative(Hash = "ignore", PartialEq = "ignore", Debug = "ignore")]
+    max_steps: Option<u32>,
+
+    /// The number of actions taken to get here. This is only counted when the craft has a
+    /// [maximum length](CraftingMdp::with_max_steps), and is always `0` otherwise, since it would
+    /// otherwise split states that are the same in every way that matters.
+    pub steps: u32,
+
+    /// Whether the craft has either completed or failed.
+    pub finished: bool,
+}
+
+impl<'a, A, C, M> CraftingMdp<'a, A, C, M>
+where
+    C: Condition + Default,
+    M: QualityMap,
+{
+    /// Starts a fresh craft of `problem_def` which may only use the given `actions`.
+    pub fn new(problem_def: &'a CraftingSimulator<C, M>, actions: &'a [A]) -> Self {
+        Self {
+            state: CraftingState::new_simulation(problem_def),
+            actions,
+            prune_observe: false,
+            max_steps: None,
+            steps: 0,
+            finished: false,
+        }
+    }
+}
+
+impl<'a, A, C, M> CraftingMdp<'a, A, C, M>
+where
+    C: Condition,
+    M: QualityMap,
+{
+    /// Stops the solver from choosing an action like Observe (one that sets up the Observe combo)
+    /// while the combo is already set up, so it can never Observe twice in a row. Otherwise
+    /// Observe is available in nearly every state, and chains of them make the state space much
+    /// larger for no benefit.
+    ///
+    /// This is an approximation: a rotation that relies on several Observes in a row (e.g. to wait
+    /// out a bad condition) would be missed, though such rotations are rarely if ever optimal.
+    pub fn without_repeated_observe(self) -> Self {
+        Self {
+            prune_observe: true,
+            ..self
+        }
+    }
+
+    /// Limits the craft to at most `max_steps` actions, e.g. so the rotation fits in a macro.
+    /// Once that many actions have been taken the state is treated as terminal, the same as a
+    /// failed craft, so the solver is biased towards rotations that finish in time.
+    ///
+    /// This also bounds the state space, at the cost of states that are only distinguished by
+    /// their [`steps`](CraftingMdp::steps) no longer being shared.
+    pub fn with_max_steps(self, max_steps: u32) -> Self {
+        Self {
+            max_steps: Some(max_steps),
+            ..self
+        }
+    }
+}
+
+impl<'a, A, C, M> State<SimpleQ, NoDiscountReward, SimpleQ> for CraftingMdp<'a, A, C, M>
+where
+    A: Action + RandomAction + Copy,
+    C: ConditionTransitions + Hash + Eq,
+    M: QualityMap + Hash + Eq,
+    M::Outcome: OutcomeValue,
+{
+    type SuccRewardIter = std::vec::IntoIter<(Self, NoDiscountReward)>;
+
+    type Action = A;
+
+    type ActionIter = std::vec::IntoIter<A>;
+
+    type Key = Self;
+
+    fn successors(&self, action: Self::Action) -> Self::SuccRewardIter {
+        self.branches(action)
+            .into_iter()
+            .map(|(succ, prob)| {
+                let value = succ.outcome_value();
+                (succ, NoDiscountReward(prob, value))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn actions(&self) -> Self::ActionIter {
+        self.available_actions().into_iter()
+    }
+
+    fn key(&self) -> Self::Key {
+        *self
+    }
+}
+
+impl<'a, A, C, M> CraftingMdp<'a, A, C, M>
+where
+    C: Condition,
+    M: QualityMap,
+{
+    fn finish(&self, state: CraftingState<'a, C, M>) -> Self {
+        Self {
+            state,
+            finished: true,
+            ..self.advance(state)
+        }
+    }
+
+    /// The state after taking an action that leads to `state`.
+    fn advance(&self, state: CraftingState<'a, C, M>) -> Self {
+        Self {
+            state,
+            steps: self.steps + self.max_steps.is_some() as u32,
+            ..*self
+        }
+    }
+
+    /// Whether the craft has hit its [maximum length](CraftingMdp::with_max_steps).
+    fn out_of_steps(&self) -> bool {
+        self.max_steps.is_some_and(|max| self.steps >= max)
+    }
+
+    /// Whether this is a finished craft that reached its recipe's progress.
+    fn completed(&self) -> bool {
+        self.finished && self.state.curr_progress >= self.state.problem_def.recipe.max_progress
+    }
+
+    /// The [`OutcomeValue`] of the item if this is a completed craft, or `0` otherwise.
+    fn outcome_value(&self) -> i64
+    where
+        M::Outcome: OutcomeValue,
+    {
+        if self.completed() {
+            M::convert(
+                self.state.curr_quality,
+                self.state.problem_def.recipe.max_quality,
+            )
+            .value()
+        } else {
+            0
+        }
+    }
+}
+
+impl<'a, A, C, M> CraftingMdp<'a, A, C, M>
+where
+    A: Action + RandomAction + Copy,
+    C: ConditionTransitions,
+    M: QualityMap,
+{
+    /// Every state `action` can lead to, along with the probability of ending up there. This is
+    /// what the [`State`] implementations build their successors from, without any reward.
+    pub fn branches(&self, action: A) -> Vec<(Self, f64)> {
+        let mut branches = vec![];
+
+        // Not using `act_and_fail` since actions that can't fail panic if asked for their
+        // failure action
+        let fail_rate = action.fail_rate(&self.state);
+        let mut rolls = vec![(100 - fail_rate, action.act(&self.state))];
+        if fail_rate > 0 {
+            rolls.push((fail_rate, action.fail_action().act(&self.state)));
+        }
+
+        for (chance, outcome) in rolls {
+            if chance == 0 {
+                continue;
+            }
+
+            let chance = chance as f64 / 100.;
+
+            match outcome {
+                ActionOutcome::Completed(delta) | ActionOutcome::Failure(delta) => {
+                    branches.push((self.finish(self.state + delta), chance))
+                }
+                // The condition only changes if a step passed (or on Careful Observation)
+                ActionOutcome::InProgress(delta) if !delta.condition_changed() => {
+                    branches.push((self.advance(self.state + delta), chance))
+                }
+                ActionOutcome::InProgress(delta) => {
+                    let next = self.state + delta;
+                    for (condition, prob) in self.state.condition.transitions() {
+                        let state = CraftingState { condition, ..next };
+                        branches.push((self.advance(state), chance * prob));
+                    }
+                }
+            }
+        }
+
+        branches
+    }
+
+    /// The actions that can currently be taken: those that can be executed and afforded, or none
+    /// if the craft is finished or out of steps.
+    pub fn available_actions(&self) -> Vec<A> {
+        if self.finished || self.out_of_steps() {
+            return vec![];
+        }
+
+        self.actions
+            .iter()
+            .copied()
+            .filter(|action| {
+                action.can_execute(&self.state)
+                    && self.state.curr_cp + action.cp_cost(&self.state) >= 0
+                    && !(self.prune_observe && self.repeats_observe(*action))
+            })
+            .collect()
+    }
+
+    /// Whether the Observe combo is already set up, and `action` would just set it up again.
+    fn repeats_observe(&self, action: A) -> bool {
+        self.state.buffs.combo.observation.is_active()
+            && (self.state + action.act(&self.state).outcome())
+                .buffs
+                .combo
+                .observation
+                .is_active()
+    }
+}
+
+/// A [`QVal`] for players who care about finishing the craft above all else. Rotations are ranked
+/// lexicographically: first by their chance of completing the craft, then by the expected quality
+/// of the completed item, then by the expected number of steps (fewer is better).
+///
+/// Each field is an expectation over the rest of the craft, so quality only counts when the craft
+/// completes, and is capped at the recipe's maximum since anything over it is wasted. Since the
+/// fields are sums of floats, two values within [`CompletionFirstQ::TOLERANCE`] of each other are
+/// considered tied rather than letting rounding error decide the ranking.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+pub struct CompletionFirstQ {
+    /// The probability of completing the craft.
+    pub completion: f64,
+
+    /// The expected quality of the item, counting failed crafts as no quality.
+    pub quality: f64,
+
+    /// The expected number of actions until the craft is finished.
+    pub steps: f64,
+}
+
+impl CompletionFirstQ {
+    /// How far apart two fields can be while still being considered equal.
+    pub const TOLERANCE: f64 = 1e-9;
+
+    fn cmp_field(a: f64, b: f64) -> Ordering {
+        debug_assert!(!a.is_nan());
+        debug_assert!(!b.is_nan());
+
+        if (a - b).abs() <= Self::TOLERANCE {
+            Ordering::Equal
+        } else {
+            a.partial_cmp(&b).unwrap()
+        }
+    }
+}
+
+impl QVal for CompletionFirstQ {}
+
+impl Bellman for CompletionFirstQ {
+    fn update(&self, other: &Self) -> Self {
+        Self {
+            completion: self.completion + other.completion,
+            quality: self.quality + other.quality,
+            steps: self.steps + other.steps,
+        }
+    }
+
+    fn partial_update(&self, other: &Self) -> Self {
+        self.update(other)
+    }
+
+    fn reweight(&self) -> Self {
+        *self
+    }
+}
+
+impl SemanticEq for CompletionFirstQ {
+    fn sem_eq(&self, other: &Self) -> bool {
+        self.sem_cmp(other) == Ordering::Equal
+    }
+}
+
+impl SemanticOrd for CompletionFirstQ {
+    fn sem_cmp(&self, other: &Self) -> Ordering {
+        Self::cmp_field(self.completion, other.completion)
+            .then_with(|| Self::cmp_field(self.quality, other.quality))
+            .then_with(|| Self::cmp_field(other.steps, self.steps))
+    }
+}
+
+/// The [`TransitionReward`](structured_rewards::TransitionReward) paired with
+/// [`CompletionFirstQ`]. The first element is the transition probability, and the second is the
+/// quality of the item if this transition completed the craft (or [`None`] if it didn't).
+///
+/// Every transition is one step.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+pub struct CompletionFirstReward(pub f64, pub Option<u32>);
+
+impl Compose<CompletionFirstQ, CompletionFirstQ> for CompletionFirstReward {
+    fn compose(&self, other: &CompletionFirstQ) -> CompletionFirstQ {
+        let completion = if self.1.is_some() { 1. } else { 0. };
+        let quality = self.1.unwrap_or(0) as f64;
+
+        CompletionFirstQ {
+            completion: self.0 * (completion + other.completion),
+            quality: self.0 * (quality + other.quality),
+            steps: self.0 * (1. + other.steps),
+        }
+    }
+}
+
+impl<'a, A, C, M> State<CompletionFirstQ, CompletionFirstReward, CompletionFirstQ>
+    for CraftingMdp<'a, A, C, M>
+where
+    A: Action + RandomAction + Copy,
+    C: ConditionTransitions + Hash + Eq,
+    M: QualityMap + Hash + Eq,
+{
+    type SuccRewardIter = std::vec::IntoIter<(Self, CompletionFirstReward)>;
+
+    type Action = A;
+
+    type ActionIter = std::vec::IntoIter<A>;
+
+    type Key = Self;
+
+    fn successors(&self, action: Self::Action) -> Self::SuccRewardIter {
+        self.branches(action)
+            .into_iter()
+            .map(|(succ, prob)| {
+                let recipe = &succ.state.problem_def.recipe;
+                let quality = succ
+                    .completed()
+                    .then(|| succ.state.curr_quality.min(recipe.max_quality));
+
+                (succ, CompletionFirstReward(prob, quality))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn actions(&self) -> Self::ActionIter {
+        self.available_actions().into_iter()
+    }
+
+    fn key(&self) -> Self::Key {
+        *self
+    }
+}
+
+/// A [`QVal`] for studying rotations that exploit the condition. Rotations are ranked by the
+/// expected [`OutcomeValue`] of the item, exactly like a [`SimpleQ`], and any ties are broken by the
+/// expected number of Good or Excellent conditions that were put to use.
+///
+/// A condition counts as used if an action added quality while it was active. Actions that only
+/// benefit from the condition in other ways (e.g. Tricks of the Trade) aren't counted.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+pub struct GoodProcsQ {
+    /// The expected value of the finished item.
+    pub outcome: SimpleQ,
+
+    /// The expected number of Good or Excellent conditions used to add quality.
+    pub good_procs: CountQ,
+}
+
+impl QVal for GoodProcsQ {}
+
+impl Bellman for GoodProcsQ {
+    fn update(&self, other: &Self) -> Self {
+        Self {
+            outcome: self.outcome.update(&other.outcome),
+            good_procs: self.good_procs.update(&other.good_procs),
+        }
+    }
+
+    fn partial_update(&self, other: &Self) -> Self {
+        self.update(other)
+    }
+
+    fn reweight(&self) -> Self {
+        *self
+    }
+}
+
+impl SemanticEq for GoodProcsQ {
+    fn sem_eq(&self, other: &Self) -> bool {
+        self.sem_cmp(other) == Ordering::Equal
+    }
+}
+
+impl SemanticOrd for GoodProcsQ {
+    fn sem_cmp(&self, other: &Self) -> Ordering {
+        self.outcome
+            .sem_cmp(&other.outcome)
+            .then_with(|| self.good_procs.sem_cmp(&other.good_procs))
+    }
+}
+
+/// The [`TransitionReward`](structured_rewards::TransitionReward) paired with [`GoodProcsQ`],
+/// which is just the rewards for each of its fields. Both share the same transition probability.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+pub struct GoodProcsReward(pub NoDiscountReward, pub CountReward);
+
+impl Compose<GoodProcsQ, GoodProcsQ> for GoodProcsReward {
+    fn compose(&self, other: &GoodProcsQ) -> GoodProcsQ {
+        GoodProcsQ {
+            outcome: self.0.compose(&other.outcome),
+            good_procs: self.1.compose(&other.good_procs),
+        }
+    }
+}
+
+impl<'a, A, C, M> State<GoodProcsQ, GoodProcsReward, GoodProcsQ> for CraftingMdp<'a, A, C, M>
+where
+    A: Action + RandomAction + Copy,
+    C: ConditionTransitions + Hash + Eq,
+    M: QualityMap + Hash + Eq,
+    M::Outcome: OutcomeValue,
+{
+    type SuccRewardIter = std::vec::IntoIter<(Self, GoodProcsReward)>;
+
+    type Action = A;
+
+    type ActionIter = std::vec::IntoIter<A>;
+
+    type Key = Self;
+
+    fn successors(&self, action: Self::Action) -> Self::SuccRewardIter {
+        let lucky = self.state.condition.is_good() || self.state.condition.is_excellent();
+
+        self.branches(action)
+            .into_iter()
+            .map(|(succ, prob)| {
+                let used = lucky && succ.state.curr_quality > self.state.curr_quality;
+                let reward = GoodProcsReward(
+                    NoDiscountReward(prob, succ.outcome_value()),
+                    CountReward(prob, used as u32),
+                );
+
+                (succ, reward)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn actions(&self) -> Self::ActionIter {
+        self.available_actions().into_iter()
+    }
+
+    fn key(&self) -> Self::Key {
+        *self
+    }
+}
+
+/// A [`ConvergenceMeasure`] that stops once no Q-value changed by more than the given amount
+/// over a sweep of the solver, and every Q-value has been computed at least once.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct MaxDelta(pub f64);
+
+impl<S, A> ConvergenceMeasure<S, A, SimpleQ> for MaxDelta
+where
+    S: Hash + Eq,
+    A: Hash + Eq,
+{
+    fn converges(
+        &mut self,
+        old: &HashMap<S, HashMap<A, SimpleQ>>,
+        curr: &HashMap<S, HashMap<A, SimpleQ>>,
+    ) -> bool {
+        curr.iter().all(|(state, qs)| {
+            qs.iter().all(|(action, new_q)| {
+                old.get(state)
+                    .and_then(|old_qs| old_qs.get(action))
+                    .is_some_and(|old_q| (old_q.0 - new_q.0).abs() <= self.0)
+            })
+        })
+    }
+}
+
+/// One action of a [`linear_rotation`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct RotationStep<A> {
+    /// The action the policy chose.
+    pub action: A,
+
+    /// Whether the policy would choose a different next action if this one turned out
+    /// differently, i.e. if it failed or the condition changed to something other than the most
+    /// likely one. A macro that follows the rotation blindly may be suboptimal past this point.
+    pub branches: bool,
+}
+
+/// Flattens a solved `policy` into the single rotation a player would follow if every roll went
+/// the most likely way. Starting from `start`, this repeatedly takes the policy's best action and
+/// moves on to that action's most likely outcome (for the regular conditions, the condition stays
+/// Normal and actions that can fail succeed more often than not), until the craft finishes or the
+/// policy has nothing to say about a state.
+///
+/// This is only an approximation of the policy, which in general reacts to the condition and
+/// to actions failing. Each step records whether the policy [`branches`](RotationStep::branches)
+/// after it, to show where the rotation stops matching what the policy would actually do.
+pub fn linear_rotation<'a, A, C, M, Q>(
+    start: CraftingMdp<'a, A, C, M>,
+    policy: &SolvedPolicy<CraftingMdp<'a, A, C, M>, A, Q>,
+) -> Vec<RotationStep<A>>
+where
+    A: Action + RandomAction + Copy + Hash + Ord,
+    C: ConditionTransitions + Hash + Eq,
+    M: QualityMap + Hash + Eq,
+    Q: SemanticOrd,
+{
+    let mut rotation = vec![];
+    let mut state = start;
+
+    while let Some(&action) = policy.best_action(&state) {
+        let branches = state.branches(action);
+        let Some(&(next, _)) = branches.iter().max_by(|(_, a), (_, b)| a.total_cmp(b)) else {
+            break;
+        };
+
+        let planned = policy.best_action(&next);
+        rotation.push(RotationStep {
+            action,
+            branches: branches
+                .iter()
+                .any(|(other, _)| policy.best_action(other) != planned),
+        });
+
+        state = next;
+    }
+
+    rotation
+}
+
+/// Renders the part of a solved `policy` reachable from `start` as a [GraphViz] DOT graph, to
+/// inspect how it reacts to the condition and to actions failing. Available under the `dot`
+/// feature.
+///
+/// Each node is a state, labeled with its [`summary`](CraftingState::summary), and each edge is
+/// one of the ways the policy's best action for that state can turn out, labeled with the action
+/// and the chance of that outcome. The graph grows with every branch, so this is only readable
+/// for small crafts.
+///
+/// [GraphViz]: https://graphviz.org/
+#[cfg(feature = "dot")]
+pub fn policy_dot<'a, A, C, M, Q>(
+    start: CraftingMdp<'a, A, C, M>,
+    policy: &SolvedPolicy<CraftingMdp<'a, A, C, M>, A, Q>,
+) -> String
+where
+    A: Action + RandomAction + Copy + Hash + Ord + std::fmt::Debug,
+    C: ConditionTransitions + Hash + Eq + std::fmt::Debug,
+    M: QualityMap + Hash + Eq,
+    Q: SemanticOrd,
+{
+    use std::{collections::VecDeque, fmt::Write};
+
+    let mut dot = String::from("digraph policy {\n    node [shape=box];\n");
+    let mut ids = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((state, step)) = queue.pop_front() {
+        let id = ids[&state];
+        writeln!(
+            dot,
+            "    s{} [label=\"{}\"];",
+            id,
+            state.state.summary(step)
+        )
+        .unwrap();
+
+        let Some(&action) = policy.best_action(&state) else {
+            continue;
+        };
+
+        for (next, p) in state.branches(action) {
+            let len = ids.len();
+            let next_id = *ids.entry(next).or_insert_with(|| {
+                queue.push_back((next, step + 1));
+                len
+            });
+
+            writeln!(
+                dot,
+                "    s{} -> s{} [label=\"{:?} ({:.0}%)\"];",
+                id,
+                next_id,
+                action,
+                p * 100.
+            )
+            .unwrap();
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+    use structured_rewards::{laws::assert_bellman_laws, simple_solvers::simple_solver};
+
+    use super::*;
+    use crate::{
+        actions::collection::FfxivCraftingActions, conditions::QARegularConditions,
+        quality_map::HQMap, test_util::CLASSICAL_SIMULATOR, RecipeLevelRanges, RecipeStats,
+    };
+
+    /// Floats that are exactly representable, along with any sum of a few of them.
+    fn exact() -> impl Strategy<Value = f64> {
+        (-1_000_000i32..1_000_000).prop_map(|n| n as f64 / 64.)
+    }
+
+    fn completion_first_q() -> impl Strategy<Value = CompletionFirstQ> {
+        (exact(), exact(), exact()).prop_map(|(completion, quality, steps)| CompletionFirstQ {
+            completion,
+            quality,
+            steps,
+        })
+    }
+
+    fn good_procs_q() -> impl Strategy<Value = GoodProcsQ> {
+        (exact(), exact()).prop_map(|(outcome, good_procs)| GoodProcsQ {
+            outcome: SimpleQ(outcome),
+            good_procs: CountQ(good_procs),
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn completion_first_q_follows_bellman_laws(
+            a in completion_first_q(),
+            b in completion_first_q(),
+            c in completion_first_q(),
+        ) {
+            assert_bellman_laws::<CompletionFirstQ, _>(&a, &b, &c);
+        }
+
+        #[test]
+        fn good_procs_q_follows_bellman_laws(
+            a in good_procs_q(),
+            b in good_procs_q(),
+            c in good_procs_q(),
+        ) {
+            assert_bellman_laws::<GoodProcsQ, _>(&a, &b, &c);
+        }
+    }
+
+    #[test]
+    fn successors_sum_to_one() {
+        let actions = [
+            FfxivCraftingActions::BasicSynthesis,
+            FfxivCraftingActions::HastyTouch,
+        ];
+        let start = CraftingMdp::new(&CLASSICAL_SIMULATOR, &actions);
+
+        for action in start.available_actions() {
+            let total = start
+                .branches(action)
+                .into_iter()
+                .map(|(_, prob)| prob)
+                .sum::<f64>();
+            assert!((total - 1.).abs() < 1e-9, "{:?} sums to {}", action, total);
+        }
+    }
+
+    #[test]
+    fn time_stops_keep_the_condition() {
+        let actions = [FfxivCraftingActions::FinalAppraisal];
+        let mut start = CraftingMdp::new(&CLASSICAL_SIMULATOR, &actions);
+        start.state.condition = QARegularConditions::Excellent;
+
+        let branches = start.branches(FfxivCraftingActions::FinalAppraisal);
+        assert_eq!(branches.len(), 1);
+        let (next, prob) = branches[0];
+        assert_eq!(prob, 1.);
+        assert_eq!(next.state.condition, QARegularConditions::Excellent);
+    }
+
+    #[test]
+    fn careful_observation_changes_the_condition() {
+        let actions = [FfxivCraftingActions::CarefulObservation];
+        let mut start = CraftingMdp::new(&CLASSICAL_SIMULATOR, &actions);
+        start.state = CraftingState::new_specialist_simulation(&CLASSICAL_SIMULATOR, 3);
+
+        let branches = start.branches(FfxivCraftingActions::CarefulObservation);
+        assert!(branches.len() > 1);
+        assert!(branches
+            .iter()
+            .any(|(next, _)| next.state.condition != QARegularConditions::Normal));
+    }
+
+    #[test]
+    fn touches_before_finishing() {
+        // Room for exactly two actions, and a single Basic Synthesis finishes the craft
+        const TWO_STEPS: CraftingSimulator<QARegularConditions, HQMap> = CraftingSimulator::new(
+            CLASSICAL_SIMULATOR.character,
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 20, 10_000, 700),
+            QARegularConditions::Normal,
+        );
+
+        let actions = [
+            FfxivCraftingActions::BasicSynthesis,
+            FfxivCraftingActions::BasicTouch,
+        ];
+        let start = CraftingMdp::new(&TWO_STEPS, &actions);
+        let qs = simple_solver(start, MaxDelta(1e-9));
+
+        let synth = qs.q(&start, &FfxivCraftingActions::BasicSynthesis).unwrap();
+        let touch = qs.q(&start, &FfxivCraftingActions::BasicTouch).unwrap();
+        assert!(touch.0 > synth.0, "touch: {:?}, synth: {:?}", touch, synth);
+    }
+
+    #[test]
+    fn linear_rotation_follows_likely_path() {
+        const TWO_STEPS: CraftingSimulator<QARegularConditions, HQMap> = CraftingSimulator::new(
+            CLASSICAL_SIMULATOR.character,
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 20, 10_000, 700),
+            QARegularConditions::Normal,
+        );
+
+        let actions = [
+            FfxivCraftingActions::BasicSynthesis,
+            FfxivCraftingActions::BasicTouch,
+        ];
+        let start = CraftingMdp::new(&TWO_STEPS, &actions);
+        let policy = simple_solver(start, MaxDelta(1e-9));
+
+        assert_eq!(
+            linear_rotation(start, &policy),
+            [
+                RotationStep {
+                    action: FfxivCraftingActions::BasicTouch,
+                    branches: false,
+                },
+                RotationStep {
+                    action: FfxivCraftingActions::BasicSynthesis,
+                    branches: false,
+                },
+            ]
+        );
+
+        // A (made up) policy that touches again if the condition turns Good
+        let mut qs = policy.into_inner();
+        for (next, _) in start.branches(FfxivCraftingActions::BasicTouch) {
+            if next.state.condition == QARegularConditions::Good {
+                qs.get_mut(&next)
+                    .unwrap()
+                    .insert(FfxivCraftingActions::BasicTouch, SimpleQ(1_000.));
+            }
+        }
+        let rotation = linear_rotation(start, &SolvedPolicy::from(qs));
+        assert!(rotation[0].branches);
+        assert_eq!(rotation[1].action, FfxivCraftingActions::BasicSynthesis);
+    }
+
+    #[cfg(feature = "dot")]
+    #[test]
+    fn policy_dot_walks_branches() {
+        const TWO_STEPS: CraftingSimulator<QARegularConditions, HQMap> = CraftingSimulator::new(
+            CLASSICAL_SIMULATOR.character,
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 20, 10_000, 700),
+            QARegularConditions::Normal,
+        );
+
+        let actions = [
+            FfxivCraftingActions::BasicSynthesis,
+            FfxivCraftingActions::BasicTouch,
+        ];
+        let start = CraftingMdp::new(&TWO_STEPS, &actions);
+        let policy = simple_solver(start, MaxDelta(1e-9));
+
+        let dot = policy_dot(start, &policy);
+        assert!(dot.starts_with("digraph policy {"));
+        assert!(dot.contains(&start.state.summary(0)));
+
+        // Basic Touch branches on the three conditions that can follow Normal, then each of
+        // those finishes with Basic Synthesis
+        assert_eq!(dot.matches("BasicTouch (").count(), 3);
+        assert_eq!(dot.matches("BasicSynthesis (").count(), 3);
+        assert_eq!(dot.matches("[label=\"[").count(), 7);
+    }
+
+    #[test]
+    fn max_steps_caps_rotation() {
+        // Room for four actions, and a single Basic Synthesis finishes the craft
+        const FOUR_STEPS: CraftingSimulator<QARegularConditions, HQMap> = CraftingSimulator::new(
+            CLASSICAL_SIMULATOR.character,
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 40, 10_000, 700),
+            QARegularConditions::Normal,
+        );
+
+        let actions = [
+            FfxivCraftingActions::BasicSynthesis,
+            FfxivCraftingActions::BasicTouch,
+        ];
+        let start = CraftingMdp::new(&FOUR_STEPS, &actions);
+        let rotation = linear_rotation(start, &simple_solver(start, MaxDelta(1e-9)));
+        assert_eq!(rotation.len(), 4);
+
+        for max_steps in 1..4 {
+            let start = start.with_max_steps(max_steps);
+            let rotation = linear_rotation(start, &simple_solver(start, MaxDelta(1e-9)));
+
+            assert_eq!(rotation.len(), max_steps as usize);
+            assert_eq!(
+                rotation.last().unwrap().action,
+                FfxivCraftingActions::BasicSynthesis
+            );
+        }
+    }
+
+    #[test]
+    fn completion_beats_any_quality() {
+        let completes = CompletionFirstQ {
+            completion: 1.,
+            quality: 0.,
+            steps: 30.,
+        };
+        let risky = CompletionFirstQ {
+            completion: 0.99,
+            quality: 10_000.,
+            steps: 1.,
+        };
+
+        assert_eq!(completes.sem_cmp(&risky), Ordering::Greater);
+        assert_eq!(risky.sem_cmp(&completes), Ordering::Less);
+    }
+
+    #[test]
+    fn completion_first_avoids_risky_quality() {
+        // Two actions of durability, two Basic Synthesis always finish the craft, but Basic Touch
+        // followed by a (50% chance) Rapid Synthesis is worth a lot more quality
+        const TWO_STEPS: CraftingSimulator<QARegularConditions, HQMap> = CraftingSimulator::new(
+            CLASSICAL_SIMULATOR.character,
+            RecipeStats::new(RecipeLevelRanges::ShbLeveling(75), 20, 1_000, 1_500),
+            QARegularConditions::Normal,
+        );
+
+        let actions = [
+            FfxivCraftingActions::BasicSynthesis,
+            FfxivCraftingActions::BasicTouch,
+            FfxivCraftingActions::RapidSynthesis,
+        ];
+        let start = CraftingMdp::new(&TWO_STEPS, &actions);
+
+        let hq_qs = simple_solver(start, MaxDelta(1e-9));
+        assert_eq!(
+            hq_qs.best_action(&start),
+            Some(&FfxivCraftingActions::BasicTouch)
+        );
+
+        let qs = simple_solver::<_, _, CompletionFirstQ, _, _>(start, Unchanged);
+        assert_eq!(
+            qs.best_action(&start),
+            Some(&FfxivCraftingActions::BasicSynthesis)
+        );
+        let q = qs.value(&start).unwrap();
+        assert!((q.completion - 1.).abs() < CompletionFirstQ::TOLERANCE);
+    }
+
+    #[test]
+    fn prunes_repeated_observe() {
+        const ACTIONS: [FfxivCraftingActions; 2] = [
+            FfxivCraftingActions::Observe,
+            FfxivCraftingActions::BasicSynthesis,
+        ];
+        let start = CraftingMdp::new(&CLASSICAL_SIMULATOR, &ACTIONS);
+        let observed = |mdp: CraftingMdp<'static, _, _, _>| {
+            mdp.branches(FfxivCraftingActions::Observe)
+                .into_iter()
+                .find(|(next, _)| next.state.condition == QARegularConditions::Normal)
+                .unwrap()
+                .0
+        };
+
+        let unpruned = observed(start);
+        assert_eq!(unpruned.available_actions(), ACTIONS);
+
+        let pruned = observed(start.without_repeated_observe());
+        assert_eq!(
+            pruned.available_actions(),
+            [FfxivCraftingActions::BasicSynthesis]
+        );
+        assert_eq!(
+            start.without_repeated_observe().available_actions(),
+            ACTIONS
+        );
+    }
+
+    #[test]
+    fn good_procs_break_ties() {
+        // Quality is already maxed, so touching first can't change the HQ chance, but it does make
+        // use of the Good condition
+        let actions = [
+            FfxivCraftingActions::BasicSynthesis,
+            FfxivCraftingActions::BasicTouch,
+        ];
+        let mut start = CraftingMdp::new(&CLASSICAL_SIMULATOR, &actions);
+        start.state.condition = QARegularConditions::Good;
+        start.state.curr_quality = CLASSICAL_SIMULATOR.recipe.max_quality;
+        start.state.curr_progress = CLASSICAL_SIMULATOR.recipe.max_progress - 1;
+
+        let qs = simple_solver::<_, _, GoodProcsQ, _, _>(start, Unchanged);
+        let synth = qs.q(&start, &FfxivCraftingActions::BasicSynthesis).unwrap();
+        let touch = qs.q(&start, &FfxivCraftingActions::BasicTouch).unwrap();
+
+        assert!(touch.outcome.sem_eq(&synth.outcome));
+        assert!(touch.good_procs.0 >= 1.);
+        assert_eq!(synth.good_procs, CountQ(0.));
+        assert_eq!(touch.sem_cmp(synth), Ordering::Greater);
+    }
+
+    /// The crafts in these tests are short enough that two identical sweeps means we're done.
+    struct Unchanged;
+
+    impl<S, A, Q> ConvergenceMeasure<S, A, Q> for Unchanged
+    where
+        S: Hash + Eq,
+        A: Hash + Eq,
+        Q: QVal + PartialEq,
+    {
+        fn converges(
+            &mut self,
+            old: &HashMap<S, HashMap<A, Q>>,
+            curr: &HashMap<S, HashMap<A, Q>>,
+        ) -> bool {
+            old == curr
+        }
+    }
+}
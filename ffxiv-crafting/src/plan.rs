@@ -0,0 +1,152 @@
+//! Bundles a rotation together with the result of simulating it, as a single document that can be
+//! shared. With the `serde` feature, a [`CraftingPlan`] can be serialized to (or loaded from) any
+//! format `serde` supports, such as JSON.
+
+use crate::{
+    actions::{collection::FfxivCraftingActions, errors::ActionError, Action, ActionOutcome},
+    conditions::Condition,
+    quality_map::HQMap,
+    CharacterStats, CraftingSimulator, CraftingState, RecipeStats,
+};
+
+/// A rotation for a given character and recipe, along with what it results in.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CraftingPlan {
+    /// The character performing the rotation.
+    pub character: CharacterStats,
+
+    /// The recipe being crafted.
+    pub recipe: RecipeStats,
+
+    /// The actions making up the rotation, in order.
+    pub actions: Vec<FfxivCraftingActions>,
+
+    /// The state of the craft once the rotation has been performed.
+    pub result: PlanResult,
+}
+
+/// The final stats of a [`CraftingPlan`]'s craft.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlanResult {
+    /// The quality the craft ended on. Like [`CraftingState`], this can be over the recipe's maximum.
+    pub quality: u32,
+
+    /// The progress the craft ended on. Like [`CraftingState`], this can be over the recipe's maximum.
+    pub progress: u32,
+
+    /// The durability the item was left with.
+    pub durability: i8,
+
+    /// The CP left over.
+    pub cp: i16,
+
+    /// The chance of the item being HQ, if the craft was completed.
+    pub hq_chance: Option<u8>,
+
+    /// The number of actions that were actually performed. This will be less than the length of the
+    /// rotation if the craft finished (or failed) before the rotation did.
+    pub steps: usize,
+}
+
+impl CraftingPlan {
+    /// Simulates `actions` on a fresh craft of `problem_def` to fill in the result.
+    ///
+    /// The simulation is deterministic: every action that can fail is assumed to succeed, and
+    /// the condition never changes from its default (e.g. Normal). Any actions after the craft
+    /// completes or fails are kept in the plan, but aren't simulated.
+    ///
+    /// If an action can't be executed when it comes up in the rotation, this returns the
+    /// [`ActionError`] explaining why.
+    pub fn simulate<C>(
+        problem_def: &CraftingSimulator<C, HQMap>,
+        actions: Vec<FfxivCraftingActions>,
+    ) -> Result<Self, ActionError>
+    where
+        C: Condition + Default,
+    {
+        let mut state = CraftingState::new_simulation(problem_def);
+
+        let mut steps = 0;
+        let mut hq_chance = None;
+
+        for action in actions.iter() {
+            let outcome = action.prospective_act(&state)?;
+            steps += 1;
+
+            if let ActionOutcome::Completed(_) = outcome {
+                hq_chance = outcome.map_quality(&state).map(|hq| hq.0);
+            }
+
+            state += outcome.outcome();
+
+            if !matches!(outcome, ActionOutcome::InProgress(_)) {
+                break;
+            }
+        }
+
+        Ok(Self {
+            character: problem_def.character,
+            recipe: problem_def.recipe,
+            actions,
+            result: PlanResult {
+                quality: state.curr_quality,
+                progress: state.curr_progress,
+                durability: state.curr_durability,
+                cp: state.curr_cp,
+                hq_chance,
+                steps,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::CLASSICAL_SIMULATOR;
+
+    use FfxivCraftingActions::*;
+
+    #[test]
+    fn stops_simulating_when_finished() {
+        let actions = vec![
+            BasicTouch,
+            StandardTouch,
+            CarefulSynthesis,
+            CarefulSynthesis,
+            CarefulSynthesis,
+            CarefulSynthesis,
+            BasicTouch,
+        ];
+
+        let plan = CraftingPlan::simulate(&CLASSICAL_SIMULATOR, actions.clone()).unwrap();
+
+        assert_eq!(plan.actions, actions);
+        assert_eq!(plan.result.steps, 6);
+        assert!(plan.result.progress >= CLASSICAL_SIMULATOR.recipe.max_progress);
+        assert!(plan.result.hq_chance.is_some());
+        assert_eq!(plan.result.durability, 20);
+    }
+
+    #[test]
+    fn invalid_action_is_an_error() {
+        let actions = vec![BasicTouch, MuscleMemory];
+
+        assert!(matches!(
+            CraftingPlan::simulate(&CLASSICAL_SIMULATOR, actions),
+            Err(ActionError::ActionInvalid(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let plan =
+            CraftingPlan::simulate(&CLASSICAL_SIMULATOR, vec![BasicTouch, BasicSynthesis]).unwrap();
+
+        let json = serde_json::to_string(&plan).unwrap();
+        assert_eq!(serde_json::from_str::<CraftingPlan>(&json).unwrap(), plan);
+    }
+}
@@ -1,6 +1,8 @@
 //! Contains types that map the crafting state's quality value to [`HQChance`]
 //! or [`Collectability`].
 
+use std::marker::PhantomData;
+
 use crate::lookups;
 
 /// Maps the `quality` property to either [`HQChance`] or
@@ -14,7 +16,8 @@ pub trait QualityMap: Copy {
     fn convert(quality: u32, recipe_quality: u32) -> Self::Outcome;
 }
 
-/// Maps quality to [`HQChance`].
+/// Maps quality to [`HQChance`] using the game's own [`HQTable`]. For a different
+/// [`HQFormula`], use [`FormulaHQMap`].
 #[derive(Clone, Copy, Hash, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct HQMap;
 
@@ -22,16 +25,50 @@ impl QualityMap for HQMap {
     type Outcome = HQChance;
 
     fn convert(quality: u32, recipe_quality: u32) -> HQChance {
-        HQChance(lookups::lookup_hq(
-            quality.min(recipe_quality),
-            recipe_quality,
-        ))
+        FormulaHQMap::<HQTable>::convert(quality, recipe_quality)
+    }
+}
+
+/// Maps quality to [`HQChance`] using the given [`HQFormula`], e.g. to model content from
+/// patches where the formula was different.
+#[derive(Clone, Copy, Hash, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FormulaHQMap<F>(PhantomData<F>)
+where
+    F: HQFormula;
+
+impl<F> QualityMap for FormulaHQMap<F>
+where
+    F: HQFormula,
+{
+    type Outcome = HQChance;
+
+    fn convert(quality: u32, recipe_quality: u32) -> HQChance {
+        HQChance(F::hq_chance(quality.min(recipe_quality), recipe_quality))
+    }
+}
+
+/// Determines the chance of an item coming out HQ for [`FormulaHQMap`]. This can be swapped out to
+/// model content from patches where the formula was different.
+pub trait HQFormula: Copy {
+    /// Returns the chance from 1-100 that an item will come out HQ. The `quality` will never
+    /// be above the `recipe_quality`.
+    fn hq_chance(quality: u32, recipe_quality: u32) -> u8;
+}
+
+/// The game's lookup table from the percentage of max quality to HQ chance, as used by [`HQMap`].
+#[derive(Clone, Copy, Hash, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HQTable;
+
+impl HQFormula for HQTable {
+    fn hq_chance(quality: u32, recipe_quality: u32) -> u8 {
+        lookups::lookup_hq(quality, recipe_quality)
     }
 }
 
 /// The chance from 1-100 that an item will come out HQ at the current
 /// quality. This can convert into its dual, [`NQChance`].
 #[derive(Clone, Copy, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HQChance(pub u8);
 
 impl From<NQChance> for HQChance {
@@ -49,6 +86,7 @@ impl Default for HQChance {
 /// The chance from 1-100 that an item will come out NQ at the current
 /// quality. This can convert into its dual, [`HQChance`].
 #[derive(Clone, Copy, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NQChance(pub u8);
 
 impl Default for NQChance {
@@ -78,4 +116,89 @@ impl QualityMap for CollectabilityMap {
 
 /// The collectability of an item, for turnins. The tiers
 /// are recipe (or at least rlvl) specific and should be mapped by the user.
+#[derive(Clone, Copy, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Collectability(pub u32);
+
+impl Collectability {
+    /// The tier this collectability reaches, given the minimum collectability of each tier in
+    /// ascending order (e.g. `[mid, high]` for most turn-ins). This is the number of thresholds
+    /// reached, so `0` means it isn't good enough to turn in at all, and reaching a threshold
+    /// exactly counts.
+    pub fn tier(&self, thresholds: &[u32]) -> usize {
+        thresholds.partition_point(|threshold| *threshold <= self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    struct Linear;
+
+    impl HQFormula for Linear {
+        fn hq_chance(quality: u32, recipe_quality: u32) -> u8 {
+            (quality * 100 / recipe_quality).max(1) as u8
+        }
+    }
+
+    #[test]
+    fn custom_hq_formula() {
+        assert_eq!(FormulaHQMap::<Linear>::convert(500, 1_000), HQChance(50));
+        assert_eq!(FormulaHQMap::<Linear>::convert(2_000, 1_000), HQChance(100));
+        assert_eq!(FormulaHQMap::<Linear>::convert(0, 1_000), HQChance(1));
+
+        assert_eq!(HQMap::convert(500, 1_000), HQChance(15));
+        assert_eq!(
+            FormulaHQMap::<HQTable>::convert(500, 1_000),
+            HQMap::convert(500, 1_000)
+        );
+    }
+
+    #[test]
+    fn collectability_tiers() {
+        let thresholds = [500, 650, 800];
+
+        assert_eq!(Collectability(0).tier(&thresholds), 0);
+        assert_eq!(Collectability(499).tier(&thresholds), 0);
+        assert_eq!(Collectability(500).tier(&thresholds), 1);
+        assert_eq!(Collectability(649).tier(&thresholds), 1);
+        assert_eq!(Collectability(650).tier(&thresholds), 2);
+        assert_eq!(Collectability(800).tier(&thresholds), 3);
+        assert_eq!(Collectability(1_000).tier(&thresholds), 3);
+        assert_eq!(Collectability(1_000).tier(&[]), 0);
+    }
+
+    #[test]
+    fn collectable_crafts() {
+        use crate::{
+            actions::{progress::BasicSynthesis, Action},
+            conditions::QARegularConditions,
+            test_util::CLASSICAL_SIMULATOR,
+            CraftingSimulator, CraftingState,
+        };
+
+        let problem_def = CraftingSimulator::<_, CollectabilityMap>::new(
+            CLASSICAL_SIMULATOR.character,
+            CLASSICAL_SIMULATOR.recipe,
+            QARegularConditions::Normal,
+        );
+        let mut state = CraftingState::new_simulation(&problem_def);
+        state.curr_progress = problem_def.recipe.max_progress - 1;
+
+        // Exactly on the threshold for the middle tier
+        state.curr_quality = 6_509;
+        let outcome = BasicSynthesis.act(&state);
+        let collectability = outcome.map_quality(&state).unwrap();
+        assert_eq!(collectability, Collectability(650));
+        assert_eq!(collectability.tier(&[500, 650, 800]), 2);
+
+        state.curr_quality = problem_def.recipe.max_quality + 1_000;
+        let collectability = BasicSynthesis.act(&state).map_quality(&state).unwrap();
+        assert_eq!(
+            collectability,
+            Collectability(problem_def.recipe.max_quality / 10)
+        );
+    }
+}
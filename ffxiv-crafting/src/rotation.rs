@@ -0,0 +1,645 @@
+//! Tools for inspecting a fixed rotation (a list of actions performed in order), as opposed to
+//! solving for one.
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    actions::{
+        collection::FfxivCraftingActions, Action, ActionMeta, ActionOutcome, CanExecute, CpCost,
+    },
+    buffs::BuffName,
+    conditions::Condition,
+    quality_map::{HQMap, QualityMap},
+    CraftingState, Outcome, RecipeStats,
+};
+
+/// A problem [`analyze_rotation`] found with one of the actions in a rotation.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Warning {
+    /// The index of the offending action in the rotation.
+    pub step: usize,
+
+    /// The offending action.
+    pub action: FfxivCraftingActions,
+
+    /// What's wrong with it.
+    pub kind: WarningKind,
+}
+
+/// The ways an action in a rotation can be a mistake.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WarningKind {
+    /// The action can't be used at this point in the craft, such as a second Muscle Memory or
+    /// Byregot's Blessing without Inner Quiet. The rest of the rotation is analyzed as if the
+    /// action was skipped.
+    Invalid,
+
+    /// There isn't enough CP left for the action. Like [`Invalid`](WarningKind::Invalid), it's
+    /// treated as skipped.
+    NotEnoughCp,
+
+    /// The action only adds quality, but quality is already at the recipe's maximum.
+    QualityAlreadyMaxed,
+
+    /// Observe wasn't followed by Focused Synthesis or Focused Touch, so it did nothing.
+    WastedObserve,
+
+    /// The craft was already finished (completed or failed) before this action.
+    AfterFinish,
+}
+
+/// Lints a rotation by simulating it from `start`, flagging actions that are illegal or wasteful.
+/// Warnings are returned in the order of the rotation, and a single action may have more than one.
+///
+/// Like [`CraftingPlan::simulate`](crate::plan::CraftingPlan::simulate), the simulation assumes
+/// every action succeeds and the condition never changes. Every action after the craft finishes
+/// is flagged as [`AfterFinish`](WarningKind::AfterFinish) and nothing else.
+pub fn analyze_rotation<C, M>(
+    start: &CraftingState<C, M>,
+    actions: &[FfxivCraftingActions],
+) -> Vec<Warning>
+where
+    C: Condition,
+    M: QualityMap,
+{
+    let mut warnings = vec![];
+    let mut state = *start;
+    let mut finished = false;
+
+    for (step, &action) in actions.iter().enumerate() {
+        let mut warn = |kind| warnings.push(Warning { step, action, kind });
+
+        if finished {
+            warn(WarningKind::AfterFinish);
+            continue;
+        }
+
+        if action == FfxivCraftingActions::Observe
+            && !matches!(
+                actions.get(step + 1),
+                Some(FfxivCraftingActions::FocusedSynthesis | FfxivCraftingActions::FocusedTouch)
+            )
+        {
+            warn(WarningKind::WastedObserve);
+        }
+
+        // Executing some invalid actions (even prospectively) can panic, so they need to be
+        // caught before trying
+        if !action.can_execute(&state) {
+            warn(WarningKind::Invalid);
+            continue;
+        }
+
+        let outcome = match action.prospective_act(&state) {
+            Ok(outcome) => outcome,
+            Err(_) => {
+                warn(WarningKind::NotEnoughCp);
+                continue;
+            }
+        };
+
+        let next = state + outcome.outcome();
+        if state.curr_quality >= state.problem_def.recipe.max_quality
+            && next.curr_quality > state.curr_quality
+            && next.curr_progress == state.curr_progress
+        {
+            warn(WarningKind::QualityAlreadyMaxed);
+        }
+
+        state = next;
+        finished = !matches!(outcome, ActionOutcome::InProgress(_));
+    }
+
+    warnings
+}
+
+/// How much of a rotation's quality and progress came from each action, as computed by
+/// [`attribute_rotation`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Attribution {
+    /// The contribution of each action that was performed, in order.
+    pub steps: Vec<StepContribution>,
+
+    /// The quality added by the whole rotation.
+    pub total_quality: u32,
+
+    /// The progress added by the whole rotation.
+    pub total_progress: u32,
+}
+
+/// The quality and progress added by a single action in a rotation.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StepContribution {
+    /// The index of the action in the rotation.
+    pub step: usize,
+
+    /// The action performed.
+    pub action: FfxivCraftingActions,
+
+    /// The quality the action added, including any amplification from buffs.
+    pub quality: u32,
+
+    /// The progress the action added, including any amplification from buffs.
+    pub progress: u32,
+
+    /// The buffs that were active when the action was used (e.g. Innovation), which is what
+    /// amplified it.
+    pub active_buffs: Vec<BuffName>,
+}
+
+impl Attribution {
+    /// The fraction of the rotation's total quality, in `[0.0, 1.0]`, that came from `step`.
+    /// This is `0.0` if the rotation added no quality at all.
+    pub fn quality_share(&self, step: &StepContribution) -> f64 {
+        share(step.quality, self.total_quality)
+    }
+
+    /// The fraction of the rotation's total progress, in `[0.0, 1.0]`, that came from `step`.
+    /// This is `0.0` if the rotation added no progress at all.
+    pub fn progress_share(&self, step: &StepContribution) -> f64 {
+        share(step.progress, self.total_progress)
+    }
+}
+
+fn share(part: u32, total: u32) -> f64 {
+    if total == 0 {
+        0.
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// Simulates a rotation from `start` and reports how much quality and progress each action added,
+/// so it's clear where the final result came from.
+///
+/// The simulation is deterministic in the same way as [`analyze_rotation`]. It stops when the
+/// craft finishes, or at the first action that can't be executed (which
+/// [`analyze_rotation`] can explain), so `steps` may be shorter than the rotation.
+pub fn attribute_rotation<C, M>(
+    start: &CraftingState<C, M>,
+    actions: &[FfxivCraftingActions],
+) -> Attribution
+where
+    C: Condition,
+    M: QualityMap,
+{
+    let mut steps = vec![];
+    let mut state = *start;
+
+    for (step, &action) in actions.iter().enumerate() {
+        if !action.can_execute(&state) {
+            break;
+        }
+
+        let outcome = match action.prospective_act(&state) {
+            Ok(outcome) => outcome,
+            Err(_) => break,
+        };
+
+        let next = state + outcome.outcome();
+        steps.push(StepContribution {
+            step,
+            action,
+            quality: next.curr_quality - state.curr_quality,
+            progress: next.curr_progress - state.curr_progress,
+            active_buffs: state.buffs.active(),
+        });
+
+        state = next;
+        if !matches!(outcome, ActionOutcome::InProgress(_)) {
+            break;
+        }
+    }
+
+    Attribution {
+        steps,
+        total_quality: state.curr_quality - start.curr_quality,
+        total_progress: state.curr_progress - start.curr_progress,
+    }
+}
+
+/// The results of running a rotation many times with [`simulate_batch`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchStats {
+    /// The number of times the rotation was run.
+    pub trials: usize,
+
+    /// The number of trials that completed the craft.
+    pub completed: usize,
+
+    /// The average HQ chance of the completed crafts, or `0.0` if none completed.
+    pub mean_hq: f64,
+
+    /// The lowest HQ chance of the completed crafts, if any completed.
+    pub min_hq: Option<u8>,
+
+    /// The highest HQ chance of the completed crafts, if any completed.
+    pub max_hq: Option<u8>,
+
+    /// The average number of actions performed per trial, whether it completed or not.
+    pub mean_steps: f64,
+
+    /// How many trials failed to complete the craft for each reason.
+    pub failures: HashMap<FailureCause, usize>,
+}
+
+impl BatchStats {
+    /// The fraction of trials, in `[0.0, 1.0]`, that completed the craft. This is `0.0` if there
+    /// were no trials.
+    pub fn completion_rate(&self) -> f64 {
+        if self.trials == 0 {
+            0.
+        } else {
+            self.completed as f64 / self.trials as f64
+        }
+    }
+}
+
+/// How long crafting takes in real time, for estimating [`throughput`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CraftTiming {
+    /// The average time each action takes, in seconds. Macros usually wait 3 seconds after an
+    /// action, or 2 after a buff, so this is somewhere in between depending on the rotation.
+    pub seconds_per_step: f64,
+
+    /// The time between one craft finishing and the first action of the next, in seconds. This
+    /// covers the animations at the end and start of a synthesis, as well as pressing the button to
+    /// start the next one.
+    pub overhead: f64,
+}
+
+impl Default for CraftTiming {
+    /// 3 seconds per action, as for an unoptimized macro, and 3 seconds between crafts.
+    fn default() -> Self {
+        Self {
+            seconds_per_step: 3.,
+            overhead: 3.,
+        }
+    }
+}
+
+/// Estimates how many items per hour crafting `recipe` over and over with a rotation makes, given
+/// the rotation's [`BatchStats`].
+///
+/// Each craft takes the rotation's average number of steps, plus the `timing` overhead. Failed
+/// crafts take just as long but make nothing, so this is scaled by the
+/// [`completion_rate`](BatchStats::completion_rate). This doesn't account for anything outside of
+/// crafting itself, such as repairs or eating food. If a craft would somehow take no time at all,
+/// this is `0.0` rather than infinite.
+pub fn throughput(recipe: &RecipeStats, stats: &BatchStats, timing: CraftTiming) -> f64 {
+    let seconds = stats.mean_steps * timing.seconds_per_step + timing.overhead;
+    if seconds <= 0. {
+        return 0.;
+    }
+
+    3_600. / seconds * stats.completion_rate() * recipe.result_quantity() as f64
+}
+
+/// Why a trial in [`simulate_batch`] didn't complete the craft.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FailureCause {
+    /// The item ran out of durability.
+    Broke,
+
+    /// An action couldn't be used when it came up, e.g. a Good-only action when the condition
+    /// wasn't Good. The trial stops there.
+    Invalid,
+
+    /// There wasn't enough CP left for an action. The trial stops there.
+    NotEnoughCp,
+
+    /// The craft got [stuck] partway through the rotation: no actions the character has learned
+    /// could complete it with the durability and CP it had left. The trial stops there.
+    ///
+    /// [stuck]: CraftingState::is_stuck
+    ResourceExhaustion,
+
+    /// The rotation ran out of actions before the craft was finished.
+    Unfinished,
+}
+
+/// Finds the actions in `rotation` that a character at `char_level` hasn't learned yet, and
+/// suggests a learned action from the same [category](FfxivCraftingActions::category) to use in
+/// their place. The result is the step, the unlearned action, and its suggested substitute.
+///
+/// The substitute is the learned action whose [`metadata`](FfxivCraftingActions::metadata) is
+/// closest, comparing durability usage first (counting a full repair as more than any other), then
+/// fail rate, then CP cost, and preferring the higher level action on a tie. For instance, Advanced Touch becomes Standard Touch. This doesn't
+/// simulate anything, so the substitute may not be usable at that point of the rotation (or as good).
+/// Actions without any learned substitute are left out.
+pub fn suggest_substitutes(
+    rotation: &[FfxivCraftingActions],
+    char_level: u8,
+) -> Vec<(usize, FfxivCraftingActions, FfxivCraftingActions)> {
+    let learned = |action: &FfxivCraftingActions| action.metadata().level <= char_level as u16;
+    let durability = |meta: ActionMeta| {
+        if meta.full_repair {
+            i8::MAX
+        } else {
+            meta.durability
+        }
+    };
+
+    rotation
+        .iter()
+        .enumerate()
+        .filter(|(_, action)| !learned(action))
+        .filter_map(|(step, &action)| {
+            let meta = action.metadata();
+
+            let substitute = FfxivCraftingActions::ALL
+                .into_iter()
+                .filter(|sub| sub.category() == action.category() && learned(sub))
+                .min_by_key(|sub| {
+                    let sub = sub.metadata();
+                    (
+                        durability(meta).abs_diff(durability(sub)),
+                        meta.fail_rate.abs_diff(sub.fail_rate),
+                        meta.cp_cost.abs_diff(sub.cp_cost),
+                        Reverse(sub.level),
+                    )
+                })?;
+
+            Some((step, action, substitute))
+        })
+        .collect()
+}
+
+/// Runs a rotation `trials` times from `start`, with actions succeeding or failing and the
+/// condition changing at random, to see how reliable it is in practice.
+///
+/// Like [`CraftingState::act`], the action rolls and condition changes come from two separate
+/// [`Rng`]s, both of which are seeded from `seed`. The same `seed` will always produce the same
+/// stats for a given version of `rand`.
+pub fn simulate_batch<C>(
+    start: &CraftingState<C, HQMap>,
+    actions: &[FfxivCraftingActions],
+    trials: usize,
+    seed: u64,
+) -> BatchStats
+where
+    C: Condition,
+{
+    let mut seeder = StdRng::seed_from_u64(seed);
+    let mut action_rng = StdRng::seed_from_u64(seeder.gen());
+    let mut condition_rng = StdRng::seed_from_u64(seeder.gen());
+
+    let mut hqs = vec![];
+    let mut steps = 0;
+    let mut failures = HashMap::new();
+
+    for _ in 0..trials {
+        let mut state = *start;
+        let mut result = Err(FailureCause::Unfinished);
+
+        for &action in actions {
+            if !action.can_execute(&state) {
+                result = Err(FailureCause::Invalid);
+                break;
+            }
+
+            if state.curr_cp + action.cp_cost(&state) < 0 {
+                result = Err(FailureCause::NotEnoughCp);
+                break;
+            }
+
+            steps += 1;
+            match state.act(action, &mut action_rng, &mut condition_rng) {
+                Outcome::InProgress { state: next, .. } if next.is_stuck() => {
+                    result = Err(FailureCause::ResourceExhaustion);
+                    break;
+                }
+                Outcome::InProgress { state: next, .. } => state = next,
+                Outcome::Completed { outcome, .. } => {
+                    result = Ok(outcome.0);
+                    break;
+                }
+                Outcome::Failure { .. } => {
+                    result = Err(FailureCause::Broke);
+                    break;
+                }
+            }
+        }
+
+        match result {
+            Ok(hq) => hqs.push(hq),
+            Err(cause) => *failures.entry(cause).or_default() += 1,
+        }
+    }
+
+    let mean = |total: usize, count: usize| {
+        if count == 0 {
+            0.
+        } else {
+            total as f64 / count as f64
+        }
+    };
+
+    BatchStats {
+        trials,
+        completed: hqs.len(),
+        mean_hq: mean(hqs.iter().map(|&hq| hq as usize).sum(), hqs.len()),
+        min_hq: hqs.iter().copied().min(),
+        max_hq: hqs.iter().copied().max(),
+        mean_steps: mean(steps, trials),
+        failures,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::CLASSICAL_SIMULATOR;
+
+    use FfxivCraftingActions::*;
+
+    fn kinds(warnings: &[Warning]) -> Vec<(usize, WarningKind)> {
+        warnings.iter().map(|w| (w.step, w.kind)).collect()
+    }
+
+    #[test]
+    fn clean_rotation() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let rotation = [
+            MuscleMemory,
+            BasicTouch,
+            Observe,
+            FocusedTouch,
+            BasicSynthesis,
+        ];
+
+        assert_eq!(analyze_rotation(&start, &rotation), vec![]);
+    }
+
+    #[test]
+    fn flags_mistakes() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let rotation = [
+            MuscleMemory,
+            MuscleMemory,
+            ByregotsBlessing,
+            Observe,
+            BasicTouch,
+        ];
+
+        assert_eq!(
+            kinds(&analyze_rotation(&start, &rotation)),
+            vec![
+                (1, WarningKind::Invalid),
+                (2, WarningKind::Invalid),
+                (3, WarningKind::WastedObserve),
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_maxed_quality_and_finished_craft() {
+        let mut start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        start.curr_quality = CLASSICAL_SIMULATOR.recipe.max_quality;
+        start.curr_progress = CLASSICAL_SIMULATOR.recipe.max_progress - 1;
+
+        let rotation = [BasicTouch, BasicSynthesis, BasicTouch];
+
+        assert_eq!(
+            kinds(&analyze_rotation(&start, &rotation)),
+            vec![
+                (0, WarningKind::QualityAlreadyMaxed),
+                (2, WarningKind::AfterFinish),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_amplified_quality() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let rotation = [BasicTouch, Innovation, BasicTouch, BasicSynthesis];
+
+        let attribution = attribute_rotation(&start, &rotation);
+        assert_eq!(attribution.steps.len(), 4);
+
+        let plain = &attribution.steps[0];
+        let innovated = &attribution.steps[2];
+        assert!(innovated.active_buffs.contains(&BuffName::Innovation));
+        assert!(!plain.active_buffs.contains(&BuffName::Innovation));
+        assert!(innovated.quality > plain.quality);
+
+        assert_eq!(
+            attribution.steps.iter().map(|s| s.quality).sum::<u32>(),
+            attribution.total_quality
+        );
+        let shares = attribution
+            .steps
+            .iter()
+            .map(|s| attribution.quality_share(s))
+            .sum::<f64>();
+        assert!((shares - 1.).abs() < 1e-9);
+
+        assert_eq!(attribution.progress_share(&attribution.steps[3]), 1.);
+    }
+
+    #[test]
+    fn substitutes_unlearned_actions() {
+        let rotation = [
+            Reflect,
+            AdvancedTouch,
+            BasicSynthesis,
+            TrainedFinesse,
+            CarefulSynthesis,
+        ];
+
+        let suggestions = suggest_substitutes(&rotation, 80);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0], (1, AdvancedTouch, StandardTouch));
+        assert_eq!(suggestions[1].0, 3);
+        assert!(suggestions[1].2.metadata().level <= 80);
+
+        assert!(suggest_substitutes(&rotation, 90).is_empty());
+        assert!(suggest_substitutes(&[Veneration], 1).is_empty());
+
+        // A full repair is closest to the biggest fixed one
+        assert_eq!(
+            suggest_substitutes(&[ImmaculateMend], 97),
+            [(0, ImmaculateMend, MastersMend)]
+        );
+    }
+
+    #[test]
+    fn batch_is_reproducible() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let rotation = [
+            HastyTouch,
+            HastyTouch,
+            CarefulSynthesis,
+            CarefulSynthesis,
+            CarefulSynthesis,
+            CarefulSynthesis,
+        ];
+
+        let stats = simulate_batch(&start, &rotation, 200, 7);
+        assert_eq!(stats, simulate_batch(&start, &rotation, 200, 7));
+
+        assert_eq!(stats.trials, 200);
+        assert!(stats.completed > 0);
+        assert!(stats.min_hq <= stats.max_hq);
+        assert!(stats.mean_steps <= rotation.len() as f64);
+    }
+
+    #[test]
+    fn batch_counts_failures() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+
+        let stats = simulate_batch(&start, &[BasicTouch], 10, 0);
+        assert_eq!(stats.completion_rate(), 0.);
+        assert_eq!(stats.min_hq, None);
+        assert_eq!(stats.failures[&FailureCause::Unfinished], 10);
+
+        let stats = simulate_batch(&start, &[BasicSynthesis, MuscleMemory], 10, 0);
+        assert_eq!(stats.failures[&FailureCause::Invalid], 10);
+        assert_eq!(stats.mean_steps, 1.);
+
+        let mut problem = CLASSICAL_SIMULATOR;
+        problem.recipe.max_progress = 20_000;
+        let mut start = CraftingState::new_simulation(&problem);
+        start.curr_durability = 20;
+        start.curr_cp = 0;
+        let stats = simulate_batch(&start, &[BasicSynthesis, BasicSynthesis], 10, 0);
+        assert_eq!(stats.failures[&FailureCause::ResourceExhaustion], 10);
+        assert_eq!(stats.mean_steps, 1.);
+    }
+
+    #[test]
+    fn throughput_counts_completed_items() {
+        let mut stats = BatchStats {
+            trials: 4,
+            completed: 4,
+            mean_hq: 100.,
+            min_hq: Some(100),
+            max_hq: Some(100),
+            mean_steps: 9.,
+            failures: HashMap::new(),
+        };
+        let recipe = CLASSICAL_SIMULATOR.recipe;
+
+        // 30 seconds per craft
+        assert_eq!(throughput(&recipe, &stats, CraftTiming::default()), 120.);
+        assert_eq!(
+            throughput(
+                &recipe.with_result_quantity(3),
+                &stats,
+                CraftTiming::default()
+            ),
+            360.
+        );
+
+        stats.completed = 3;
+        assert_eq!(throughput(&recipe, &stats, CraftTiming::default()), 90.);
+
+        let instant = CraftTiming {
+            seconds_per_step: 0.,
+            overhead: 0.,
+        };
+        assert_eq!(throughput(&recipe, &stats, instant), 0.);
+    }
+}
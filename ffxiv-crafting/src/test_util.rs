@@ -0,0 +1,246 @@
+//! Helpers for testing code built on the simulator, available under the `test-util` feature.
+//!
+//! [`FixedRng`] makes rolls (both [`RandomAction`] successes and [`Condition`] changes)
+//! reproducible without needing to reason about a seeded generator, and [`shrink_rotation`]
+//! narrows a failing rotation down to the action that actually broke things.
+//!
+//! [`RandomAction`]: crate::actions::RandomAction
+
+use rand::{Error, Rng, RngCore};
+
+use crate::{
+    actions::{Action, RandomAction},
+    conditions::Condition,
+    quality_map::QualityMap,
+    CraftingState, Outcome,
+};
+
+#[cfg(test)]
+pub(crate) use self::fixtures::*;
+
+/// An [`Rng`] that yields the same value forever, so every roll it's used for comes out the same.
+///
+/// The value is chosen such that any 100-wide range (as is used for action success rates and
+/// [`Condition`] changes) always rolls the same position in that range. Other ranges will still
+/// roll consistently, just not necessarily at a predictable position.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FixedRng(u32);
+
+impl FixedRng {
+    /// Always rolls the bottom of the range, so [`RandomAction`]s always fail and a
+    /// [`Condition`] will always move to its first non-Normal state.
+    ///
+    /// [`RandomAction`]: crate::actions::RandomAction
+    pub const fn lowest() -> Self {
+        Self::new(0)
+    }
+
+    /// Always rolls the top of the range, so [`RandomAction`]s always succeed (unless they
+    /// can never succeed) and a [`Condition`] will stay Normal.
+    ///
+    /// [`RandomAction`]: crate::actions::RandomAction
+    pub const fn highest() -> Self {
+        Self::new(99)
+    }
+
+    /// Creates an [`Rng`] that always rolls the value at position `roll` (from 0 to 99) of a
+    /// 100-wide range.
+    pub const fn new(roll: u8) -> Self {
+        debug_assert!(roll < 100);
+        // Ceiling of `roll/100` of the way through `u32`, which keeps `rand`'s widening
+        // multiply sampler from rejecting the value.
+        let val = ((roll as u64) << 32).div_ceil(100);
+        Self(val as u32)
+    }
+}
+
+impl RngCore for FixedRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        ((self.0 as u64) << 32) | self.0 as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let bytes = self.0.to_le_bytes();
+        for (dest, src) in dest.iter_mut().zip(bytes.iter().cycle()) {
+            *dest = *src;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Runs `rotation` from `start`, checking `property` against the [`Outcome`] of every action,
+/// and returns the shortest prefix of `rotation` whose outcome violates it. If the craft completes
+/// or fails partway through, nothing after that point is run.
+///
+/// Since the rngs are cloned fresh for the run, each prefix sees exactly the same rolls it would
+/// have seen as part of the full rotation. This means the returned prefix is the minimal
+/// reproduction for the given rngs, so it's best to pair this with [`FixedRng`] or a seeded [`Rng`].
+///
+/// Returns [`None`] if the property holds for the whole rotation.
+///
+/// # Panics
+///
+/// As this uses [`CraftingState::act`], this panics if an action in the rotation can't be executed.
+pub fn shrink_rotation<'r, 'a, A, C, M, R1, R2, P>(
+    start: CraftingState<'a, C, M>,
+    rotation: &'r [A],
+    action_rng: &R1,
+    condition_rng: &R2,
+    mut property: P,
+) -> Option<&'r [A]>
+where
+    A: Action + RandomAction + Copy,
+    C: Condition,
+    M: QualityMap,
+    R1: Rng + Clone,
+    R2: Rng + Clone,
+    P: FnMut(&Outcome<'a, C, M>) -> bool,
+{
+    let mut action_rng = action_rng.clone();
+    let mut condition_rng = condition_rng.clone();
+    let mut state = start;
+
+    for (i, action) in rotation.iter().enumerate() {
+        let outcome = state.act(*action, &mut action_rng, &mut condition_rng);
+
+        if !property(&outcome) {
+            return Some(&rotation[..=i]);
+        }
+
+        match outcome {
+            Outcome::InProgress { state: next, .. } => state = next,
+            Outcome::Completed { .. } | Outcome::Failure { .. } => return None,
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod fixtures {
+    use std::marker::PhantomData;
+
+    use crate::{
+        conditions::QARegularConditions, quality_map::HQMap, CharacterStats, CraftingSimulator,
+        RecipeLevelRanges, RecipeStats,
+    };
+
+    /// A level-capped ShB character making a regular (Normal/Good/Excellent/Poor) recipe.
+    pub(crate) const CLASSICAL_SIMULATOR: CraftingSimulator<QARegularConditions, HQMap> =
+        CraftingSimulator {
+            character: CharacterStats {
+                craftsmanship: 2000,
+                control: 2000,
+                max_cp: 500,
+                char_level: 80,
+            },
+            recipe: RecipeStats {
+                recipe_level: RecipeLevelRanges::ShbLeveling(75),
+                max_durability: 80,
+                max_quality: 10_000,
+                max_progress: 3_000,
+                required_quality: 0,
+                result_quantity: 1,
+                specialist_required: false,
+            },
+            conditions: QARegularConditions::Normal,
+            quality_map: PhantomData,
+        };
+
+    /// A level-capped DT character making a regular recipe at the top of the level range, with
+    /// every action learned.
+    pub(crate) const DAWNTRAIL_SIMULATOR: CraftingSimulator<QARegularConditions, HQMap> =
+        CraftingSimulator {
+            character: CharacterStats {
+                craftsmanship: 4500,
+                control: 4400,
+                max_cp: 600,
+                char_level: 100,
+            },
+            recipe: RecipeStats {
+                recipe_level: RecipeLevelRanges::DtMax(0),
+                max_durability: 80,
+                max_quality: 12_000,
+                max_progress: 6_600,
+                required_quality: 0,
+                result_quantity: 1,
+                specialist_required: false,
+            },
+            conditions: QARegularConditions::Normal,
+            quality_map: PhantomData,
+        };
+}
+
+#[cfg(test)]
+mod test {
+    use rand::distributions::Distribution;
+
+    use super::*;
+    use crate::{
+        actions::{collection::FfxivCraftingActions, quality::BasicTouch, RollOutcome},
+        conditions::QARegularConditions,
+    };
+
+    #[test]
+    fn fixed_rng_rolls() {
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let hasty = FfxivCraftingActions::HastyTouch;
+        let (mut lowest, mut highest) = (FixedRng::lowest(), FixedRng::highest());
+
+        assert!(matches!(
+            hasty.roll(&mut lowest, &state),
+            RollOutcome::Failure(_)
+        ));
+        assert!(matches!(
+            hasty.roll(&mut highest, &state),
+            RollOutcome::Success(_)
+        ));
+
+        let normal = QARegularConditions::Normal;
+        assert_eq!(normal.sample(&mut lowest), QARegularConditions::Good);
+        assert_eq!(normal.sample(&mut highest), QARegularConditions::Normal);
+    }
+
+    #[test]
+    fn shrinks_to_first_violation() {
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let rotation = [BasicTouch; 7];
+
+        let prefix = shrink_rotation(
+            state,
+            &rotation,
+            &FixedRng::highest(),
+            &FixedRng::highest(),
+            |outcome| match outcome {
+                Outcome::InProgress { state, .. } => state.curr_durability > 40,
+                _ => false,
+            },
+        );
+
+        assert_eq!(prefix.map(<[_]>::len), Some(4));
+    }
+
+    #[test]
+    fn no_violation_no_prefix() {
+        let state = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let rotation = [BasicTouch; 3];
+
+        let prefix = shrink_rotation(
+            state,
+            &rotation,
+            &FixedRng::highest(),
+            &FixedRng::highest(),
+            |outcome| matches!(outcome, Outcome::InProgress { .. }),
+        );
+
+        assert!(prefix.is_none());
+    }
+}
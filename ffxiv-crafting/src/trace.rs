@@ -0,0 +1,183 @@
+//! A record of everything that happened over the course of a craft, from
+//! [`CraftingState::run_traced`], so it can be replayed or analyzed after the fact without
+//! re-running the simulation (and re-rolling its randomness). With the `serde` feature a
+//! [`Trace`] can be saved and loaded like a [`CraftingPlan`].
+//!
+//! [`CraftingPlan`]: crate::plan::CraftingPlan
+
+use crate::{
+    actions::StateDelta, conditions::Condition, quality_map::QualityMap, CraftingState, OutcomeRank,
+};
+
+/// One action of a [`Trace`], along with how it turned out.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraceStep<A, C, O> {
+    /// The action that was used.
+    pub action: A,
+
+    /// The delta the action applied to the state, after its success roll.
+    pub delta: StateDelta,
+
+    /// The condition rolled for the next step. If the craft finished on this step, the
+    /// condition doesn't change.
+    pub condition: C,
+
+    /// Which way the craft went after this step.
+    pub outcome: OutcomeRank<O>,
+}
+
+/// Every step of a craft, in order. The trace ends early if the craft completed or failed before
+/// the rotation did.
+///
+/// The states themselves aren't stored, as they borrow their problem definition, but
+/// [`replay`](Trace::replay) reconstructs them exactly from the state the craft started in.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trace<A, C, O> {
+    /// The steps of the craft, in the order they happened.
+    pub steps: Vec<TraceStep<A, C, O>>,
+}
+
+impl<A, C, O> Trace<A, C, O> {
+    /// The number of actions that were performed.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether no actions were performed at all.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Iterates over the steps in the order they happened.
+    pub fn iter(&self) -> std::slice::Iter<'_, TraceStep<A, C, O>> {
+        self.steps.iter()
+    }
+
+    /// Which way the craft went after the last step, or [`None`] if nothing was done.
+    pub fn outcome(&self) -> Option<&OutcomeRank<O>> {
+        self.steps.last().map(|step| &step.outcome)
+    }
+
+    /// Rebuilds every state of the craft from `start`, which must be the state the trace was
+    /// recorded from. The first state is `start` itself, and each one after it is the state
+    /// following the corresponding step, so the state before step `i` is at index `i`.
+    pub fn replay<'a, M>(&self, start: CraftingState<'a, C, M>) -> Vec<CraftingState<'a, C, M>>
+    where
+        C: Condition,
+        M: QualityMap,
+    {
+        let mut states = Vec::with_capacity(self.steps.len() + 1);
+        states.push(start);
+
+        for step in &self.steps {
+            let prev = states[states.len() - 1];
+            states.push(CraftingState {
+                condition: step.condition,
+                ..prev + step.delta
+            });
+        }
+
+        states
+    }
+}
+
+impl<A, C, O> IntoIterator for Trace<A, C, O> {
+    type Item = TraceStep<A, C, O>;
+
+    type IntoIter = std::vec::IntoIter<TraceStep<A, C, O>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.steps.into_iter()
+    }
+}
+
+impl<'t, A, C, O> IntoIterator for &'t Trace<A, C, O> {
+    type Item = &'t TraceStep<A, C, O>;
+
+    type IntoIter = std::slice::Iter<'t, TraceStep<A, C, O>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.steps.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        actions::collection::FfxivCraftingActions,
+        quality_map::HQChance,
+        test_util::{FixedRng, CLASSICAL_SIMULATOR},
+    };
+
+    use FfxivCraftingActions::*;
+
+    #[test]
+    fn replay_matches_simulation() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let rotation = [BasicTouch, HastyTouch, RapidSynthesis, BasicSynthesis];
+
+        // Low rolls fail every action that can fail, and move the condition off of Normal
+        let trace = start.run_traced(rotation, &mut FixedRng::lowest(), &mut FixedRng::lowest());
+        assert_eq!(trace.len(), 4);
+        assert_eq!(
+            trace.iter().map(|step| step.action).collect::<Vec<_>>(),
+            rotation
+        );
+        assert!(trace
+            .iter()
+            .all(|step| step.outcome == OutcomeRank::InProgress));
+
+        let mut state = start;
+        let mut action_rng = FixedRng::lowest();
+        let mut condition_rng = FixedRng::lowest();
+        let states = trace.replay(start);
+        for (i, action) in rotation.into_iter().enumerate() {
+            assert_eq!(states[i], state);
+
+            let outcome = state.act(action, &mut action_rng, &mut condition_rng);
+            state = *outcome.state();
+        }
+        assert_eq!(states[4], state);
+        assert!(trace.iter().any(|step| step.condition != start.condition));
+    }
+
+    #[test]
+    fn stops_when_finished() {
+        let mut start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        start.curr_progress = CLASSICAL_SIMULATOR.recipe.max_progress - 1;
+        start.curr_quality = CLASSICAL_SIMULATOR.recipe.max_quality;
+
+        let trace = start.run_traced(
+            [BasicSynthesis, BasicTouch],
+            &mut FixedRng::highest(),
+            &mut FixedRng::highest(),
+        );
+
+        assert_eq!(trace.len(), 1);
+        assert_eq!(
+            trace.outcome(),
+            Some(&OutcomeRank::Completed(HQChance(100)))
+        );
+        assert_eq!(trace.steps[0].condition, start.condition);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let start = CraftingState::new_simulation(&CLASSICAL_SIMULATOR);
+        let trace = start.run_traced(
+            [Innovation, BasicTouch, BasicSynthesis],
+            &mut FixedRng::highest(),
+            &mut FixedRng::lowest(),
+        );
+
+        let json = serde_json::to_string(&trace).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Trace<_, _, _>>(&json).unwrap(),
+            trace
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Checks for the invariants the [`Bellman`] transformation cycle relies on. Solvers assume any
+//! [`PartialQ`] they're handed follows these, but nothing in the type system can enforce them, so
+//! implementors of custom [`QVal`]s are encouraged to run [`assert_bellman_laws`] (or
+//! [`check_bellman_laws`], to handle the error themselves) over generated values in their own
+//! property tests.
+//!
+//! This is only available with the `test-util` feature, for use in tests.
+
+use std::fmt::Debug;
+
+use crate::prelude::*;
+
+/// Checks that `a`, `b`, and `c` follow the laws that solvers expect of [`Bellman`]
+/// implementations, returning a description of the first law that's broken, prefixed by its name:
+///
+/// - Update: a single [`partial_update`](Bellman::partial_update) of `a` from [`Default`], followed by
+///   [`reweight`](Bellman::reweight), is the same as an [`update`](Bellman::update) from
+///   [`Default`].
+/// - Identity: [`Default`] is an identity for [`partial_update`](Bellman::partial_update), up to reweighting.
+/// - Associativity: [`partial_update`](Bellman::partial_update) is associative up to reweighting, so the order
+///   the successors of a state are visited in (or how they're [merged](PartialQ::merge)) doesn't
+///   matter.
+///
+/// The comparisons are done with [`SemanticEq`], so for float-backed values the generated values
+/// should be exactly representable (e.g. multiples of a power of two) unless the [`QVal`] has some
+/// tolerance built in.
+pub fn check_bellman_laws<Q, P>(a: &P, b: &P, c: &P) -> Result<(), String>
+where
+    Q: QVal + Debug,
+    P: PartialQ<Q> + Default + Debug,
+{
+    let zero = P::default();
+
+    let (partial, update) = (zero.partial_update(a).reweight(), zero.update(a));
+    if !partial.sem_eq(&update) {
+        return Err(format!(
+            "update law broken: reweighting a single partial update of {a:?} gave {partial:?}, but updating gave {update:?}"
+        ));
+    }
+
+    let (padded, plain) = (a.partial_update(&zero).reweight(), a.reweight());
+    if !padded.sem_eq(&plain) {
+        return Err(format!(
+            "identity law broken: partially updating {a:?} with the default changed it from {plain:?} to {padded:?}"
+        ));
+    }
+
+    let left = a.partial_update(b).partial_update(c).reweight();
+    let right = a.partial_update(&b.partial_update(c)).reweight();
+    if !left.sem_eq(&right) {
+        return Err(format!(
+            "associativity law broken: partial updates of {a:?}, {b:?}, and {c:?} aren't associative: {left:?} != {right:?}"
+        ));
+    }
+
+    Ok(())
+}
+
+/// [`check_bellman_laws`], but panics with the name of the broken law and the [`QVal`] that broke
+/// it. Since proptest catches panics, this can be used directly in its tests.
+///
+/// # Panics
+///
+/// If any of the laws are broken by `a`, `b`, and `c`.
+pub fn assert_bellman_laws<Q, P>(a: &P, b: &P, c: &P)
+where
+    Q: QVal + Debug,
+    P: PartialQ<Q> + Default + Debug,
+{
+    if let Err(err) = check_bellman_laws::<Q, P>(a, b, c) {
+        panic!(
+            "{} breaks the Bellman laws, {err}",
+            std::any::type_name::<Q>()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tracks the last value it was updated with, so it doesn't build up any expectation.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+    struct LastQ(f64);
+
+    impl QVal for LastQ {}
+
+    impl Bellman for LastQ {
+        fn update(&self, other: &Self) -> Self {
+            *other
+        }
+
+        fn partial_update(&self, other: &Self) -> Self {
+            *other
+        }
+
+        fn reweight(&self) -> Self {
+            *self
+        }
+    }
+
+    impl SemanticOrd for LastQ {
+        fn sem_cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.partial_cmp(other).unwrap()
+        }
+    }
+
+    impl SemanticEq for LastQ {
+        fn sem_eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "identity law broken")]
+    fn names_broken_law() {
+        assert_bellman_laws::<LastQ, _>(&LastQ(1.), &LastQ(2.), &LastQ(3.));
+    }
+}
@@ -30,18 +30,35 @@
     a very basic feel for how the API and rewards work.  
 "#
 )]
+#![cfg_attr(
+    not(feature = "derive"),
+    doc = "The `derive` feature adds derive macros for [`SemanticEq`] and [`SemanticOrd`]."
+)]
+#![cfg_attr(
+    feature = "derive",
+    doc = "[`SemanticEq`] and [`SemanticOrd`] can also be derived for simple structs and enums."
+)]
 //!
 //! In the future this should link to a more thorough overview of the concept.
 
 #![warn(missing_docs)]
 
+// Lets the derive macros refer to `::structured_rewards` whether they're used in this
+// crate or downstream.
+#[cfg(feature = "derive")]
+extern crate self as structured_rewards;
+
 pub mod sem;
 
+#[cfg(any(test, feature = "test-util"))]
+pub mod laws;
 pub mod rewards;
 #[cfg(feature = "solvers")]
 pub mod simple_solvers;
 
 pub use sem::{Sem, SemanticEq, SemanticOrd};
+#[cfg(feature = "derive")]
+pub use structured_rewards_derive::{SemanticEq, SemanticOrd};
 
 /// Contains the basic traits required to implement a generalized algorithm on structured rewards.
 /// This merely provides a structured framework for defining your types. While it is recommended
@@ -134,6 +151,19 @@ pub trait PartialQ<Q>: Bellman<Self, Self, Q> + Sized
 where
     Q: QVal,
 {
+    /// Combines two partial results, each built up from a disjoint set of successors, into the
+    /// partial result for all of them. This allows the `tree` over successors to be split up
+    /// (e.g. across threads) and folded back together afterwards.
+    ///
+    /// For this to give the same result regardless of how the successors were split, `merge`
+    /// **must** be associative, and [`Default::default`] (if implemented) should be its identity.
+    /// This is implemented via [`partial_update`](Bellman::partial_update), which is valid as long
+    /// as [`partial_update`](Bellman::partial_update) itself is associative, as it is for sums
+    /// (e.g. [`SimpleQ`](rewards::SimpleQ)) and for collecting outcomes (e.g.
+    /// [`DistributionQ`](rewards::DistributionQ)).
+    fn merge(&self, other: &Self) -> Self {
+        self.partial_update(other)
+    }
 }
 
 impl<Q, T> PartialQ<Q> for T
@@ -3,6 +3,8 @@
 //! At the moment these just emulate traditional RL as a proof of concept that
 //! traditional RL rewards can be reduced to Structured Rewards.
 
+use std::hash::{Hash, Hasher};
+
 use crate::prelude::*;
 
 /// A very simple numeric [Q-value](QVal) backed by an `f64`. This is essentially a
@@ -13,7 +15,8 @@ use crate::prelude::*;
 /// despite being applied solely to the Q-value, is because the [`TransitionReward`] holds the [`Compose`]
 /// implementation, and the choice of discounting is up to that.
 ///
-/// Currently, no matching [`TransitionReward`] implementation exists for non-exact domains.
+/// For learning solvers such as [`q_learning`](crate::simple_solvers::q_learning), use a
+/// [`LearnedQ`] instead.
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
 pub struct SimpleQ(pub f64);
 
@@ -83,3 +86,543 @@ impl<const D: i64> Compose<SimpleQ, SimpleQ> for DiscountedReward<D> {
         SimpleQ(self.0 * (self.1 as f64 + (D as f64 / 100.) * other.0))
     }
 }
+
+/// A numeric [Q-value](QVal) like [`SimpleQ`], but learned from observations one at a time, as in
+/// Q-learning, rather than computed from exact transition probabilities.
+///
+/// Since structured Q-values can't generally be scaled by a learning rate, the learning rate is
+/// split across the transformation cycle instead: an [`ObservedReward`] scales each sample down by
+/// the learning rate when it's [`compose`](Compose::compose)d, and [`update`](Bellman::update)
+/// scales the current estimate down by the rest before adding the sample, giving the usual
+/// `Q(s,a) = (1 - α) Q(s,a) + α (r + γ max_a' Q(s',a'))`.
+///
+/// As with [`DiscountedReward`], generic constants can't be floats, so the learning rate `A` is
+/// divided by `100` to get `α`. Since [`partial_update`](Bellman::partial_update) is a plain sum,
+/// this can still be used in exact domains, though there's no point.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct LearnedQ<const A: i64>(pub f64);
+
+impl<const A: i64> QVal for LearnedQ<A> {}
+
+impl<const A: i64> Bellman for LearnedQ<A> {
+    fn update(&self, other: &Self) -> Self {
+        LearnedQ((1. - A as f64 / 100.) * self.0 + other.0)
+    }
+
+    fn partial_update(&self, other: &Self) -> Self {
+        LearnedQ(self.0 + other.0)
+    }
+
+    fn reweight(&self) -> Self {
+        *self
+    }
+}
+
+impl<const A: i64> SemanticOrd for LearnedQ<A> {
+    fn sem_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.partial_cmp(other).unwrap()
+    }
+}
+
+impl<const A: i64> SemanticEq for LearnedQ<A> {
+    fn sem_eq(&self, other: &Self) -> bool {
+        debug_assert!(!self.0.is_nan());
+        debug_assert!(!other.0.is_nan());
+        self.0 == other.0
+    }
+}
+
+impl<const A: i64> Scalar for LearnedQ<A> {
+    fn scalar(&self) -> f64 {
+        self.0
+    }
+}
+
+/// A [`TransitionReward`] for learning domains, where it's a single observed reward rather than
+/// an expectation, for use with [`LearnedQ`]. Its [`compose`](Compose::compose) applies the
+/// discount factor `D` and the learning rate `A` (both divided by `100`), the latter of which must
+/// match the [`LearnedQ`].
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default)]
+pub struct ObservedReward<const A: i64, const D: i64>(pub i64);
+
+impl<const A: i64, const D: i64> Compose<LearnedQ<A>, LearnedQ<A>> for ObservedReward<A, D> {
+    fn compose(&self, other: &LearnedQ<A>) -> LearnedQ<A> {
+        LearnedQ((A as f64 / 100.) * (self.0 as f64 + (D as f64 / 100.) * other.0))
+    }
+}
+
+/// A [Q-value](QVal) counting how many times some event is expected to happen, such as how many
+/// times a policy takes advantage of a lucky break in its domain. Like [`SimpleQ`] this is a
+/// [`TotalQ`], and more events are better.
+///
+/// This is most useful as part of a larger structured Q-value, ranking policies that are otherwise
+/// tied by how often they exploit the domain. It needs to be paired with a [`CountReward`].
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct CountQ(pub f64);
+
+impl QVal for CountQ {}
+
+impl Bellman for CountQ {
+    fn update(&self, other: &Self) -> Self {
+        CountQ(self.0 + other.0)
+    }
+
+    fn partial_update(&self, other: &Self) -> Self {
+        self.update(other)
+    }
+
+    fn reweight(&self) -> Self {
+        *self
+    }
+}
+
+impl SemanticOrd for CountQ {
+    fn sem_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.partial_cmp(other).unwrap()
+    }
+}
+
+impl SemanticEq for CountQ {
+    fn sem_eq(&self, other: &Self) -> bool {
+        debug_assert!(!self.0.is_nan());
+        debug_assert!(!other.0.is_nan());
+        self.0 == other.0
+    }
+}
+
+/// A [`TransitionReward`] for exact domains counting the events that happened during a transition,
+/// for use with [`CountQ`].
+///
+/// The first element is the exact transition probability `T(s,a,s')` of the
+/// (state, action, next-state) triad that emitted this reward, in the range
+/// [0.0, 1.0], and the second is the number of events.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default)]
+pub struct CountReward(pub f64, pub u32);
+
+impl Compose<CountQ, CountQ> for CountReward {
+    fn compose(&self, other: &CountQ) -> CountQ {
+        CountQ(self.0 * (self.1 as f64 + other.0))
+    }
+}
+
+/// A [Q-value](QVal) that keeps the whole distribution of total rewards a policy can end up with,
+/// rather than just its expectation like [`SimpleQ`]. Each element is an outcome and its
+/// probability. Policies are still ranked (and tied) by their expectation, but the distribution
+/// is there to inspect afterwards, e.g. to see how likely a policy is to reach some threshold.
+///
+/// This needs to be paired with a [`DistributionReward`]. [`partial_update`](Bellman::partial_update)
+/// collects the outcomes of each successor, and [`reweight`](Bellman::reweight) sorts them and
+/// combines equal outcomes. The [`Default`] (empty) distribution is the identity of
+/// [`partial_update`](Bellman::partial_update), and is treated as a certain reward of `0` when
+/// composed, as it is for terminal states.
+///
+/// Equality and hashing are done on the [`canonical`](DistributionQ::canonical) form, so
+/// distributions that only differ in the order they were built up in, or by float error in their
+/// probabilities, are the same when memoized.
+#[derive(Clone, Debug, Default)]
+pub struct DistributionQ(pub Vec<(i64, f64)>);
+
+impl DistributionQ {
+    /// How finely probabilities are rounded by [`canonical`](DistributionQ::canonical), i.e. to
+    /// 9 decimal places.
+    const PRECISION: f64 = 1e9;
+
+    /// The expected reward of this distribution.
+    pub fn expectation(&self) -> f64 {
+        self.0.iter().map(|(outcome, p)| *outcome as f64 * p).sum()
+    }
+
+    /// This distribution with its outcomes sorted and combined as by [`reweight`](Bellman::reweight),
+    /// its probabilities rounded to 9 decimal places, and any outcomes that are left impossible
+    /// dropped.
+    pub fn canonical(&self) -> Self {
+        DistributionQ(
+            self.rounded()
+                .map(|(outcome, p)| (outcome, p as f64 / Self::PRECISION))
+                .collect(),
+        )
+    }
+
+    /// The canonical outcomes, with probabilities in units of the precision so they can be
+    /// compared and hashed exactly.
+    fn rounded(&self) -> impl Iterator<Item = (i64, i64)> {
+        self.reweight()
+            .0
+            .into_iter()
+            .map(|(outcome, p)| (outcome, (p * Self::PRECISION).round() as i64))
+            .filter(|(_, p)| *p != 0)
+    }
+}
+
+impl PartialEq for DistributionQ {
+    fn eq(&self, other: &Self) -> bool {
+        self.rounded().eq(other.rounded())
+    }
+}
+
+impl Eq for DistributionQ {}
+
+impl Hash for DistributionQ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for outcome in self.rounded() {
+            outcome.hash(state);
+        }
+    }
+}
+
+impl QVal for DistributionQ {}
+
+impl Bellman for DistributionQ {
+    fn update(&self, other: &Self) -> Self {
+        self.partial_update(other).reweight()
+    }
+
+    fn partial_update(&self, other: &Self) -> Self {
+        DistributionQ(self.0.iter().chain(&other.0).copied().collect())
+    }
+
+    fn reweight(&self) -> Self {
+        // The sort is stable, so equal outcomes are always summed in the order they were collected
+        let mut sorted = self.0.clone();
+        sorted.sort_by_key(|(outcome, _)| *outcome);
+
+        let mut combined: Vec<(i64, f64)> = Vec::with_capacity(sorted.len());
+        for (outcome, p) in sorted {
+            match combined.last_mut() {
+                Some((last, total)) if *last == outcome => *total += p,
+                _ => combined.push((outcome, p)),
+            }
+        }
+
+        DistributionQ(combined)
+    }
+}
+
+impl SemanticOrd for DistributionQ {
+    fn sem_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.expectation()
+            .partial_cmp(&other.expectation())
+            .unwrap()
+    }
+}
+
+impl SemanticEq for DistributionQ {
+    fn sem_eq(&self, other: &Self) -> bool {
+        let (ours, theirs) = (self.expectation(), other.expectation());
+        debug_assert!(!ours.is_nan());
+        debug_assert!(!theirs.is_nan());
+        ours == theirs
+    }
+}
+
+impl Scalar for DistributionQ {
+    fn scalar(&self) -> f64 {
+        self.expectation()
+    }
+}
+
+/// A [`TransitionReward`] for exact domains for use with [`DistributionQ`], shifting every outcome
+/// of the next state by the reward.
+///
+/// The first element is the exact transition probability `T(s,a,s')` of the
+/// (state, action, next-state) triad that emitted this reward, in the range
+/// [0.0, 1.0], and the second is the reward itself.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default)]
+pub struct DistributionReward(pub f64, pub i64);
+
+impl Compose<DistributionQ, DistributionQ> for DistributionReward {
+    fn compose(&self, other: &DistributionQ) -> DistributionQ {
+        if other.0.is_empty() {
+            return DistributionQ(vec![(self.1, self.0)]);
+        }
+
+        DistributionQ(
+            other
+                .0
+                .iter()
+                .map(|(outcome, p)| (outcome + self.1, p * self.0))
+                .collect(),
+        )
+    }
+}
+
+/// A [Q-value](QVal) that can be reduced to a single number, so it can be traded off against
+/// others in a [`Weighted`] Q-value.
+pub trait Scalar {
+    /// The value as a number, where higher is better.
+    fn scalar(&self) -> f64;
+}
+
+impl Scalar for SimpleQ {
+    fn scalar(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Scalar for CountQ {
+    fn scalar(&self) -> f64 {
+        self.0
+    }
+}
+
+/// The weights of the two objectives in a [`Weighted`] Q-value, in the same order. Negative
+/// weights turn an objective into a cost.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Weights(pub f64, pub f64);
+
+/// A [Q-value](QVal) made of two others, ranked by a weighted sum of their [`Scalar`] values,
+/// e.g. `0.7 * quality - 0.3 * steps`. This trades the objectives off against each other, unlike a
+/// lexicographic Q-value which only looks at the second when the first is tied.
+///
+/// The [`Weights`] are set at runtime by the [`WeightedReward`] this needs to be paired with, so
+/// they're [`None`] for the [`Default`] value until it's been composed with one. Updates keep
+/// whichever weights are set, and comparisons use them for both sides.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Weighted<A, B>(pub A, pub B, pub Option<Weights>);
+
+impl<A, B> Weighted<A, B>
+where
+    A: Scalar,
+    B: Scalar,
+{
+    /// The weighted sum this is ranked by, or 0 if it has no weights yet.
+    pub fn scalar(&self) -> f64 {
+        self.scalar_with(self.2.unwrap_or_default())
+    }
+
+    fn scalar_with(&self, Weights(wa, wb): Weights) -> f64 {
+        wa * self.0.scalar() + wb * self.1.scalar()
+    }
+
+    /// The weighted sums of `self` and `other`, using whichever weights are set.
+    fn scalars(&self, other: &Self) -> (f64, f64) {
+        let weights = self.2.or(other.2).unwrap_or_default();
+        (self.scalar_with(weights), other.scalar_with(weights))
+    }
+}
+
+impl<A, B> QVal for Weighted<A, B>
+where
+    A: QVal + Scalar,
+    B: QVal + Scalar,
+{
+}
+
+impl<A, B> Bellman for Weighted<A, B>
+where
+    A: Bellman,
+    B: Bellman,
+{
+    fn update(&self, other: &Self) -> Self {
+        Self(
+            self.0.update(&other.0),
+            self.1.update(&other.1),
+            self.2.or(other.2),
+        )
+    }
+
+    fn partial_update(&self, other: &Self) -> Self {
+        Self(
+            self.0.partial_update(&other.0),
+            self.1.partial_update(&other.1),
+            self.2.or(other.2),
+        )
+    }
+
+    fn reweight(&self) -> Self {
+        Self(self.0.reweight(), self.1.reweight(), self.2)
+    }
+}
+
+impl<A, B> SemanticOrd for Weighted<A, B>
+where
+    A: Scalar,
+    B: Scalar,
+{
+    fn sem_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let (me, other) = self.scalars(other);
+        me.partial_cmp(&other).unwrap()
+    }
+}
+
+impl<A, B> SemanticEq for Weighted<A, B>
+where
+    A: Scalar,
+    B: Scalar,
+{
+    fn sem_eq(&self, other: &Self) -> bool {
+        let (me, other) = self.scalars(other);
+        debug_assert!(!me.is_nan());
+        debug_assert!(!other.is_nan());
+        me == other
+    }
+}
+
+/// The [`TransitionReward`] paired with [`Weighted`], which is the rewards for each of its
+/// Q-values, along with the weights to rank them by.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default)]
+pub struct WeightedReward<RA, RB>(pub RA, pub RB, pub Weights);
+
+impl<A, B, RA, RB> Compose<Weighted<A, B>, Weighted<A, B>> for WeightedReward<RA, RB>
+where
+    RA: Compose<A, A>,
+    RB: Compose<B, B>,
+{
+    fn compose(&self, other: &Weighted<A, B>) -> Weighted<A, B> {
+        Weighted(
+            self.0.compose(&other.0),
+            self.1.compose(&other.1),
+            Some(self.2),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    use crate::laws::assert_bellman_laws;
+
+    /// Floats that are exactly representable, along with any sum of a few of them, so rounding
+    /// can't make a difference.
+    fn exact() -> impl Strategy<Value = f64> {
+        (-1_000_000i32..1_000_000).prop_map(|n| n as f64 / 64.)
+    }
+
+    proptest! {
+        #[test]
+        fn shipped_qvals_follow_bellman_laws(a in exact(), b in exact(), c in exact()) {
+            assert_bellman_laws::<SimpleQ, _>(&SimpleQ(a), &SimpleQ(b), &SimpleQ(c));
+            assert_bellman_laws::<CountQ, _>(&CountQ(a), &CountQ(b), &CountQ(c));
+            assert_bellman_laws::<LearnedQ<50>, _>(&LearnedQ(a), &LearnedQ(b), &LearnedQ(c));
+            let weights = Some(Weights(0.5, -0.25));
+            assert_bellman_laws::<Weighted<SimpleQ, CountQ>, _>(
+                &Weighted(SimpleQ(a), CountQ(b), weights),
+                &Weighted(SimpleQ(b), CountQ(c), weights),
+                &Weighted(SimpleQ(c), CountQ(a), weights),
+            );
+            assert_bellman_laws::<DistributionQ, _>(
+                &DistributionQ(vec![(a as i64, 0.5), (b as i64, 0.25)]),
+                &DistributionQ(vec![(b as i64, 0.125)]),
+                &DistributionQ(vec![(c as i64, 0.125), (a as i64, 0.)]),
+            );
+        }
+    }
+
+    #[test]
+    fn simple_q_merge_is_associative() {
+        // Exactly representable, so rounding can't make a difference
+        let (a, b, c) = (SimpleQ(0.5), SimpleQ(0.25), SimpleQ(-2.));
+
+        let left = a.merge(&b).merge(&c);
+        let right = a.merge(&b.merge(&c));
+        assert!(left.sem_eq(&right));
+
+        assert!(a.merge(&SimpleQ::default()).sem_eq(&a));
+    }
+
+    #[test]
+    fn distribution_q_merge_is_associative() {
+        let a = DistributionQ(vec![(10, 0.25), (0, 0.125)]);
+        let b = DistributionQ(vec![(5, 0.25)]);
+        let c = DistributionQ(vec![(10, 0.125), (5, 0.25)]);
+
+        // Compared structurally, not just by expectation
+        let left = a.merge(&b).merge(&c).reweight();
+        let right = a.merge(&b.merge(&c)).reweight();
+        assert_eq!(left, right);
+        assert_eq!(left, DistributionQ(vec![(0, 0.125), (5, 0.5), (10, 0.375)]));
+
+        assert_eq!(a.merge(&DistributionQ::default()), a);
+    }
+
+    #[test]
+    fn equal_distributions_are_equal() {
+        use std::collections::HashSet;
+
+        // 0.1 + 0.2 isn't exactly 0.3, and the impossible outcome shouldn't count either
+        let summed = DistributionQ(vec![(1, 0.1), (2, 0.7), (1, 0.2), (5, 0.)]);
+        let direct = DistributionQ(vec![(2, 0.7), (1, 0.3)]);
+        assert_ne!(summed.reweight().0, direct.reweight().0);
+
+        assert_eq!(summed, direct);
+        assert_eq!(summed.canonical().0, direct.canonical().0);
+        assert_eq!(HashSet::from([summed, direct]).len(), 1);
+    }
+
+    #[test]
+    fn distribution_reward_shifts_outcomes() {
+        // A coin flip for 10 after a state that always gives 1 more
+        let future = DistributionReward(1., 1).compose(&DistributionQ::default());
+        let heads = DistributionReward(0.5, 10).compose(&future);
+        let tails = DistributionReward(0.5, 0).compose(&future);
+
+        let q = DistributionQ::default().update(&heads.partial_update(&tails));
+        assert_eq!(q, DistributionQ(vec![(1, 0.5), (11, 0.5)]));
+        assert!(q.sem_eq(&DistributionQ(vec![(6, 1.)])));
+    }
+
+    #[test]
+    fn learned_q_blends_observations() {
+        // Observing exactly the current estimate, 1 + 0.5 * 6, changes nothing
+        let current = LearnedQ::<50>(4.);
+        let sample = ObservedReward::<50, 50>(1).compose(&LearnedQ(6.));
+        assert!(current.update(&sample).sem_eq(&LearnedQ(4.)));
+
+        // Whereas 11 + 0.5 * 6 moves it halfway there
+        let sample = ObservedReward::<50, 50>(11).compose(&LearnedQ(6.));
+        assert!(current.update(&sample).sem_eq(&LearnedQ(9.)));
+
+        // The first observation is taken as-is, scaled by the learning rate
+        assert!(LearnedQ::default().update(&sample).sem_eq(&LearnedQ(7.)));
+    }
+
+    #[test]
+    fn count_reward_weights_by_probability() {
+        let future = CountQ(2.);
+        let lucky = CountReward(0.25, 1).compose(&future);
+        let unlucky = CountReward(0.75, 0).compose(&future);
+
+        let expected = lucky.partial_update(&unlucky).reweight();
+        assert!(expected.sem_eq(&CountQ(2.25)));
+        assert!(expected.sem() > future.sem());
+    }
+
+    #[test]
+    fn weighted_trades_off_objectives() {
+        // Quality, and the number of steps taken to get it
+        type Tradeoff = Weighted<SimpleQ, CountQ>;
+
+        let reward = |quality, steps, weights| {
+            WeightedReward(
+                NoDiscountReward(1., quality),
+                CountReward(1., steps),
+                weights,
+            )
+        };
+        let even = Weights(0.5, -0.5);
+        let slow = reward(100, 20, even).compose(&Tradeoff::default());
+        let fast = reward(90, 5, even).compose(&Tradeoff::default());
+
+        // A lexicographic ranking only cares about steps once quality is tied
+        let lexicographic = |q: &Tradeoff| (q.0.sem(), q.1.sem());
+        assert!(lexicographic(&slow) > lexicographic(&fast));
+
+        // Whereas here 10 quality isn't worth 15 more steps
+        assert!(fast.sem() > slow.sem());
+        assert_eq!(slow.scalar(), 40.);
+
+        // But it is if steps hardly matter, without needing a different type
+        let quality_first = Weights(1., -0.1);
+        let slow = reward(100, 20, quality_first).compose(&Tradeoff::default());
+        let fast = reward(90, 5, quality_first).compose(&Tradeoff::default());
+        assert!(slow.sem() > fast.sem());
+
+        // Values that haven't been weighted yet take on the weights they're combined with
+        let total = Tradeoff::default().update(&slow);
+        assert_eq!(total.2, Some(quality_first));
+        assert!(total.sem_eq(&slow));
+        assert!(Tradeoff::default().sem() < slow.sem());
+    }
+}
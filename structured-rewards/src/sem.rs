@@ -65,7 +65,7 @@ where
     T: SemanticOrd + SemanticEq,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.sem_cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
@@ -0,0 +1,69 @@
+//! Adapts domains where every action has exactly one outcome, so they don't each need to wrap
+//! their successor in an iterator to implement [`State`].
+
+use std::hash::Hash;
+
+use crate::{prelude::*, simple_solvers::State};
+
+/// A state in a domain where taking an action always leads to the same next state, such as a
+/// craft where the condition never changes and nothing can fail. Wrap it in [`Deterministic`] to
+/// use it as a [`State`].
+///
+/// Since there's nothing to be uncertain about, `R` is free to be either a reward with a
+/// transition probability of `1.0` (for value iteration), or a plain observation (for a learning
+/// solver), both follow the single-successor contract [`State`] describes for learning solvers.
+pub trait DeterministicState<R>: Sized {
+    /// The set of all valid actions for this domain.
+    type Action;
+    /// An iterator over [`Self::Action`](DeterministicState::Action), listing all
+    /// the ones valid in the current state.
+    type ActionIter: Iterator<Item = Self::Action>;
+    /// See [`State::Key`].
+    type Key: Eq + Hash;
+
+    /// The state `action` leads to, and the reward for getting there, or [`None`] if this
+    /// state is terminal.
+    fn step(&self, action: Self::Action) -> Option<(Self, R)>;
+
+    /// Yields an iterator over all valid actions for the current state.
+    fn actions(&self) -> Self::ActionIter;
+
+    /// Computes the [`Key`](DeterministicState::Key) for this state.
+    fn key(&self) -> Self::Key;
+}
+
+/// Wraps a [`DeterministicState`] as a [`State`] whose successors are always just the one
+/// [`step`](DeterministicState::step) leads to.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Deterministic<S>(pub S);
+
+impl<S, Q, R, P> State<Q, R, P> for Deterministic<S>
+where
+    S: DeterministicState<R>,
+    Q: QVal,
+    R: TransitionReward<Q, P>,
+    P: PartialQ<Q>,
+{
+    type SuccRewardIter = std::option::IntoIter<(Self, R)>;
+
+    type Action = S::Action;
+
+    type ActionIter = S::ActionIter;
+
+    type Key = S::Key;
+
+    fn successors(&self, action: Self::Action) -> Self::SuccRewardIter {
+        self.0
+            .step(action)
+            .map(|(succ, reward)| (Deterministic(succ), reward))
+            .into_iter()
+    }
+
+    fn actions(&self) -> Self::ActionIter {
+        self.0.actions()
+    }
+
+    fn key(&self) -> Self::Key {
+        self.0.key()
+    }
+}
@@ -1,37 +1,48 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, hash::Hash};
 
 use crate::{
-    rewards::SimpleQ,
-    simple_solvers::{
-        environments::{traits::SimpleReward, GridAction, GridState},
-        ConvergenceMeasure,
-    },
+    rewards::{CountQ, SimpleQ},
+    simple_solvers::{environments::GridAction, ConvergenceMeasure},
 };
 
 pub struct StandardConvergenceMeasure;
 
-impl<'a, R, const N: usize> ConvergenceMeasure<GridState<'a, R, N>, GridAction, SimpleQ>
-    for StandardConvergenceMeasure
-where
-    R: SimpleReward,
-{
+impl<K: Eq + Hash> ConvergenceMeasure<K, GridAction, SimpleQ> for StandardConvergenceMeasure {
     fn converges(
         &mut self,
-        old: &HashMap<GridState<'a, R, N>, HashMap<GridAction, SimpleQ>>,
-        curr: &HashMap<GridState<'a, R, N>, HashMap<GridAction, SimpleQ>>,
+        old: &HashMap<K, HashMap<GridAction, SimpleQ>>,
+        curr: &HashMap<K, HashMap<GridAction, SimpleQ>>,
     ) -> bool {
-        for (state, qs) in curr.iter() {
-            for (action, new_q) in qs.iter() {
-                if let Some(old_q) = old.get(state).and_then(|v| v.get(action)) {
-                    if (old_q.0 - new_q.0).abs() > 1e-8 {
-                        return false;
-                    }
-                } else {
+        within_delta(old, curr, |q| q.0)
+    }
+}
+
+impl<K: Eq + Hash> ConvergenceMeasure<K, GridAction, CountQ> for StandardConvergenceMeasure {
+    fn converges(
+        &mut self,
+        old: &HashMap<K, HashMap<GridAction, CountQ>>,
+        curr: &HashMap<K, HashMap<GridAction, CountQ>>,
+    ) -> bool {
+        within_delta(old, curr, |q| q.0)
+    }
+}
+
+fn within_delta<K: Eq + Hash, Q>(
+    old: &HashMap<K, HashMap<GridAction, Q>>,
+    curr: &HashMap<K, HashMap<GridAction, Q>>,
+    value: impl Fn(&Q) -> f64,
+) -> bool {
+    for (state, qs) in curr.iter() {
+        for (action, new_q) in qs.iter() {
+            if let Some(old_q) = old.get(state).and_then(|v| v.get(action)) {
+                if (value(old_q) - value(new_q)).abs() > 1e-8 {
                     return false;
                 }
+            } else {
+                return false;
             }
         }
-
-        true
     }
+
+    true
 }
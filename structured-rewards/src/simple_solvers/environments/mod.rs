@@ -6,28 +6,44 @@ use std::{
 
 use derivative::Derivative;
 
-use crate::{rewards::SimpleQ, simple_solvers::State};
+use crate::{prelude::*, simple_solvers::State};
 
 pub mod actions;
 pub mod convergence;
 pub mod traits;
+pub mod visualize;
 
 #[cfg(test)]
 mod test;
 
 pub use actions::*;
-use traits::SimpleReward;
+use traits::CellReward;
 
+/// A square grid whose cells each hold a payload `C` (the value of moving onto them, turned into a
+/// reward via [`CellReward`]), a [`RandomTransition`], and whether they're terminal.
+///
+/// The payload is a scalar reward by default, but can be anything a [`CellReward`] can be built
+/// from, such as several values for a structured [`QVal`].
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub struct GridWorld<const N: usize> {
-    grid: [[(i64, RandomTransition, bool); N]; N],
+pub struct GridWorld<const N: usize, C = i64> {
+    grid: [[(C, RandomTransition, bool); N]; N],
+}
+
+impl<const N: usize, C> GridWorld<N, C>
+where
+    C: Copy + Default,
+{
+    /// A grid where every cell holds the default payload, and nothing is random or terminal.
+    pub fn empty() -> Self {
+        GridWorld {
+            grid: [[Default::default(); N]; N],
+        }
+    }
 }
 
 impl<const N: usize> Default for GridWorld<N> {
     fn default() -> Self {
-        let mut me = GridWorld {
-            grid: [[Default::default(); N]; N],
-        };
+        let mut me = GridWorld::empty();
         me[(0, 0)] = (15, RandomTransition::None, true);
         me[(0, 1)] = (
             0,
@@ -41,15 +57,15 @@ impl<const N: usize> Default for GridWorld<N> {
     }
 }
 
-impl<const N: usize> Index<(usize, usize)> for GridWorld<N> {
-    type Output = (i64, RandomTransition, bool);
+impl<const N: usize, C> Index<(usize, usize)> for GridWorld<N, C> {
+    type Output = (C, RandomTransition, bool);
 
     fn index(&self, index: (usize, usize)) -> &Self::Output {
         &self.grid[index.1][index.0]
     }
 }
 
-impl<const N: usize> IndexMut<(usize, usize)> for GridWorld<N> {
+impl<const N: usize, C> IndexMut<(usize, usize)> for GridWorld<N, C> {
     fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
         &mut self.grid[index.1][index.0]
     }
@@ -57,22 +73,19 @@ impl<const N: usize> IndexMut<(usize, usize)> for GridWorld<N> {
 
 #[derive(Derivative, Copy, Clone)]
 #[derivative(Hash, PartialEq, Eq, Debug)]
-pub struct GridState<'a, R, const N: usize>
-where
-    R: SimpleReward,
-{
+pub struct GridState<'a, R, const N: usize, C = i64> {
     #[derivative(Hash = "ignore", PartialEq = "ignore", Debug = "ignore")]
-    pub grid: &'a GridWorld<N>,
+    pub grid: &'a GridWorld<N, C>,
     #[derivative(Hash = "ignore", PartialEq = "ignore", Debug = "ignore")]
     pd: PhantomData<R>,
     pub curr_square: (usize, usize),
 }
 
-impl<'a, R, const N: usize> GridState<'a, R, N>
+impl<'a, R, const N: usize, C> GridState<'a, R, N, C>
 where
-    R: SimpleReward,
+    C: Copy,
 {
-    pub fn from_grid(grid: &'a GridWorld<N>, square: (usize, usize)) -> Self {
+    pub fn from_grid(grid: &'a GridWorld<N, C>, square: (usize, usize)) -> Self {
         Self {
             grid,
             curr_square: square,
@@ -89,24 +102,31 @@ where
         }
     }
 
-    fn succ_reward(&self, next_square: (usize, usize), prob: f64) -> (Self, R) {
+    fn succ_reward<Q>(&self, next_square: (usize, usize), prob: f64) -> (Self, R)
+    where
+        R: CellReward<C, Q>,
+    {
         let next_state = self.succ(next_square);
-        let reward = R::from_prob_val(prob, self.grid[next_square].0);
+        let reward = R::from_prob_cell(prob, self.grid[next_square].0);
 
         (next_state, reward)
     }
 }
 
-impl<'a, R, const N: usize> State<SimpleQ, R, SimpleQ> for GridState<'a, R, N>
+impl<'a, R, Q, const N: usize, C> State<Q, R, Q> for GridState<'a, R, N, C>
 where
-    R: SimpleReward,
+    R: CellReward<C, Q>,
+    Q: TotalQ,
+    C: Copy,
 {
-    type SuccRewardIter = GridSuccessors<'a, R, N>;
+    type SuccRewardIter = GridSuccessors<'a, R, N, C>;
 
     type Action = GridAction;
 
     type ActionIter = AvailableActions;
 
+    type Key = (usize, usize);
+
     fn successors(&self, action: Self::Action) -> Self::SuccRewardIter {
         let curr = self.grid.borrow()[self.curr_square];
         match (curr.1, curr.2, action.next_coords(&self.curr_square, N)) {
@@ -131,34 +151,33 @@ where
             AvailableActions::Empty
         }
     }
+
+    #[inline]
+    fn key(&self) -> Self::Key {
+        self.curr_square
+    }
 }
 
-#[derive(Default)]
-pub struct GridSuccessors<'a, R, const N: usize>
-where
-    R: SimpleReward,
-{
-    first: Option<(GridState<'a, R, N>, R)>,
-    second: Option<(GridState<'a, R, N>, R)>,
+pub struct GridSuccessors<'a, R, const N: usize, C = i64> {
+    first: Option<(GridState<'a, R, N, C>, R)>,
+    second: Option<(GridState<'a, R, N, C>, R)>,
 }
 
-impl<'a, R, const N: usize> GridSuccessors<'a, R, N>
-where
-    R: SimpleReward,
-{
+impl<'a, R, const N: usize, C> GridSuccessors<'a, R, N, C> {
     fn empty() -> Self {
         Self {
-            ..Default::default()
+            first: None,
+            second: None,
         }
     }
-    fn once(succ: (GridState<'a, R, N>, R)) -> Self {
+    fn once(succ: (GridState<'a, R, N, C>, R)) -> Self {
         Self {
             first: Some(succ),
             second: None,
         }
     }
 
-    fn twice(succ: (GridState<'a, R, N>, R), succ2: (GridState<'a, R, N>, R)) -> Self {
+    fn twice(succ: (GridState<'a, R, N, C>, R), succ2: (GridState<'a, R, N, C>, R)) -> Self {
         Self {
             first: Some(succ),
             second: Some(succ2),
@@ -166,11 +185,8 @@ where
     }
 }
 
-impl<'a, R, const N: usize> Iterator for GridSuccessors<'a, R, N>
-where
-    R: SimpleReward,
-{
-    type Item = (GridState<'a, R, N>, R);
+impl<'a, R, const N: usize, C> Iterator for GridSuccessors<'a, R, N, C> {
+    type Item = (GridState<'a, R, N, C>, R);
 
     fn next(&mut self) -> Option<Self::Item> {
         self.first.take().or_else(|| self.second.take())
@@ -1,11 +1,14 @@
 use std::collections::HashMap;
 
+use rand::{rngs::StdRng, SeedableRng};
 use strum::IntoEnumIterator;
 
 use crate::rewards::*;
 use crate::simple_solvers::{
+    bounded_solver,
     environments::{convergence::StandardConvergenceMeasure, *},
-    simple_solver, State,
+    q_learning, simple_solver, ConvergenceMeasure, Deterministic, DeterministicState,
+    EpsilonGreedy, LearningBudget, SolvedPolicy, SolverBudget, State,
 };
 
 #[test]
@@ -134,7 +137,7 @@ fn test_minimal_solver() {
 
     let grid = GridWorld::<N>::default();
     let start_state: GridState<'_, NoDiscountReward, N> = GridState::from_grid(&grid, (14, 14));
-    let results = simple_solver(start_state, StandardConvergenceMeasure);
+    let results = simple_solver(start_state, StandardConvergenceMeasure).into_inner();
 
     let mut grid = GridAction::iter()
         .map(|a| (a, [[0.; 15]; 15]))
@@ -142,7 +145,7 @@ fn test_minimal_solver() {
 
     for (s, map) in results.iter() {
         for (a, q) in map.iter() {
-            grid.get_mut(a).unwrap()[s.curr_square.1][s.curr_square.0] = q.0;
+            grid.get_mut(a).unwrap()[s.1][s.0] = q.0;
         }
     }
 
@@ -159,10 +162,10 @@ fn test_minimal_solver() {
         .into_iter()
         .flat_map(|(s, inner)| inner.into_iter().map(move |(a, q)| (s, a, q)))
         .fold(true, |acc, (s, a, q)| {
-            let outcome = if s.curr_square == (1, 0) && a == GridAction::North {
+            let outcome = if s == (1, 0) && a == GridAction::North {
                 (q.0 - (0.2 * 15.)).abs() < 0.04
-            } else if s.curr_square == (0, 0) // Terminal
-                || a.next_coords(&s.curr_square, 15).is_none()
+            } else if s == (0, 0) // Terminal
+                || a.next_coords(&s, 15).is_none()
             // On the edge
             {
                 q.0 == 0.0
@@ -192,7 +195,7 @@ fn test_discounted_solver() {
     grid[(X as usize, Y as usize)] = (REWARD, RandomTransition::None, true);
     let start_state: GridState<'_, DiscountedReward<80>, N> = GridState::from_grid(&grid, (14, 14));
 
-    let results = simple_solver(start_state, StandardConvergenceMeasure);
+    let results = simple_solver(start_state, StandardConvergenceMeasure).into_inner();
 
     /* prints (got, expected) for debugging */
     let mut grid = GridAction::iter()
@@ -201,12 +204,10 @@ fn test_discounted_solver() {
 
     for (s, map) in results.iter() {
         for (a, q) in map.iter() {
-            let next = a
-                .next_coords(&s.curr_square, 15)
-                .unwrap_or((X as usize, Y as usize));
+            let next = a.next_coords(s, 15).unwrap_or((X as usize, Y as usize));
             let manhattan_distance = (next.0 as i64 - X).abs() + (next.1 as i64 - Y).abs();
             let expected = (DISCOUNT as f64 / 100.).powi(manhattan_distance as i32) * REWARD as f64;
-            grid.get_mut(a).unwrap()[s.curr_square.1][s.curr_square.0] = (q.0, expected);
+            grid.get_mut(a).unwrap()[s.1][s.0] = (q.0, expected);
         }
     }
 
@@ -223,9 +224,9 @@ fn test_discounted_solver() {
         .into_iter()
         .flat_map(|(s, inner)| inner.into_iter().map(move |(a, q)| (s, a, q)))
         .fold(true, |acc, (s, a, q)| {
-            let appropriate_q = if s.curr_square == (X as usize, Y as usize) {
+            let appropriate_q = if s == (X as usize, Y as usize) {
                 0.0
-            } else if let Some(next) = a.next_coords(&s.curr_square, 15) {
+            } else if let Some(next) = a.next_coords(&s, 15) {
                 let manhattan_distance = (next.0 as i64 - X).abs() + (next.1 as i64 - Y).abs();
 
                 (DISCOUNT as f64 / 100.).powi(manhattan_distance as i32) * REWARD as f64
@@ -246,3 +247,284 @@ fn test_discounted_solver() {
 
     assert!(correct);
 }
+
+#[test]
+fn test_bounded_solver() {
+    const N: usize = 15;
+
+    let grid = GridWorld::<N>::default();
+    let start_state: GridState<'_, NoDiscountReward, N> = GridState::from_grid(&grid, (14, 14));
+
+    let budget = SolverBudget {
+        max_sweeps: Some(1),
+        ..Default::default()
+    };
+    let cut_short = bounded_solver(start_state, StandardConvergenceMeasure, budget);
+    assert!(!cut_short.converged);
+    assert_eq!(cut_short.sweeps, 1);
+    assert!(!cut_short.policy.into_inner().is_empty());
+
+    let unlimited = bounded_solver(
+        start_state,
+        StandardConvergenceMeasure,
+        SolverBudget::default(),
+    );
+    assert!(unlimited.converged);
+    assert!(unlimited.sweeps > 1);
+    assert_eq!(
+        unlimited.policy.into_inner(),
+        simple_solver(start_state, StandardConvergenceMeasure).into_inner()
+    );
+}
+
+#[test]
+fn test_skip_dead_states() {
+    const N: usize = 15;
+
+    let skip = SolverBudget {
+        skip_dead_states: true,
+        ..Default::default()
+    };
+
+    let grid = GridWorld::<N>::default();
+    let start_state: GridState<'_, NoDiscountReward, N> = GridState::from_grid(&grid, (14, 14));
+    assert_eq!(
+        bounded_solver(start_state, StandardConvergenceMeasure, skip)
+            .policy
+            .into_inner(),
+        simple_solver(start_state, StandardConvergenceMeasure).into_inner()
+    );
+
+    // Without any rewards, every state is dead
+    let mut grid = GridWorld::<N>::default();
+    grid[(0, 0)] = (0, RandomTransition::None, true);
+    grid[(0, 1)] = (0, RandomTransition::None, false);
+    let start_state: GridState<'_, NoDiscountReward, N> = GridState::from_grid(&grid, (14, 14));
+
+    assert!(
+        bounded_solver(start_state, StandardConvergenceMeasure, skip)
+            .policy
+            .into_inner()
+            .is_empty()
+    );
+    assert!(simple_solver(start_state, StandardConvergenceMeasure)
+        .into_inner()
+        .values()
+        .flat_map(|qs| qs.values())
+        .all(|q| q.0 == 0.));
+}
+
+#[test]
+fn test_cell_payloads() {
+    const N: usize = 5;
+
+    // Two exits along the top row, counting one and three events. Events aren't discounted, so
+    // the solver should always head for the bigger one no matter how far away it is
+    let mut grid = GridWorld::<N, u32>::empty();
+    grid[(0, 0)] = (1, RandomTransition::None, true);
+    grid[(4, 0)] = (3, RandomTransition::None, true);
+    let start_state: GridState<'_, CountReward, N, u32> = GridState::from_grid(&grid, (0, 1));
+
+    let results = simple_solver(start_state, StandardConvergenceMeasure);
+
+    let q = |square, action| *results.q(&square, &action).unwrap();
+    assert!(q((0, 1), GridAction::North).sem_eq(&CountQ(1.)));
+    assert!(q((0, 1), GridAction::East).sem_eq(&CountQ(3.)));
+    assert!(q((3, 0), GridAction::East).sem_eq(&CountQ(3.)));
+}
+
+#[test]
+fn test_solved_policy() {
+    const N: usize = 5;
+
+    // Right next to the smaller exit, but every other way leads to the bigger one eventually
+    let mut grid = GridWorld::<N, u32>::empty();
+    grid[(0, 0)] = (1, RandomTransition::None, true);
+    grid[(4, 0)] = (3, RandomTransition::None, true);
+    let start_state: GridState<'_, CountReward, N, u32> = GridState::from_grid(&grid, (1, 0));
+
+    // Results are keyed by the state's square
+    let start = start_state.curr_square;
+    let results = simple_solver(start_state, StandardConvergenceMeasure);
+    let best = results.best_action(&start).unwrap();
+    assert_ne!(best, &GridAction::West);
+    assert!(results.value(&start).unwrap().sem_eq(&CountQ(3.)));
+    assert!(results
+        .q(&start, &GridAction::West)
+        .unwrap()
+        .sem_eq(&CountQ(1.)));
+
+    let exit = (4, 0);
+    assert_eq!(results.best_action(&exit), None);
+
+    let policy = results.policy();
+    assert_eq!(policy[&start], best);
+    assert!(!policy.contains_key(&exit));
+}
+
+#[test]
+fn test_solved_policy_ties() {
+    // Ties go to the smallest action, no matter what order they were inserted in
+    for order in [
+        [GridAction::West, GridAction::East, GridAction::South],
+        [GridAction::South, GridAction::East, GridAction::West],
+        [GridAction::East, GridAction::West, GridAction::South],
+    ] {
+        let qs = order
+            .into_iter()
+            .map(|action| {
+                let q = if action == GridAction::West { 1. } else { 2. };
+                (action, SimpleQ(q))
+            })
+            .collect::<HashMap<_, _>>();
+        let results = SolvedPolicy::from(HashMap::from([((0, 0), qs)]));
+
+        assert_eq!(results.best_action(&(0, 0)), Some(&GridAction::South));
+        assert!(results.value(&(0, 0)).unwrap().sem_eq(&SimpleQ(2.)));
+    }
+}
+
+#[test]
+fn test_value_grid() {
+    const N: usize = 5;
+
+    let mut grid = GridWorld::<N, u32>::empty();
+    grid[(0, 0)] = (1, RandomTransition::None, true);
+    grid[(4, 0)] = (3, RandomTransition::None, true);
+    let start_state: GridState<'_, CountReward, N, u32> = GridState::from_grid(&grid, (0, 1));
+
+    let results = simple_solver(start_state, StandardConvergenceMeasure);
+    let values = visualize::value_grid(&results, N, N, |&s| Some(s));
+
+    assert_eq!(values.len(), N);
+    assert!(values.iter().all(|row| row.len() == N));
+
+    // The exits are terminal, so there's nothing to draw there
+    assert!(values[0][0].is_none());
+    assert!(values[0][4].is_none());
+
+    // Undiscounted, so everything else is worth heading to the bigger exit for
+    let (action, _) = values[0][1].unwrap();
+    assert_ne!(action, GridAction::West);
+    assert!(values
+        .iter()
+        .flatten()
+        .flatten()
+        .all(|(_, value)| value.sem_eq(&CountQ(3.))));
+
+    // Everything placed off the grid is skipped
+    let clipped = visualize::value_grid(&results, 2, 1, |&s| Some(s));
+    assert_eq!(clipped, [[None, values[0][1]]]);
+}
+
+/// A walk over a [`GridWorld`] without any random transitions, stepped through as a
+/// [`DeterministicState`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+struct GridWalk<'a>(GridState<'a, NoDiscountReward, 4>);
+
+impl<'a> DeterministicState<NoDiscountReward> for GridWalk<'a> {
+    type Action = GridAction;
+    type ActionIter = AvailableActions;
+    type Key = (usize, usize);
+
+    fn step(&self, action: GridAction) -> Option<(Self, NoDiscountReward)> {
+        let mut succ = State::<SimpleQ, _, SimpleQ>::successors(&self.0, action);
+        let (next, reward) = succ.next()?;
+        assert!(succ.next().is_none());
+
+        Some((GridWalk(next), reward))
+    }
+
+    fn actions(&self) -> AvailableActions {
+        State::<SimpleQ, NoDiscountReward, SimpleQ>::actions(&self.0)
+    }
+
+    fn key(&self) -> (usize, usize) {
+        self.0.curr_square
+    }
+}
+
+/// Converges after a fixed number of sweeps, regardless of the estimates.
+struct Sweeps(usize);
+
+impl<S: std::hash::Hash, A: std::hash::Hash, Q: crate::QVal> ConvergenceMeasure<S, A, Q>
+    for Sweeps
+{
+    fn converges(&mut self, _: &HashMap<S, HashMap<A, Q>>, _: &HashMap<S, HashMap<A, Q>>) -> bool {
+        self.0 = self.0.saturating_sub(1);
+        self.0 == 0
+    }
+}
+
+#[test]
+fn test_deterministic_adapter() {
+    let mut grid = GridWorld::<4>::empty();
+    grid[(0, 0)] = (15, RandomTransition::None, true);
+    grid[(3, 3)] = (-5, RandomTransition::None, true);
+
+    let start = GridState::from_grid(&grid, (2, 1));
+    let stochastic: SolvedPolicy<_, _, SimpleQ> = simple_solver(start, StandardConvergenceMeasure);
+
+    let walk = Deterministic(GridWalk(start));
+    assert_eq!(
+        State::<SimpleQ, NoDiscountReward, SimpleQ>::successors(&walk, GridAction::West)
+            .collect::<Vec<_>>(),
+        vec![(
+            Deterministic(GridWalk(GridState::from_grid(&grid, (1, 1)))),
+            NoDiscountReward(1.0, 0)
+        )]
+    );
+
+    let deterministic: SolvedPolicy<_, _, SimpleQ> = simple_solver(walk, Sweeps(20));
+    let deterministic = deterministic.into_inner();
+    assert_eq!(deterministic.len(), stochastic.clone().into_inner().len());
+
+    // Both are keyed by the square, so they line up directly
+    for (state, qs) in deterministic {
+        for (action, q) in qs {
+            assert_eq!(stochastic.q(&state, &action), Some(&q), "{:?}", state);
+        }
+    }
+}
+
+#[test]
+fn test_q_learning() {
+    const N: usize = 5;
+    const GOAL: (usize, usize) = (1, 2);
+
+    // Q-learning only sees one successor at a time, so nothing can be random
+    let mut grid = GridWorld::<N>::empty();
+    grid[GOAL] = (15, RandomTransition::None, true);
+
+    let start: GridState<'_, DiscountedReward<80>, N> = GridState::from_grid(&grid, (4, 4));
+    let exact = simple_solver(start, StandardConvergenceMeasure).into_inner();
+
+    let start: GridState<'_, ObservedReward<50, 80>, N> = GridState::from_grid(&grid, (4, 4));
+    let exploration = EpsilonGreedy {
+        epsilon: 0.5,
+        rng: StdRng::seed_from_u64(776),
+    };
+    let budget = LearningBudget {
+        episodes: 20000,
+        max_steps: Some(100),
+    };
+    let learned = q_learning(start, exploration, budget);
+
+    for (s, qs) in exact {
+        for (a, q) in qs {
+            let learned_q = learned.q(&s, &a).copied().unwrap_or_default();
+            assert!(
+                (learned_q.0 - q.0).abs() < 0.01,
+                "State: {:?}, Action: {:?}, learned {:?} but expected {:?}",
+                s,
+                a,
+                learned_q,
+                q
+            );
+        }
+    }
+
+    // Right next to the goal, and exactly one step further for each square away
+    assert_eq!(learned.best_action(&(1, 3)), Some(&GridAction::North));
+    assert!((learned.value(&start.curr_square).unwrap().0 - 15. * 0.8f64.powi(4)).abs() < 0.01);
+}
@@ -15,3 +15,36 @@ impl<const D: i64> SimpleReward for DiscountedReward<D> {
         Self(prob, val)
     }
 }
+
+/// Builds a [`TransitionReward`] for [`Q`](QVal) from the payload `C` of a
+/// [`GridWorld`](super::GridWorld) cell. Anything that's a [`SimpleReward`] can be built from the
+/// default scalar payload.
+pub trait CellReward<C, Q>: Sized + Compose<Q, Q> + Default + std::fmt::Debug {
+    /// Builds the reward for moving onto a cell holding `cell`, with probability `prob`.
+    fn from_prob_cell(prob: f64, cell: C) -> Self;
+}
+
+impl<R> CellReward<i64, SimpleQ> for R
+where
+    R: SimpleReward,
+{
+    fn from_prob_cell(prob: f64, cell: i64) -> Self {
+        Self::from_prob_val(prob, cell)
+    }
+}
+
+impl CellReward<u32, CountQ> for CountReward {
+    fn from_prob_cell(prob: f64, cell: u32) -> Self {
+        Self(prob, cell)
+    }
+}
+
+impl<const A: i64, const D: i64> CellReward<i64, LearnedQ<A>> for ObservedReward<A, D> {
+    fn from_prob_cell(prob: f64, cell: i64) -> Self {
+        debug_assert_eq!(
+            prob, 1.0,
+            "observed rewards can't be used on grids with random transitions"
+        );
+        Self(cell)
+    }
+}
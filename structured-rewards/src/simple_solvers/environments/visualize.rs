@@ -0,0 +1,46 @@
+//! Helpers for drawing a [`SolvedPolicy`] over a two dimensional state space, such as a
+//! [`GridWorld`](super::GridWorld), for teaching or debugging.
+
+use std::hash::Hash;
+
+use crate::{prelude::*, simple_solvers::SolvedPolicy};
+
+/// Lays the value function of `policy` out as a dense `height` by `width` grid, indexed by row
+/// (`y`) and then column (`x`). Each cell holds the best action of the state `coords` places there
+/// along with its estimate, or [`None`] if no non-terminal state was placed there.
+///
+/// States that `coords` returns [`None`] for, or places outside the grid, are skipped. If several
+/// states land on the same cell, the one with the best estimate is kept.
+pub fn value_grid<S, A, Q, F>(
+    policy: &SolvedPolicy<S, A, Q>,
+    width: usize,
+    height: usize,
+    mut coords: F,
+) -> Vec<Vec<Option<(A, Q)>>>
+where
+    S: Eq + Hash,
+    A: Ord + Hash + Clone,
+    Q: SemanticOrd + Clone,
+    F: FnMut(&S) -> Option<(usize, usize)>,
+{
+    let mut grid = vec![vec![None; width]; height];
+
+    for (state, action) in policy.policy() {
+        let Some((x, y)) = coords(state).filter(|&(x, y)| x < width && y < height) else {
+            continue;
+        };
+        let Some(value) = policy.value(state) else {
+            continue;
+        };
+
+        let cell: &mut Option<(A, Q)> = &mut grid[y][x];
+        if cell
+            .as_ref()
+            .is_none_or(|(_, best)| value.sem_cmp(best).is_gt())
+        {
+            *cell = Some((action.clone(), value.clone()));
+        }
+    }
+
+    grid
+}
@@ -0,0 +1,139 @@
+//! A very basic implementation of Structural Q-learning as [`q_learning`], which learns from
+//! single observed transitions rather than needing every successor and its probability up front.
+
+use std::{collections::HashMap, hash::Hash};
+
+use rand::Rng;
+
+use crate::{
+    prelude::*,
+    simple_solvers::{
+        get_best_action, get_state_action, get_state_action_mut, SolvedPolicy, State,
+    },
+};
+
+/// Decides which action [`q_learning`] takes next, given the current estimate of every action
+/// valid in the current state. Actions that have never been taken are estimated at the
+/// [`Default`] of the [`QVal`].
+pub trait ExplorationPolicy<A, Q> {
+    /// Picks one of the `estimates`' actions. This is never called with no estimates.
+    fn choose(&mut self, estimates: &[(A, &Q)]) -> A;
+}
+
+/// The classic exploration policy, which takes a uniformly random action with probability
+/// `epsilon`, and otherwise the action with the best estimate according to [`SemanticOrd`].
+#[derive(Clone, Debug)]
+pub struct EpsilonGreedy<G> {
+    /// How often to explore, from `0.0` (never) to `1.0` (always).
+    pub epsilon: f64,
+
+    /// The source of randomness, seed it for reproducible runs.
+    pub rng: G,
+}
+
+impl<A, Q, G> ExplorationPolicy<A, Q> for EpsilonGreedy<G>
+where
+    A: Copy,
+    Q: SemanticOrd,
+    G: Rng,
+{
+    fn choose(&mut self, estimates: &[(A, &Q)]) -> A {
+        if self.rng.gen_bool(self.epsilon) {
+            estimates[self.rng.gen_range(0..estimates.len())].0
+        } else {
+            estimates
+                .iter()
+                .max_by(|(_, a), (_, b)| a.sem_cmp(b))
+                .unwrap()
+                .0
+        }
+    }
+}
+
+/// How long [`q_learning`] trains for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LearningBudget {
+    /// The number of episodes to run, each starting from the start state and running until it
+    /// reaches a terminal state.
+    pub episodes: usize,
+
+    /// The maximum number of actions to take in a single episode, in case the policy wanders in
+    /// circles. If [`None`], episodes only end at terminal states.
+    pub max_steps: Option<usize>,
+}
+
+/// An extremely simple implementation of Structural Q-learning, the learning counterpart to
+/// [`simple_solver`](super::simple_solver). After every action it performs the update
+///
+/// ```notrust
+/// Q(s,a) = Q(s,a) ⊕ [r ∘ max_a' Q(s',a')]
+/// ```
+///
+/// Where `r` is the observed [`TransitionReward`], [`.compose`](Compose::compose)d with the
+/// best estimate for the next state, and `⊕` is [`update`](Bellman::update). Unvisited `Q(s,a)`
+/// start at the [`Default::default`] of [`QVal`].
+///
+/// There's no scalar learning rate, since structured Q-values can't generally be scaled. Instead
+/// the learning rate is part of the transformation cycle: [`compose`](Compose::compose) should
+/// weigh the observation by it and [`update`](Bellman::update) should keep the rest of the
+/// current estimate, as [`ObservedReward`](crate::rewards::ObservedReward) and
+/// [`LearnedQ`](crate::rewards::LearnedQ) do.
+///
+/// Every transition is a single sample, so [`successors`](State::successors) should yield at
+/// most one element, as described on [`State`]. If it yields none, the episode ends.
+///
+/// As with the value iteration solver, this is **not** recommended for serious work.
+pub fn q_learning<S, R, Q, E>(
+    start: S,
+    mut exploration: E,
+    budget: LearningBudget,
+) -> SolvedPolicy<S::Key, S::Action, Q>
+where
+    S: State<Q, R, Q> + Clone,
+    R: TransitionReward<Q, Q>,
+    S::Action: Eq + Hash + Copy,
+    Q: TotalQ,
+    E: ExplorationPolicy<S::Action, Q>,
+{
+    let mut qs: HashMap<S::Key, HashMap<S::Action, Q>> = HashMap::new();
+    let unvisited = Q::default();
+
+    for _ in 0..budget.episodes {
+        let mut state = start.clone();
+        let mut steps = 0;
+
+        while budget.max_steps.is_none_or(|max| steps < max) {
+            let key = state.key();
+            let estimates = state
+                .actions()
+                .map(|action| {
+                    let q = get_state_action(&key, action, &qs).unwrap_or(&unvisited);
+                    (action, q)
+                })
+                .collect::<Vec<_>>();
+            if estimates.is_empty() {
+                break;
+            }
+            let action = exploration.choose(&estimates);
+
+            let mut successors = state.successors(action);
+            let Some((succ, reward)) = successors.next() else {
+                break;
+            };
+            debug_assert!(
+                successors.next().is_none(),
+                "Q-learning needs a single observed successor per transition"
+            );
+
+            let sample = reward
+                .compose(get_best_action(&succ.key(), succ.actions(), &qs).unwrap_or(&unvisited));
+            let q = get_state_action_mut(key, action, &mut qs);
+            *q = q.update(&sample);
+
+            state = succ;
+            steps += 1;
+        }
+    }
+
+    qs.into()
+}
@@ -1,19 +1,26 @@
 //! This contains a very basic implementation of Structural Value Iteration as [`simple_solver`],
-//! as well as a few traits necessary to get a basic environment together to run it.
+//! and of Structural Q-learning as [`q_learning`], as well as a few traits necessary to get a
+//! basic environment together to run them.
 //!
 //! It is not recommended to use the implementation in this crate for serious work, only as
 //! a quick and dirty testing ground for ideas.
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, HashMap, HashSet},
     hash::Hash,
     mem,
+    time::{Duration, Instant},
 };
 
 use crate::{prelude::*, Sem};
 
+pub mod deterministic;
 #[cfg(feature = "environments")]
 pub mod environments;
+pub mod learning;
+
+pub use deterministic::{Deterministic, DeterministicState};
+pub use learning::{q_learning, EpsilonGreedy, ExplorationPolicy, LearningBudget};
 
 /// Defines the current place the agent/solver is evaluating. The general loop is that
 /// [`actions`](State::actions) will provide an iterator over anything that will yield
@@ -36,6 +43,13 @@ pub trait State<Q: QVal, R: TransitionReward<Q, P>, P: PartialQ<Q>>: Sized {
     /// An iterator over [`Self::Action`](State::Action), listing all
     /// the ones valid in the current state.
     type ActionIter: Iterator<Item = Self::Action>;
+    /// A cheap, hashable stand-in for this state used by solvers to track which states they've
+    /// already visited, and to store their Q-values by, so they don't need to keep clones of large
+    /// states around. Two states must have the same key if and only if they're equal.
+    ///
+    /// If your state is already small (or `Copy`), this can simply be `Self`. Sadly associated type
+    /// defaults are unstable, so this has to be stated explicitly.
+    type Key: Eq + Hash;
 
     /// Yields all (s',R(s,a,s')) from this state, or a single-element iterator
     /// with an observation, depending on the intended use for this [`State`].
@@ -43,6 +57,9 @@ pub trait State<Q: QVal, R: TransitionReward<Q, P>, P: PartialQ<Q>>: Sized {
 
     /// Yields an iterator over all valid actions for the current state.
     fn actions(&self) -> Self::ActionIter;
+
+    /// Computes the [`Key`](State::Key) for this state.
+    fn key(&self) -> Self::Key;
 }
 
 /// Determines if the Q-value estimate has converged in Value Iteration, generally
@@ -53,11 +70,15 @@ pub trait State<Q: QVal, R: TransitionReward<Q, P>, P: PartialQ<Q>>: Sized {
 /// However, if desired you could also ignore it entirely and
 /// simply keep track of the number of calls and cut off after a given number, for
 /// instance.
-pub trait ConvergenceMeasure<S: Hash, A: Hash, Q: QVal> {
+///
+/// The estimates are stored by the [`Key`](State::Key) of each state, which is `K`.
+pub trait ConvergenceMeasure<K: Hash, A: Hash, Q: QVal> {
+    /// Returns `true` once the Q-values have converged, given the estimates from the
+    /// previous sweep (`old`) and the one that just finished (`curr`).
     fn converges(
         &mut self,
-        old: &HashMap<S, HashMap<A, Q>>,
-        curr: &HashMap<S, HashMap<A, Q>>,
+        old: &HashMap<K, HashMap<A, Q>>,
+        curr: &HashMap<K, HashMap<A, Q>>,
     ) -> bool;
 }
 
@@ -89,44 +110,189 @@ pub trait ConvergenceMeasure<S: Hash, A: Hash, Q: QVal> {
 /// be as general as possible.
 ///
 /// [`BTreeMap`]: std::collections::BTreeMap
-pub fn simple_solver<S, R, Q, P, C>(start: S, mut measure: C) -> HashMap<S, HashMap<S::Action, Q>>
+pub fn simple_solver<S, R, Q, P, C>(start: S, measure: C) -> SolvedPolicy<S::Key, S::Action, Q>
+where
+    S: State<Q, R, P> + Sized + Clone + std::fmt::Debug,
+    R: TransitionReward<Q, P> + std::fmt::Debug,
+    P: PartialQ<Q> + Default + std::fmt::Debug,
+    S::Action: Eq + Hash + Sized + Clone + Copy + std::fmt::Debug,
+    Q: QVal + Sized + std::fmt::Debug,
+    C: ConvergenceMeasure<S::Key, S::Action, Q>,
+{
+    bounded_solver(start, measure, SolverBudget::default()).policy
+}
+
+/// The Q-values computed by [`simple_solver`] or [`q_learning`], along with the greedy policy they imply. Where
+/// several actions are equally good in a state, the smallest one by its [`Ord`] is considered best, so the
+/// policy doesn't depend on the order the Q-values happen to be stored in.
+///
+/// States are looked up by their [`Key`](State::Key), which is `K`. For states that are their own
+/// key this is just the state.
+#[derive(Clone, Debug)]
+pub struct SolvedPolicy<K, A, Q> {
+    qs: HashMap<K, HashMap<A, Q>>,
+}
+
+impl<K, A, Q> SolvedPolicy<K, A, Q>
+where
+    K: Eq + Hash,
+    A: Ord + Hash,
+    Q: SemanticOrd,
+{
+    /// The estimate for taking `action` in the state with the given key, if it was ever visited.
+    pub fn q(&self, state: &K, action: &A) -> Option<&Q> {
+        self.qs.get(state)?.get(action)
+    }
+
+    /// The action with the best estimate in the state with the given key, or `None` if it wasn't
+    /// visited or is terminal. Ties go to the smallest action.
+    pub fn best_action(&self, state: &K) -> Option<&A> {
+        self.best(state).map(|(action, _)| action)
+    }
+
+    /// The estimate of a state itself, i.e. of its [`best_action`](SolvedPolicy::best_action).
+    pub fn value(&self, state: &K) -> Option<&Q> {
+        self.best(state).map(|(_, q)| q)
+    }
+
+    /// The [`best_action`](SolvedPolicy::best_action) of every non-terminal state that was visited,
+    /// by key.
+    pub fn policy(&self) -> HashMap<&K, &A> {
+        self.qs
+            .keys()
+            .filter_map(|state| Some((state, self.best_action(state)?)))
+            .collect()
+    }
+
+    /// The raw Q-values, by state key and then by action.
+    pub fn into_inner(self) -> HashMap<K, HashMap<A, Q>> {
+        self.qs
+    }
+
+    fn best(&self, state: &K) -> Option<(&A, &Q)> {
+        self.qs
+            .get(state)?
+            .iter()
+            .max_by(|(a, q_a), (b, q_b)| q_a.sem_cmp(q_b).then_with(|| b.cmp(a)))
+    }
+}
+
+impl<K, A, Q> From<HashMap<K, HashMap<A, Q>>> for SolvedPolicy<K, A, Q> {
+    fn from(qs: HashMap<K, HashMap<A, Q>>) -> Self {
+        Self { qs }
+    }
+}
+
+/// Limits on how long [`bounded_solver`] may run before giving up on convergence, and how much of
+/// the state space it bothers with. Any limit left as [`None`] isn't enforced, so the [`Default`]
+/// budget is unlimited and explores everything.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SolverBudget {
+    /// The maximum number of sweeps over the state space to perform.
+    pub max_sweeps: Option<usize>,
+
+    /// The maximum wall-clock time to spend solving. This is only checked between sweeps, so the
+    /// solver may run over by up to the length of one sweep.
+    pub timeout: Option<Duration>,
+
+    /// Whether to skip "dead" states, which can never reach a transition with a reward. These are
+    /// found by a pre-pass over everything reachable from the start, after which they're left out
+    /// of every sweep (and so out of the returned Q-values, treat missing entries as the
+    /// [`Default`] of the [`QVal`]).
+    ///
+    /// This doesn't change the solution, as long as composing a [`TransitionReward`] that's
+    /// worth nothing with a [`Default`] estimate yields a [`Default`] estimate. In resource-bound
+    /// domains (where running out of e.g. time or durability leaves nothing left to gain) it can
+    /// save a lot of work, but in domains where everything can reach a reward the pre-pass is
+    /// pure overhead.
+    pub skip_dead_states: bool,
+}
+
+/// The Q-values computed by [`bounded_solver`], along with whether they actually converged.
+#[derive(Clone, Debug)]
+pub struct BoundedSolution<K, A, Q> {
+    /// The Q-values from the last sweep that was completed, as [`simple_solver`] would return them.
+    pub policy: SolvedPolicy<K, A, Q>,
+
+    /// Whether the [`ConvergenceMeasure`] was satisfied, rather than the budget running out.
+    pub converged: bool,
+
+    /// How many sweeps were performed.
+    pub sweeps: usize,
+}
+
+/// [`simple_solver`], but stopping early if it exceeds the given `budget`. This is useful for
+/// interactive tools which need an answer quickly, even if it's only an approximate one.
+///
+/// If the budget runs out, the Q-values are the best estimate so far and `converged` will be
+/// `false`. Be aware that a policy derived from an unconverged estimate may be suboptimal, and
+/// may not even reach the end of the problem: states deep in the search may never have had any
+/// reward propagated back to them.
+pub fn bounded_solver<S, R, Q, P, C>(
+    start: S,
+    mut measure: C,
+    budget: SolverBudget,
+) -> BoundedSolution<S::Key, S::Action, Q>
 where
-    S: State<Q, R, P> + Eq + Hash + Sized + Clone + std::fmt::Debug,
+    S: State<Q, R, P> + Sized + Clone + std::fmt::Debug,
     R: TransitionReward<Q, P> + std::fmt::Debug,
     P: PartialQ<Q> + Default + std::fmt::Debug,
     S::Action: Eq + Hash + Sized + Clone + Copy + std::fmt::Debug,
     Q: QVal + Sized + std::fmt::Debug,
-    C: ConvergenceMeasure<S, S::Action, Q>,
+    C: ConvergenceMeasure<S::Key, S::Action, Q>,
 {
+    let started = Instant::now();
+    let mut sweeps = 0;
+    let live = budget.skip_dead_states.then(|| live_states(&start));
+
     let mut seen = HashSet::new();
-    seen.insert(start.clone());
+    seen.insert(start.key());
     let mut next_qs = HashMap::new();
     let mut stationary_qs = HashMap::new();
 
     loop {
         let mut stack = vec![start.clone()];
         while let Some(state) = stack.pop() {
+            // Successors of a dead state are dead too, so there's no need to go any further
+            if live
+                .as_ref()
+                .is_some_and(|live| !live.contains(&state.key()))
+            {
+                continue;
+            }
+
             for action in state.actions() {
                 let mut tree = P::default();
                 for (succ, reward) in state.successors(action) {
                     let future_estimate = reward.compose(
-                        get_best_action(&succ, succ.actions(), &stationary_qs)
+                        get_best_action(&succ.key(), succ.actions(), &stationary_qs)
                             .unwrap_or(&Q::default()),
                     );
 
                     tree = tree.partial_update(&future_estimate);
-                    if !seen.contains(&succ) {
-                        seen.insert(succ.clone());
+                    if seen.insert(succ.key()) {
                         stack.push(succ);
                     }
                 }
 
-                *get_state_action_mut(&state, action, &mut next_qs) = tree.reweight();
+                *get_state_action_mut(state.key(), action, &mut next_qs) = tree.reweight();
             }
         }
 
-        if measure.converges(&stationary_qs, &next_qs) {
-            break next_qs;
+        sweeps += 1;
+
+        let converged = measure.converges(&stationary_qs, &next_qs);
+        let out_of_budget = budget.max_sweeps.is_some_and(|max| sweeps >= max)
+            || budget
+                .timeout
+                .is_some_and(|timeout| started.elapsed() >= timeout);
+
+        if converged || out_of_budget {
+            break BoundedSolution {
+                policy: next_qs.into(),
+                converged,
+                sweeps,
+            };
         } else {
             mem::swap(&mut next_qs, &mut stationary_qs);
             seen.clear();
@@ -134,23 +300,73 @@ where
     }
 }
 
-fn get_state_action_mut<'a, K1: Eq + Hash + Clone, K2: Eq + Hash + Clone + Copy, V: Default>(
-    state: &K1,
-    action: K2,
-    map: &'a mut HashMap<K1, HashMap<K2, V>>,
-) -> &'a mut V {
-    // Can't do `if let` cuz the borrow extends into the else :(
-    if map.contains_key(state) {
-        map.get_mut(state).unwrap().entry(action)
-    } else {
-        map.entry(state.clone())
-            .or_insert_with(HashMap::new)
-            .entry(action)
+/// Finds the keys of every state reachable from `start` that can reach a transition with a reward,
+/// by walking backwards from the transitions whose reward is worth something on its own.
+fn live_states<S, R, Q, P>(start: &S) -> HashSet<S::Key>
+where
+    S: State<Q, R, P> + Clone,
+    R: TransitionReward<Q, P>,
+    P: PartialQ<Q> + Default,
+    Q: QVal,
+{
+    let worthless = Q::default();
+
+    // States are numbered in the order they're found, since keys can't be cloned
+    let mut ids = HashMap::new();
+    ids.insert(start.key(), 0);
+    let mut predecessors: Vec<Vec<usize>> = vec![vec![]];
+    let mut live = vec![false];
+
+    let mut stack = vec![(start.clone(), 0)];
+    while let Some((state, id)) = stack.pop() {
+        for action in state.actions() {
+            for (succ, reward) in state.successors(action) {
+                let value = P::default()
+                    .partial_update(&reward.compose(&worthless))
+                    .reweight();
+                live[id] |= !value.sem_eq(&worthless);
+
+                let succ_id = match ids.entry(succ.key()) {
+                    Entry::Occupied(entry) => *entry.get(),
+                    Entry::Vacant(entry) => {
+                        let succ_id = predecessors.len();
+                        entry.insert(succ_id);
+                        predecessors.push(vec![]);
+                        live.push(false);
+                        stack.push((succ, succ_id));
+                        succ_id
+                    }
+                };
+                predecessors[succ_id].push(id);
+            }
+        }
     }
-    .or_default()
+
+    let mut frontier = (0..live.len()).filter(|&id| live[id]).collect::<Vec<_>>();
+    while let Some(id) = frontier.pop() {
+        for &pred in &predecessors[id] {
+            if !live[pred] {
+                live[pred] = true;
+                frontier.push(pred);
+            }
+        }
+    }
+
+    ids.into_iter()
+        .filter(|&(_, id)| live[id])
+        .map(|(key, _)| key)
+        .collect()
+}
+
+fn get_state_action_mut<K1: Eq + Hash, K2: Eq + Hash + Clone + Copy, V: Default>(
+    state: K1,
+    action: K2,
+    map: &mut HashMap<K1, HashMap<K2, V>>,
+) -> &mut V {
+    map.entry(state).or_default().entry(action).or_default()
 }
 
-fn get_state_action<'a, K1: Eq + Hash + Clone, K2: Eq + Hash + Clone + Copy, V: Default>(
+fn get_state_action<'a, K1: Eq + Hash, K2: Eq + Hash + Clone + Copy, V: Default>(
     state: &K1,
     action: K2,
     map: &'a HashMap<K1, HashMap<K2, V>>,
@@ -165,7 +381,7 @@ fn get_state_action<'a, K1: Eq + Hash + Clone, K2: Eq + Hash + Clone + Copy, V:
 
 fn get_best_action<
     'a,
-    K1: Eq + Hash + Clone,
+    K1: Eq + Hash,
     K2: Eq + Hash + Clone + Copy,
     I: Iterator<Item = K2>,
     V: Default + SemanticOrd + SemanticEq,
@@ -0,0 +1,281 @@
+#![doc = include_str!("../README.md")]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Error, Fields, Lit,
+    Member, Meta, NestedMeta, Path,
+};
+
+#[proc_macro_derive(SemanticEq, attributes(sem))]
+pub fn semantic_eq_derive(input: TokenStream) -> TokenStream {
+    derive(
+        input,
+        parse_quote!(::structured_rewards::SemanticEq),
+        semantic_eq,
+    )
+}
+
+#[proc_macro_derive(SemanticOrd, attributes(sem))]
+pub fn semantic_ord_derive(input: TokenStream) -> TokenStream {
+    derive(
+        input,
+        parse_quote!(::structured_rewards::SemanticOrd),
+        semantic_ord,
+    )
+}
+
+fn derive(
+    input: TokenStream,
+    bound: Path,
+    body: fn(&DeriveInput) -> syn::Result<TokenStream2>,
+) -> TokenStream {
+    let mut ast = parse_macro_input!(input as DeriveInput);
+
+    let body = match body(&ast) {
+        Ok(body) => body,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let params = ast
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    let where_clause = ast.generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics #bound for #ident #ty_generics #where_clause {
+            #body
+        }
+    }
+    .into()
+}
+
+/// A field to compare, along with the options from its `#[sem(...)]` attribute.
+struct SemField {
+    member: Member,
+    minimize: bool,
+    priority: i64,
+}
+
+impl SemField {
+    /// The name the field is bound to when matching on `self` (`prefix` "a") or `other` ("b").
+    fn binding(&self, prefix: &str) -> syn::Ident {
+        match &self.member {
+            Member::Named(ident) => format_ident!("{}_{}", prefix, ident),
+            Member::Unnamed(index) => format_ident!("{}_{}", prefix, index.index),
+        }
+    }
+}
+
+/// Reads the fields, in the order they should be compared.
+fn sem_fields(fields: &Fields) -> syn::Result<Vec<SemField>> {
+    let mut out = vec![];
+
+    for (i, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        };
+        let mut sem = SemField {
+            member,
+            minimize: false,
+            priority: 0,
+        };
+
+        for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("sem")) {
+            let Meta::List(list) = attr.parse_meta()? else {
+                return Err(Error::new(attr.span(), "expected #[sem(...)]"));
+            };
+
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("minimize") => {
+                        sem.minimize = true
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("maximize") => {
+                        sem.minimize = false
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("priority") => {
+                        let Lit::Int(priority) = &nv.lit else {
+                            return Err(Error::new(nv.lit.span(), "expected an integer priority"));
+                        };
+                        sem.priority = priority.base10_parse()?;
+                    }
+                    other => {
+                        return Err(Error::new(
+                            other.span(),
+                            "expected `minimize`, `maximize`, or `priority = N`",
+                        ))
+                    }
+                }
+            }
+        }
+
+        out.push(sem);
+    }
+
+    // Stable, so ties stay in declaration order
+    out.sort_by_key(|field| std::cmp::Reverse(field.priority));
+    Ok(out)
+}
+
+/// Binds every field of a variant, e.g. `Self::Variant { x: a_x, .. }`.
+fn variant_pattern(variant: &syn::Variant, fields: &[SemField], prefix: &str) -> TokenStream2 {
+    let ident = &variant.ident;
+    let members = fields.iter().map(|field| &field.member);
+    let bindings = fields.iter().map(|field| field.binding(prefix));
+
+    quote!(Self::#ident { #(#members: #bindings,)* .. })
+}
+
+fn semantic_eq(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let eq_all = |fields: &[SemField]| {
+        let (a, b) = (
+            fields.iter().map(|field| field.binding("a")),
+            fields.iter().map(|field| field.binding("b")),
+        );
+        quote!(true #(&& ::structured_rewards::SemanticEq::sem_eq(#a, #b))*)
+    };
+
+    let body = match &ast.data {
+        Data::Struct(data) => {
+            let fields = sem_fields(&data.fields)?;
+            let (a, b) = (
+                fields.iter().map(|field| field.binding("a")),
+                fields.iter().map(|field| field.binding("b")),
+            );
+            let members = fields.iter().map(|field| &field.member).collect::<Vec<_>>();
+            let eq = eq_all(&fields);
+
+            quote! {
+                let Self { #(#members: #a,)* .. } = self;
+                let Self { #(#members: #b,)* .. } = other;
+                #eq
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let fields = sem_fields(&variant.fields)?;
+                    let (a, b) = (
+                        variant_pattern(variant, &fields, "a"),
+                        variant_pattern(variant, &fields, "b"),
+                    );
+                    let eq = eq_all(&fields);
+                    Ok(quote!((#a, #b) => #eq,))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                match (self, other) {
+                    #(#arms)*
+                    _ => false,
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "SemanticEq can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        #[allow(unreachable_patterns)]
+        fn sem_eq(&self, other: &Self) -> bool {
+            #body
+        }
+    })
+}
+
+fn semantic_ord(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let cmp_all = |fields: &[SemField]| {
+        let cmps = fields.iter().map(|field| {
+            let (a, b) = (field.binding("a"), field.binding("b"));
+            if field.minimize {
+                quote!(::structured_rewards::SemanticOrd::sem_cmp(#b, #a))
+            } else {
+                quote!(::structured_rewards::SemanticOrd::sem_cmp(#a, #b))
+            }
+        });
+        quote!(::std::cmp::Ordering::Equal #(.then_with(|| #cmps))*)
+    };
+
+    let body = match &ast.data {
+        Data::Struct(data) => {
+            let fields = sem_fields(&data.fields)?;
+            let (a, b) = (
+                fields.iter().map(|field| field.binding("a")),
+                fields.iter().map(|field| field.binding("b")),
+            );
+            let members = fields.iter().map(|field| &field.member).collect::<Vec<_>>();
+            let cmp = cmp_all(&fields);
+
+            quote! {
+                let Self { #(#members: #a,)* .. } = self;
+                let Self { #(#members: #b,)* .. } = other;
+                #cmp
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let fields = sem_fields(&variant.fields)?;
+                    let (a, b) = (
+                        variant_pattern(variant, &fields, "a"),
+                        variant_pattern(variant, &fields, "b"),
+                    );
+                    let cmp = cmp_all(&fields);
+                    Ok(quote!((#a, #b) => #cmp,))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            let indices = data.variants.iter().enumerate().map(|(i, variant)| {
+                let ident = &variant.ident;
+                quote!(Self::#ident { .. } => #i,)
+            });
+
+            quote! {
+                let index = |value: &Self| -> usize {
+                    match value {
+                        #(#indices)*
+                    }
+                };
+
+                #[allow(unreachable_patterns)]
+                match (self, other) {
+                    #(#arms)*
+                    _ => index(self).cmp(&index(other)),
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "SemanticOrd can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        #[allow(unreachable_patterns)]
+        fn sem_cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+            #body
+        }
+    })
+}
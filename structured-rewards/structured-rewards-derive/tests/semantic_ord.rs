@@ -0,0 +1,84 @@
+//! Derives semantic comparisons for made up Q-values outside of `structured-rewards`, to make sure
+//! the derives only refer to things downstream crates can reach.
+
+use std::cmp::Ordering;
+
+use structured_rewards::{
+    rewards::{CountQ, SimpleQ},
+    SemanticEq, SemanticOrd,
+};
+
+/// Prefers higher quality, breaking ties by taking fewer steps.
+#[derive(Clone, Copy, Debug, SemanticEq, SemanticOrd)]
+struct QualityThenSteps {
+    quality: SimpleQ,
+    #[sem(minimize)]
+    steps: CountQ,
+}
+
+/// Prefers fewer steps, and only then higher quality.
+#[derive(Clone, Copy, Debug, SemanticEq, SemanticOrd)]
+struct StepsThenQuality(SimpleQ, #[sem(minimize, priority = 1)] CountQ);
+
+#[derive(Clone, Copy, Debug, SemanticEq, SemanticOrd)]
+enum Outcome<Q> {
+    Failed,
+    Completed { value: Q },
+}
+
+#[test]
+fn lexicographic_over_fields() {
+    let a = QualityThenSteps {
+        quality: SimpleQ(10.),
+        steps: CountQ(5.),
+    };
+    let b = QualityThenSteps {
+        quality: SimpleQ(10.),
+        steps: CountQ(3.),
+    };
+    let c = QualityThenSteps {
+        quality: SimpleQ(20.),
+        steps: CountQ(9.),
+    };
+
+    assert_eq!(a.sem_cmp(&b), Ordering::Less);
+    assert_eq!(c.sem_cmp(&b), Ordering::Greater);
+    assert_eq!(a.sem_cmp(&a), Ordering::Equal);
+    assert!(a.sem_eq(&a));
+    assert!(!a.sem_eq(&b));
+    assert_eq!(
+        [a, b, c]
+            .into_iter()
+            .map(SemanticEq::sem)
+            .max()
+            .unwrap()
+            .0
+            .quality
+            .0,
+        20.
+    );
+}
+
+#[test]
+fn priority_reorders_fields() {
+    let a = StepsThenQuality(SimpleQ(10.), CountQ(3.));
+    let b = StepsThenQuality(SimpleQ(20.), CountQ(9.));
+
+    assert_eq!(a.sem_cmp(&b), Ordering::Greater);
+    assert_eq!(
+        StepsThenQuality(SimpleQ(20.), CountQ(3.)).sem_cmp(&a),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn enums_order_by_variant_first() {
+    let failed = Outcome::<SimpleQ>::Failed;
+    let low = Outcome::Completed { value: SimpleQ(1.) };
+    let high = Outcome::Completed { value: SimpleQ(2.) };
+
+    assert_eq!(failed.sem_cmp(&low), Ordering::Less);
+    assert_eq!(high.sem_cmp(&low), Ordering::Greater);
+    assert!(failed.sem_eq(&Outcome::Failed));
+    assert!(!failed.sem_eq(&low));
+}